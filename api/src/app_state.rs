@@ -1,4 +1,4 @@
-use crate::services::{AuthService, EmailService};
+use crate::services::{AuthService, EmailService, OAuthService};
 use crate::{config, db};
 use std::sync::Arc;
 
@@ -7,5 +7,6 @@ pub struct AppState {
     pub db: db::Database,
     pub auth_service: Arc<AuthService>,
     pub email_service: Arc<EmailService>,
+    pub oauth_service: Arc<OAuthService>,
     pub config: Arc<config::Config>,
 }
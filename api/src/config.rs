@@ -8,6 +8,7 @@ pub struct Config {
     pub jwt: JwtConfig,
     pub oauth: OAuthConfig,
     pub email: EmailConfig,
+    pub two_factor: TwoFactorConfig,
     pub app: AppConfig,
     pub admin: UserConfig,
     pub bob: UserConfig,
@@ -38,7 +39,8 @@ pub struct DatabaseConfig {
 #[derive(Debug, Clone, Deserialize)]
 pub struct JwtConfig {
     pub secret: String,
-    pub expiration: i64, // in seconds
+    pub expiration: i64,         // access token lifetime, in seconds
+    pub refresh_expiration: i64, // idle lifetime of a session, in seconds
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -46,10 +48,26 @@ pub struct OAuthConfig {
     pub google_client_id: String,
     pub google_client_secret: String,
     pub google_redirect_uri: String,
+    pub github_client_id: String,
+    pub github_client_secret: String,
+    pub github_redirect_uri: String,
+    pub oidc: OidcConfig,
     pub cookie_secure: bool,
     pub state_ttl_seconds: i64,
 }
 
+/// A generic OpenID Connect issuer, configured by discovery from its issuer URL
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcConfig {
+    pub issuer: String,
+    pub name: String, // Shown on the login button
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub trust_email: bool, // Whether a verified email may sign in to an existing account
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct EmailConfig {
     pub smtp_host: String,
@@ -59,6 +77,15 @@ pub struct EmailConfig {
     pub from_address: String,
     pub frontend_url: String,
     pub smtp_use_tls: bool,
+    pub verification_ttl_seconds: i64, // How long a verification link works
+    pub verification_resend_seconds: i64, // Minimum wait between verification emails
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TwoFactorConfig {
+    pub issuer: String,             // Account label shown in authenticator apps
+    pub require_for_admins: bool,   // Admin routes need a session started with a second factor
+    pub challenge_ttl_seconds: i64, // How long a password login waits for its code
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -89,9 +116,13 @@ impl Config {
             jwt: JwtConfig {
                 secret: env::var("JWT_SECRET").unwrap_or_else(|_| "supersecret".to_string()),
                 expiration: env::var("JWT_EXPIRATION")
-                    .unwrap_or_else(|_| "86400".to_string())
+                    .unwrap_or_else(|_| "900".to_string())
                     .parse()
-                    .unwrap_or(86400),
+                    .unwrap_or(900),
+                refresh_expiration: env::var("REFRESH_TOKEN_EXPIRATION")
+                    .unwrap_or_else(|_| "2592000".to_string())
+                    .parse()
+                    .unwrap_or(2592000),
             },
             oauth: OAuthConfig {
                 google_client_id: env::var("GOOGLE_CLIENT_ID").unwrap_or_else(|_| "".to_string()),
@@ -100,6 +131,31 @@ impl Config {
                 google_redirect_uri: env::var("GOOGLE_REDIRECT_URI").unwrap_or_else(|_| {
                     "http://localhost:8080/api/auth/google/callback".to_string()
                 }),
+                github_client_id: env::var("GITHUB_CLIENT_ID").unwrap_or_else(|_| "".to_string()),
+                github_client_secret: env::var("GITHUB_CLIENT_SECRET")
+                    .unwrap_or_else(|_| "".to_string()),
+                github_redirect_uri: env::var("GITHUB_REDIRECT_URI").unwrap_or_else(|_| {
+                    "http://localhost:8080/api/auth/github/callback".to_string()
+                }),
+                oidc: OidcConfig {
+                    issuer: env::var("OIDC_ISSUER").unwrap_or_else(|_| "".to_string()),
+                    name: env::var("OIDC_NAME").unwrap_or_else(|_| "Single Sign-On".to_string()),
+                    client_id: env::var("OIDC_CLIENT_ID").unwrap_or_else(|_| "".to_string()),
+                    client_secret: env::var("OIDC_CLIENT_SECRET")
+                        .unwrap_or_else(|_| "".to_string()),
+                    redirect_uri: env::var("OIDC_REDIRECT_URI").unwrap_or_else(|_| {
+                        "http://localhost:8080/api/auth/oidc/callback".to_string()
+                    }),
+                    scopes: env::var("OIDC_SCOPES")
+                        .unwrap_or_else(|_| "openid email profile".to_string())
+                        .split_whitespace()
+                        .map(str::to_string)
+                        .collect(),
+                    trust_email: env::var("OIDC_TRUST_EMAIL")
+                        .unwrap_or_else(|_| "false".to_string())
+                        .parse()
+                        .unwrap_or(false),
+                },
                 cookie_secure: env::var("OAUTH_COOKIE_SECURE")
                     .unwrap_or_else(|_| "false".to_string())
                     .parse()
@@ -125,6 +181,25 @@ impl Config {
                     .unwrap_or_else(|_| "false".to_string())
                     .parse()
                     .unwrap_or(false),
+                verification_ttl_seconds: env::var("EMAIL_VERIFICATION_TTL_SECONDS")
+                    .unwrap_or_else(|_| "86400".to_string())
+                    .parse()
+                    .unwrap_or(86400),
+                verification_resend_seconds: env::var("EMAIL_VERIFICATION_RESEND_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .unwrap_or(60),
+            },
+            two_factor: TwoFactorConfig {
+                issuer: env::var("TOTP_ISSUER").unwrap_or_else(|_| "Axel Tournament".to_string()),
+                require_for_admins: env::var("REQUIRE_ADMIN_2FA")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .unwrap_or(false),
+                challenge_ttl_seconds: env::var("TWO_FACTOR_CHALLENGE_TTL_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .unwrap_or(300),
             },
             app: AppConfig {
                 max_code_size_mb: env::var("MAX_CODE_SIZE_MB")
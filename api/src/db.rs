@@ -74,10 +74,98 @@ pub async fn init_schema(db: &Database) -> Result<(), surrealdb::Error> {
          DEFINE FIELD IF NOT EXISTS updated_at ON user TYPE datetime;
          DEFINE FIELD IF NOT EXISTS password_reset_token ON user TYPE option<string>;
          DEFINE FIELD IF NOT EXISTS password_reset_expires ON user TYPE option<datetime>;
+         DEFINE FIELD IF NOT EXISTS email_verified ON user TYPE bool DEFAULT false;
+         DEFINE FIELD IF NOT EXISTS email_verification_sent_at ON user TYPE option<datetime>;
          DEFINE INDEX IF NOT EXISTS unique_email ON user COLUMNS email UNIQUE;
          DEFINE INDEX IF NOT EXISTS unique_username ON user COLUMNS username UNIQUE;",
     )
     .await?;
+    // Accounts created before email verification keep working
+    db.query("UPDATE user SET email_verified = true WHERE email_verified = NONE")
+        .await?;
+    // Login sessions, each holding the hash of its current refresh token
+    db.query(
+        "DEFINE TABLE IF NOT EXISTS session SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS user_id ON session TYPE record<user>;
+         DEFINE FIELD IF NOT EXISTS refresh_token_hash ON session TYPE string;
+         DEFINE FIELD IF NOT EXISTS previous_token_hash ON session TYPE option<string>;
+         DEFINE FIELD IF NOT EXISTS expires_at ON session TYPE datetime;
+         DEFINE FIELD IF NOT EXISTS revoked_at ON session TYPE option<datetime>;
+         DEFINE FIELD IF NOT EXISTS created_at ON session TYPE datetime;
+         DEFINE FIELD IF NOT EXISTS last_used_at ON session TYPE datetime;
+         DEFINE FIELD IF NOT EXISTS two_factor ON session TYPE bool DEFAULT false;
+         DEFINE INDEX IF NOT EXISTS unique_session_token ON session COLUMNS refresh_token_hash UNIQUE;
+         DEFINE INDEX IF NOT EXISTS idx_session_previous_token ON session COLUMNS previous_token_hash;
+         DEFINE INDEX IF NOT EXISTS idx_session_user ON session COLUMNS user_id;",
+    )
+    .await?;
+    db.query("UPDATE session SET two_factor = false WHERE two_factor = NONE")
+        .await?;
+    // Personal API tokens, stored hashed like reset and refresh tokens
+    db.query(
+        "DEFINE TABLE IF NOT EXISTS api_token SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS user_id ON api_token TYPE record<user>;
+         DEFINE FIELD IF NOT EXISTS name ON api_token TYPE string;
+         DEFINE FIELD IF NOT EXISTS token_hash ON api_token TYPE string;
+         DEFINE FIELD IF NOT EXISTS scopes ON api_token TYPE array<string>;
+         DEFINE FIELD IF NOT EXISTS expires_at ON api_token TYPE option<datetime>;
+         DEFINE FIELD IF NOT EXISTS last_used_at ON api_token TYPE option<datetime>;
+         DEFINE FIELD IF NOT EXISTS created_at ON api_token TYPE datetime;
+         DEFINE INDEX IF NOT EXISTS unique_api_token_hash ON api_token COLUMNS token_hash UNIQUE;
+         DEFINE INDEX IF NOT EXISTS idx_api_token_user ON api_token COLUMNS user_id;",
+    )
+    .await?;
+    // External accounts linked to users, several per user
+    db.query(
+        "DEFINE TABLE IF NOT EXISTS user_identity SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS user_id ON user_identity TYPE record<user>;
+         DEFINE FIELD IF NOT EXISTS provider ON user_identity TYPE string;
+         DEFINE FIELD IF NOT EXISTS subject ON user_identity TYPE string;
+         DEFINE FIELD IF NOT EXISTS email ON user_identity TYPE option<string>;
+         DEFINE FIELD IF NOT EXISTS created_at ON user_identity TYPE datetime;
+         DEFINE FIELD IF NOT EXISTS last_login_at ON user_identity TYPE option<datetime>;
+         DEFINE INDEX IF NOT EXISTS unique_identity ON user_identity COLUMNS provider, subject UNIQUE;
+         DEFINE INDEX IF NOT EXISTS unique_user_provider ON user_identity COLUMNS user_id, provider UNIQUE;",
+    )
+    .await?;
+    // OAuth flows in progress, consumed by the provider's callback
+    db.query(
+        "DEFINE TABLE IF NOT EXISTS oauth_state SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS state_hash ON oauth_state TYPE string;
+         DEFINE FIELD IF NOT EXISTS provider ON oauth_state TYPE string;
+         DEFINE FIELD IF NOT EXISTS nonce ON oauth_state TYPE string;
+         DEFINE FIELD IF NOT EXISTS link_user_id ON oauth_state TYPE option<record<user>>;
+         DEFINE FIELD IF NOT EXISTS expires_at ON oauth_state TYPE datetime;
+         DEFINE INDEX IF NOT EXISTS unique_oauth_state ON oauth_state COLUMNS state_hash UNIQUE;",
+    )
+    .await?;
+    // Authenticator apps, one per user, with the hashes of unused recovery codes
+    db.query(
+        "DEFINE TABLE IF NOT EXISTS user_totp SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS user_id ON user_totp TYPE record<user>;
+         DEFINE FIELD IF NOT EXISTS secret ON user_totp TYPE string;
+         DEFINE FIELD IF NOT EXISTS enabled_at ON user_totp TYPE option<datetime>;
+         DEFINE FIELD IF NOT EXISTS last_used_step ON user_totp TYPE option<int>;
+         DEFINE FIELD IF NOT EXISTS recovery_code_hashes ON user_totp TYPE array<string>;
+         DEFINE FIELD IF NOT EXISTS failed_attempts ON user_totp TYPE int DEFAULT 0;
+         DEFINE FIELD IF NOT EXISTS locked_until ON user_totp TYPE option<datetime>;
+         DEFINE FIELD IF NOT EXISTS created_at ON user_totp TYPE datetime;
+         DEFINE INDEX IF NOT EXISTS unique_user_totp ON user_totp COLUMNS user_id UNIQUE;",
+    )
+    .await?;
+    // Authenticators set up before failed codes were counted start with none
+    db.query("UPDATE user_totp SET failed_attempts = 0 WHERE failed_attempts = NONE")
+        .await?;
+    // Password logins waiting for a second factor
+    db.query(
+        "DEFINE TABLE IF NOT EXISTS two_factor_challenge SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS token_hash ON two_factor_challenge TYPE string;
+         DEFINE FIELD IF NOT EXISTS user_id ON two_factor_challenge TYPE record<user>;
+         DEFINE FIELD IF NOT EXISTS attempts ON two_factor_challenge TYPE int DEFAULT 0;
+         DEFINE FIELD IF NOT EXISTS expires_at ON two_factor_challenge TYPE datetime;
+         DEFINE INDEX IF NOT EXISTS unique_two_factor_challenge ON two_factor_challenge COLUMNS token_hash UNIQUE;",
+    )
+    .await?;
     // Tournaments table
     db.query(
         "DEFINE TABLE IF NOT EXISTS tournament SCHEMAFULL;
@@ -87,9 +175,19 @@ pub async fn init_schema(db: &Database) -> Result<(), surrealdb::Error> {
          DEFINE FIELD IF NOT EXISTS status ON tournament TYPE string;
          DEFINE FIELD IF NOT EXISTS min_players ON tournament TYPE number;
          DEFINE FIELD IF NOT EXISTS max_players ON tournament TYPE number;
+         DEFINE FIELD IF NOT EXISTS registration_time ON tournament TYPE option<datetime>;
          DEFINE FIELD IF NOT EXISTS start_time ON tournament TYPE option<datetime>;
          DEFINE FIELD IF NOT EXISTS end_time ON tournament TYPE option<datetime>;
          DEFINE FIELD IF NOT EXISTS match_generation_type ON tournament TYPE string DEFAULT 'all_vs_all';
+         DEFINE FIELD IF NOT EXISTS swiss_rounds ON tournament TYPE option<number>;
+         DEFINE FIELD IF NOT EXISTS current_round ON tournament TYPE number DEFAULT 0;
+         DEFINE FIELD IF NOT EXISTS start_attempts ON tournament TYPE number DEFAULT 0;
+         DEFINE FIELD IF NOT EXISTS next_start_attempt ON tournament TYPE option<datetime>;
+         DEFINE FIELD IF NOT EXISTS resource_limits ON tournament TYPE option<object>;
+         DEFINE FIELD IF NOT EXISTS resource_limits.cpu_limit ON tournament TYPE option<number>;
+         DEFINE FIELD IF NOT EXISTS resource_limits.memory_limit_mb ON tournament TYPE option<number>;
+         DEFINE FIELD IF NOT EXISTS resource_limits.max_pids ON tournament TYPE option<number>;
+         DEFINE FIELD IF NOT EXISTS resource_limits.wall_clock_ms ON tournament TYPE option<number>;
          DEFINE FIELD IF NOT EXISTS created_at ON tournament TYPE datetime;
          DEFINE FIELD IF NOT EXISTS updated_at ON tournament TYPE datetime;",
     )
@@ -102,6 +200,7 @@ pub async fn init_schema(db: &Database) -> Result<(), surrealdb::Error> {
          DEFINE FIELD IF NOT EXISTS submission_id ON tournament_participant TYPE option<record<submission>>;
          DEFINE FIELD IF NOT EXISTS score ON tournament_participant TYPE number DEFAULT 0;
          DEFINE FIELD IF NOT EXISTS rank ON tournament_participant TYPE option<number>;
+         DEFINE FIELD IF NOT EXISTS byes ON tournament_participant TYPE number DEFAULT 0;
          DEFINE FIELD IF NOT EXISTS joined_at ON tournament_participant TYPE datetime;
          DEFINE INDEX IF NOT EXISTS unique_tournament_user ON tournament_participant COLUMNS tournament_id, user_id UNIQUE;"
     ).await?;
@@ -116,6 +215,16 @@ pub async fn init_schema(db: &Database) -> Result<(), surrealdb::Error> {
          DEFINE FIELD IF NOT EXISTS status ON submission TYPE string DEFAULT 'pending';
          DEFINE FIELD IF NOT EXISTS error_message ON submission TYPE option<string>;
          DEFINE FIELD IF NOT EXISTS compiled_binary_path ON submission TYPE option<string>;
+         DEFINE FIELD IF NOT EXISTS compile_started_at ON submission TYPE option<datetime>;
+         DEFINE FIELD IF NOT EXISTS self_test ON submission TYPE option<object>;
+         DEFINE FIELD IF NOT EXISTS self_test.passed ON submission TYPE bool;
+         DEFINE FIELD IF NOT EXISTS self_test.games ON submission TYPE array<{
+             opponent: string,
+             seat: number,
+             verdict: string,
+             score: option<number>,
+             trace: option<string>
+         }>;
          DEFINE FIELD IF NOT EXISTS created_at ON submission TYPE datetime;",
     )
     .await?;
@@ -143,25 +252,142 @@ pub async fn init_schema(db: &Database) -> Result<(), surrealdb::Error> {
          DEFINE FIELD IF NOT EXISTS game_id ON match TYPE string;
          DEFINE FIELD IF NOT EXISTS room_id ON match TYPE option<record<room>>;
          DEFINE FIELD IF NOT EXISTS status ON match TYPE string;
-         DEFINE FIELD IF NOT EXISTS participants ON match TYPE array<{
+         DEFINE FIELD OVERWRITE participants ON match TYPE array<{
              user_id: record<user>,
              submission_id: option<record<submission>>,
-             score: option<number>
+             score: option<number>,
+             verdict: option<string>,
+             signal: option<number>
          }>;
          DEFINE FIELD IF NOT EXISTS metadata ON match TYPE option<object>;
+         DEFINE FIELD OVERWRITE metadata.resource_usage ON match TYPE option<array<{
+             submission_id: record<submission>,
+             peak_memory_bytes: option<number>,
+             cpu_time_us: option<number>,
+             oom_kills: option<number>,
+             pid_limit_hits: option<number>
+         }>>;
          DEFINE FIELD IF NOT EXISTS error_message ON match TYPE option<string>;
-         DEFINE FIELD IF NOT EXISTS game_event_source ON match TYPE option<string>;
          DEFINE FIELD IF NOT EXISTS judge_server_name ON match TYPE option<string>;
          DEFINE FIELD IF NOT EXISTS created_at ON match TYPE datetime;
          DEFINE FIELD IF NOT EXISTS updated_at ON match TYPE datetime;
          DEFINE FIELD IF NOT EXISTS started_at ON match TYPE option<datetime>;
          DEFINE FIELD IF NOT EXISTS completed_at ON match TYPE option<datetime>;
+         DEFINE FIELD IF NOT EXISTS rated ON match TYPE bool DEFAULT false;
          DEFINE INDEX IF NOT EXISTS idx_match_tournament ON match COLUMNS tournament_id;
          DEFINE INDEX IF NOT EXISTS idx_match_room ON match COLUMNS room_id;
          DEFINE INDEX IF NOT EXISTS idx_match_status ON match COLUMNS status;
          DEFINE INDEX IF NOT EXISTS idx_match_created ON match COLUMNS created_at;",
     )
     .await?;
+    // Game events of a match, written by the judge in sequence order
+    db.query(
+        "DEFINE TABLE IF NOT EXISTS game_event SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS match_id ON game_event TYPE record<match>;
+         DEFINE FIELD IF NOT EXISTS seq ON game_event TYPE number;
+         DEFINE FIELD IF NOT EXISTS version ON game_event TYPE number;
+         DEFINE FIELD IF NOT EXISTS event ON game_event FLEXIBLE TYPE object;
+         DEFINE FIELD IF NOT EXISTS created_at ON game_event TYPE datetime;
+         DEFINE INDEX IF NOT EXISTS unique_game_event_seq ON game_event COLUMNS match_id, seq UNIQUE;",
+    )
+    .await?;
+    // Bot stderr per match participant, written by the judge
+    db.query(
+        "DEFINE TABLE IF NOT EXISTS match_log SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS match_id ON match_log TYPE record<match>;
+         DEFINE FIELD IF NOT EXISTS submission_id ON match_log TYPE record<submission>;
+         DEFINE FIELD IF NOT EXISTS user_id ON match_log TYPE record<user>;
+         DEFINE FIELD IF NOT EXISTS stderr ON match_log TYPE string;
+         DEFINE FIELD IF NOT EXISTS truncated ON match_log TYPE bool DEFAULT false;
+         DEFINE FIELD IF NOT EXISTS created_at ON match_log TYPE datetime;
+         DEFINE INDEX IF NOT EXISTS idx_match_log_match ON match_log COLUMNS match_id;",
+    )
+    .await?;
+    // Elimination bracket nodes, one per bracket slot
+    db.query(
+        "DEFINE TABLE IF NOT EXISTS bracket_node SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS tournament_id ON bracket_node TYPE record<tournament>;
+         DEFINE FIELD IF NOT EXISTS bracket ON bracket_node TYPE string;
+         DEFINE FIELD IF NOT EXISTS round ON bracket_node TYPE number;
+         DEFINE FIELD IF NOT EXISTS position ON bracket_node TYPE number;
+         DEFINE FIELD IF NOT EXISTS player1 ON bracket_node TYPE option<{
+             user_id: record<user>,
+             submission_id: record<submission>,
+             seed: number
+         }>;
+         DEFINE FIELD IF NOT EXISTS player2 ON bracket_node TYPE option<{
+             user_id: record<user>,
+             submission_id: record<submission>,
+             seed: number
+         }>;
+         DEFINE FIELD IF NOT EXISTS pending_feeds ON bracket_node TYPE number DEFAULT 0;
+         DEFINE FIELD IF NOT EXISTS winner_to ON bracket_node TYPE option<{
+             node_id: record<bracket_node>,
+             slot: number
+         }>;
+         DEFINE FIELD IF NOT EXISTS loser_to ON bracket_node TYPE option<{
+             node_id: record<bracket_node>,
+             slot: number
+         }>;
+         DEFINE FIELD IF NOT EXISTS match_id ON bracket_node TYPE option<record<match>>;
+         DEFINE FIELD IF NOT EXISTS winner ON bracket_node TYPE option<{
+             user_id: record<user>,
+             submission_id: record<submission>,
+             seed: number
+         }>;
+         DEFINE FIELD IF NOT EXISTS resolved ON bracket_node TYPE bool DEFAULT false;
+         DEFINE FIELD IF NOT EXISTS created_at ON bracket_node TYPE datetime;
+         DEFINE FIELD IF NOT EXISTS updated_at ON bracket_node TYPE datetime;
+         DEFINE INDEX IF NOT EXISTS idx_bracket_tournament ON bracket_node COLUMNS tournament_id;
+         DEFINE INDEX IF NOT EXISTS unique_bracket_slot ON bracket_node COLUMNS tournament_id, bracket, round, position UNIQUE;",
+    )
+    .await?;
+
+    // Per-game Glicko-2 ratings and their history
+    db.query(
+        "DEFINE TABLE IF NOT EXISTS rating SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS user_id ON rating TYPE record<user>;
+         DEFINE FIELD IF NOT EXISTS game_id ON rating TYPE string;
+         DEFINE FIELD IF NOT EXISTS rating ON rating TYPE number;
+         DEFINE FIELD IF NOT EXISTS deviation ON rating TYPE number;
+         DEFINE FIELD IF NOT EXISTS volatility ON rating TYPE number;
+         DEFINE FIELD IF NOT EXISTS matches_played ON rating TYPE number DEFAULT 0;
+         DEFINE FIELD IF NOT EXISTS updated_at ON rating TYPE datetime;
+         DEFINE INDEX IF NOT EXISTS unique_rating_user_game ON rating COLUMNS user_id, game_id UNIQUE;
+         DEFINE INDEX IF NOT EXISTS idx_rating_game ON rating COLUMNS game_id, rating;
+         DEFINE TABLE IF NOT EXISTS rating_history SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS user_id ON rating_history TYPE record<user>;
+         DEFINE FIELD IF NOT EXISTS game_id ON rating_history TYPE string;
+         DEFINE FIELD IF NOT EXISTS match_id ON rating_history TYPE record<match>;
+         DEFINE FIELD IF NOT EXISTS rating ON rating_history TYPE number;
+         DEFINE FIELD IF NOT EXISTS deviation ON rating_history TYPE number;
+         DEFINE FIELD IF NOT EXISTS volatility ON rating_history TYPE number;
+         DEFINE FIELD IF NOT EXISTS created_at ON rating_history TYPE datetime;
+         DEFINE INDEX IF NOT EXISTS idx_rating_history_user ON rating_history COLUMNS user_id, game_id;",
+    )
+    .await?;
+
+    // Final standings, written once when a tournament completes
+    db.query(
+        "DEFINE TABLE IF NOT EXISTS standings_snapshot SCHEMAFULL;
+         DEFINE FIELD IF NOT EXISTS tournament_id ON standings_snapshot TYPE record<tournament>;
+         DEFINE FIELD IF NOT EXISTS entries ON standings_snapshot TYPE array<{
+             rank: number,
+             user_id: record<user>,
+             username: string,
+             score: number,
+             wins: number,
+             draws: number,
+             losses: number,
+             opponent_score: number
+         }>;
+         DEFINE FIELD IF NOT EXISTS created_at ON standings_snapshot TYPE datetime;
+         DEFINE INDEX IF NOT EXISTS unique_standings_tournament ON standings_snapshot COLUMNS tournament_id UNIQUE;
+         DEFINE EVENT IF NOT EXISTS standings_immutable ON standings_snapshot WHEN $event = 'UPDATE' THEN {
+             THROW 'Standings snapshots are immutable';
+         };",
+    )
+    .await?;
 
     // Add indexes for performance on commonly queried fields
     db.query(
@@ -205,6 +431,8 @@ pub async fn seed_users(
             updated_at: Datetime::default(),
             password_reset_token: None,
             password_reset_expires: None,
+            email_verified: true,
+            email_verification_sent_at: None,
         };
         let _admin_user: Option<User> = db.create(("user", "admin")).content(admin).await?;
         info!("Created admin user");
@@ -224,6 +452,8 @@ pub async fn seed_users(
             updated_at: Datetime::default(),
             password_reset_token: None,
             password_reset_expires: None,
+            email_verified: true,
+            email_verification_sent_at: None,
         };
         let _alice_user: Option<User> = db.create(("user", "alice")).content(alice).await?;
         info!("Created alice user");
@@ -243,6 +473,8 @@ pub async fn seed_users(
             updated_at: Datetime::default(),
             password_reset_token: None,
             password_reset_expires: None,
+            email_verified: true,
+            email_verification_sent_at: None,
         };
         let _bob_user: Option<User> = db.create(("user", "bob")).content(bob).await?;
         info!("Created bob user");
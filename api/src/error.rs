@@ -22,6 +22,8 @@ pub enum ApiError {
     Forbidden(String),
     #[error("Bad request: {0}")]
     BadRequest(String),
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
     #[error("Internal server error: {0}")]
     Internal(String),
     #[error("JWT error: {0}")]
@@ -41,6 +43,7 @@ impl IntoResponse for ApiError {
             ApiError::Conflict(msg) => (StatusCode::CONFLICT, msg),
             ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
             ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            ApiError::TooManyRequests(msg) => (StatusCode::TOO_MANY_REQUESTS, msg),
             ApiError::Database(err) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Database error: {}", err),
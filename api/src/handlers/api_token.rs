@@ -0,0 +1,66 @@
+use crate::{
+    AppState,
+    error::{ApiError, ApiResult},
+    models::{ApiTokenResponse, Claims, CreateApiTokenRequest, CreatedApiTokenResponse},
+    services,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use surrealdb::sql::Thing;
+use validator::Validate;
+
+fn user_id(claims: &Claims) -> ApiResult<Thing> {
+    claims
+        .sub
+        .parse::<Thing>()
+        .map_err(|_| ApiError::Auth("Invalid user id".to_string()))
+}
+
+pub async fn list_api_tokens(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> ApiResult<Json<Vec<ApiTokenResponse>>> {
+    let tokens = services::api_token::list_api_tokens(&state.db, user_id(&claims)?).await?;
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+pub async fn create_api_token(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateApiTokenRequest>,
+) -> ApiResult<(StatusCode, Json<CreatedApiTokenResponse>)> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    let (api_token, token) = services::api_token::create_api_token(
+        &state.db,
+        &state.auth_service,
+        user_id(&claims)?,
+        payload.name,
+        payload.scopes,
+        payload.expires_in_days,
+    )
+    .await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedApiTokenResponse {
+            token,
+            api_token: api_token.into(),
+        }),
+    ))
+}
+
+pub async fn delete_api_token(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(token_id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let token_id = token_id
+        .parse::<Thing>()
+        .map_err(|_| ApiError::BadRequest("Invalid API token id".to_string()))?;
+    services::api_token::delete_api_token(&state.db, user_id(&claims)?, token_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -5,51 +5,35 @@ use crate::{
     services::{self, AuthService},
 };
 use axum::{
-    Json,
-    extract::State,
+    Extension, Json,
+    extract::{Path, Query, State},
     http::{HeaderMap, StatusCode, header},
     response::Redirect,
 };
 use chrono::{Duration, Utc};
-use oauth2::{
-    AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, EndpointNotSet, EndpointSet,
-    RedirectUrl, TokenResponse, TokenUrl, basic::BasicClient,
-};
-use reqwest;
-use rand::{rng, Rng};
 use serde::Deserialize;
+use surrealdb::sql::Thing;
 use validator::Validate;
 
-type GoogleClient =
-    BasicClient<EndpointSet, EndpointNotSet, EndpointNotSet, EndpointNotSet, EndpointSet>;
-
 const OAUTH_STATE_COOKIE: &str = "oauth_state";
 
-fn build_google_client(config: &crate::config::Config) -> Result<GoogleClient, ApiError> {
-    let client = BasicClient::new(ClientId::new(config.oauth.google_client_id.clone()))
-        .set_client_secret(ClientSecret::new(config.oauth.google_client_secret.clone()))
-        .set_auth_uri(
-            AuthUrl::new("https://accounts.google.com/o/oauth2/v2/auth".to_string())
-                .map_err(|e| ApiError::Auth(format!("Invalid auth url: {}", e)))?,
-        )
-        .set_token_uri(
-            TokenUrl::new("https://oauth2.googleapis.com/token".to_string())
-                .map_err(|e| ApiError::Auth(format!("Invalid token url: {}", e)))?,
-        )
-        .set_redirect_uri(
-            RedirectUrl::new(config.oauth.google_redirect_uri.clone())
-                .map_err(|e| ApiError::Auth(format!("Invalid redirect url: {}", e)))?,
-        );
-    Ok(client)
+fn parse_provider(provider: &str) -> ApiResult<OAuthProvider> {
+    provider.parse().map_err(ApiError::NotFound)
 }
 
-fn build_state_cookie(state: &str, ttl_seconds: i64, secure: bool) -> Result<header::HeaderValue, ApiError> {
-    let max_age = ttl_seconds.max(60);
+/// The state cookie is only sent back to the callback of the provider it was set for
+fn state_cookie(
+    provider: OAuthProvider,
+    value: &str,
+    max_age: i64,
+    secure: bool,
+) -> Result<header::HeaderValue, ApiError> {
     let mut cookie = format!(
-        "{}={}; Max-Age={}; Path=/api/auth/google/callback; HttpOnly; SameSite=Lax",
+        "{}={}; Max-Age={}; Path=/api/auth/{}/callback; HttpOnly; SameSite=Lax",
         OAUTH_STATE_COOKIE,
-        state,
-        max_age
+        value,
+        max_age,
+        provider.as_str()
     );
     if secure {
         cookie.push_str("; Secure");
@@ -59,19 +43,6 @@ fn build_state_cookie(state: &str, ttl_seconds: i64, secure: bool) -> Result<hea
         .map_err(|_| ApiError::Internal("Failed to build OAuth cookie".to_string()))
 }
 
-fn expire_state_cookie(secure: bool) -> Result<header::HeaderValue, ApiError> {
-    let mut cookie = format!(
-        "{}=; Max-Age=0; Path=/api/auth/google/callback; HttpOnly; SameSite=Lax",
-        OAUTH_STATE_COOKIE
-    );
-    if secure {
-        cookie.push_str("; Secure");
-    }
-    cookie
-        .parse()
-        .map_err(|_| ApiError::Internal("Failed to clear OAuth cookie".to_string()))
-}
-
 fn get_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
     let header_value = headers.get(header::COOKIE)?.to_str().ok()?;
     header_value.split(';').find_map(|pair| {
@@ -86,24 +57,88 @@ fn get_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
     })
 }
 
-fn normalize_username(name: &str, email: &str) -> String {
-    let base = if name.trim().is_empty() {
-        email.split('@').next().unwrap_or("player")
-    } else {
-        name
-    };
-    let mut normalized: String = base
-        .chars()
-        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
-        .collect();
-    if normalized.is_empty() {
-        normalized = "player".to_string();
-    }
-    if normalized.len() < 3 {
-        normalized.push_str("player");
+/// Start an OAuth flow, returning the provider URL to send the browser to and
+/// the cookie that ties the callback to this browser. With `link_user_id` the
+/// callback links the account instead of signing in.
+pub async fn begin_oauth_flow(
+    state: &AppState,
+    provider: &str,
+    link_user_id: Option<Thing>,
+) -> ApiResult<(HeaderMap, String)> {
+    let provider = parse_provider(provider)?;
+    let client = state.oauth_service.get(provider)?;
+    let ttl_seconds = state.config.oauth.state_ttl_seconds.max(60);
+    let (oauth_state, nonce) = services::identity::create_oauth_state(
+        &state.db,
+        &state.auth_service,
+        provider,
+        link_user_id,
+        ttl_seconds,
+    )
+    .await?;
+    let url = client.authorize_url(&oauth_state, &nonce)?;
+    let mut headers = HeaderMap::new();
+    let cookie = state_cookie(
+        provider,
+        &oauth_state,
+        ttl_seconds,
+        state.config.oauth.cookie_secure,
+    )?;
+    headers.insert(header::SET_COOKIE, cookie);
+    Ok((headers, url))
+}
+
+/// Open a session for a user who just proved who they are
+async fn start_session(state: &AppState, user: &User, two_factor: bool) -> ApiResult<AuthResponse> {
+    let user_id = user
+        .id
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("User ID is missing".to_string()))?
+        .clone();
+    let (session, refresh_token) = services::session::create_session(
+        &state.db,
+        &state.auth_service,
+        user_id,
+        state.config.jwt.refresh_expiration,
+        two_factor,
+    )
+    .await?;
+    let session_id = session
+        .id
+        .ok_or_else(|| ApiError::Internal("Session ID is missing".to_string()))?;
+    Ok(AuthResponse {
+        token: state.auth_service.generate_token(user, &session_id)?,
+        refresh_token,
+        expires_in: state.auth_service.access_token_ttl(),
+        user: AuthService::user_to_info(user)?,
+    })
+}
+
+/// Open a session, unless the user has a second factor to show first
+async fn finish_login(state: &AppState, user: &User) -> ApiResult<LoginResponse> {
+    let user_id = user
+        .id
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("User ID is missing".to_string()))?
+        .clone();
+    if !services::two_factor::is_two_factor_enabled(&state.db, user_id.clone()).await? {
+        let session = start_session(state, user, false).await?;
+        return Ok(LoginResponse::Session(session));
     }
-    normalized.truncate(50);
-    normalized
+    let ttl_seconds = state.config.two_factor.challenge_ttl_seconds.max(60);
+    let token = services::two_factor::create_challenge(
+        &state.db,
+        &state.auth_service,
+        user_id,
+        ttl_seconds,
+    )
+    .await?;
+    let challenge = TwoFactorChallengeResponse {
+        two_factor_required: true,
+        two_factor_token: token,
+        expires_in: ttl_seconds,
+    };
+    Ok(LoginResponse::TwoFactorRequired(challenge))
 }
 
 pub async fn register(
@@ -133,22 +168,64 @@ pub async fn register(
         None,
     )
     .await?;
-    // Generate token
-    let token = state.auth_service.generate_token(&user)?;
-    let user_info = AuthService::user_to_info(&user)?;
-    Ok((
-        StatusCode::CREATED,
-        Json(AuthResponse {
-            token,
-            user: user_info,
-        }),
-    ))
+    // The account exists either way; a failed send can be retried with a resend
+    if let Err(e) = services::email_verification::send_verification_email(
+        &state.db,
+        &state.auth_service,
+        &state.email_service,
+        &user,
+        state.config.email.verification_ttl_seconds,
+        state.config.email.verification_resend_seconds,
+    )
+    .await
+    {
+        tracing::warn!("Failed to send verification email to {}: {}", user.email, e);
+    }
+    let response = start_session(&state, &user, false).await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Json(payload): Json<VerifyEmailRequest>,
+) -> ApiResult<Json<UserInfo>> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    let user =
+        services::email_verification::verify_email(&state.db, &state.auth_service, &payload.token)
+            .await?;
+    Ok(Json(AuthService::user_to_info(&user)?))
+}
+
+/// Send the signed-in user a new verification link
+pub async fn resend_verification(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let user_id = claims
+        .sub
+        .parse::<Thing>()
+        .map_err(|_| ApiError::Auth("Invalid user id".to_string()))?;
+    let user = services::auth::get_user_by_id(&state.db, user_id).await?;
+    services::email_verification::send_verification_email(
+        &state.db,
+        &state.auth_service,
+        &state.email_service,
+        &user,
+        state.config.email.verification_ttl_seconds,
+        state.config.email.verification_resend_seconds,
+    )
+    .await?;
+    Ok(Json(serde_json::json!({
+        "message": "Verification email sent"
+    })))
 }
 
 pub async fn login(
     State(state): State<AppState>,
     Json(payload): Json<LoginRequest>,
-) -> ApiResult<Json<AuthResponse>> {
+) -> ApiResult<Json<LoginResponse>> {
     payload
         .validate()
         .map_err(|e| ApiError::Validation(e.to_string()))?;
@@ -175,15 +252,94 @@ pub async fn login(
                 .unwrap_or_else(|| "No reason provided".to_string())
         )));
     }
-    // Generate token
-    let token = state.auth_service.generate_token(&user)?;
-    let user_info = AuthService::user_to_info(&user)?;
+    let response = finish_login(&state, &user).await?;
+    Ok(Json(response))
+}
+
+/// Finish a login held back for its second factor
+pub async fn login_two_factor(
+    State(state): State<AppState>,
+    Json(payload): Json<TwoFactorLoginRequest>,
+) -> ApiResult<Json<AuthResponse>> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    let user_id = services::two_factor::complete_challenge(
+        &state.db,
+        &state.auth_service,
+        &payload.two_factor_token,
+        &payload.code,
+    )
+    .await?;
+    let user = services::auth::get_user_by_id(&state.db, user_id).await?;
+    if user.is_banned {
+        return Err(ApiError::Forbidden("User is banned".to_string()));
+    }
+    let response = start_session(&state, &user, true).await?;
+    Ok(Json(response))
+}
+
+pub async fn refresh_token(
+    State(state): State<AppState>,
+    Json(payload): Json<RefreshTokenRequest>,
+) -> ApiResult<Json<AuthResponse>> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    let (session, refresh_token) = services::session::rotate_refresh_token(
+        &state.db,
+        &state.auth_service,
+        &payload.refresh_token,
+        state.config.jwt.refresh_expiration,
+    )
+    .await?;
+    let session_id = session
+        .id
+        .ok_or_else(|| ApiError::Internal("Session ID is missing".to_string()))?;
+    let user = services::auth::get_user_by_id(&state.db, session.user_id).await?;
+    if user.is_banned {
+        services::session::revoke_session(&state.db, session_id).await?;
+        return Err(ApiError::Forbidden("User is banned".to_string()));
+    }
     Ok(Json(AuthResponse {
-        token,
-        user: user_info,
+        token: state.auth_service.generate_token(&user, &session_id)?,
+        refresh_token,
+        expires_in: state.auth_service.access_token_ttl(),
+        user: AuthService::user_to_info(&user)?,
     }))
 }
 
+/// End the session the request was made with
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let session_id = claims
+        .sid
+        .parse::<Thing>()
+        .map_err(|_| ApiError::Auth("Invalid session id".to_string()))?;
+    services::session::revoke_session(&state.db, session_id).await?;
+    Ok(Json(serde_json::json!({
+        "message": "Logged out"
+    })))
+}
+
+/// End every session of the user, on every device
+pub async fn logout_all(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let user_id = claims
+        .sub
+        .parse::<Thing>()
+        .map_err(|_| ApiError::Auth("Invalid user id".to_string()))?;
+    let revoked = services::session::revoke_user_sessions(&state.db, user_id).await?;
+    Ok(Json(serde_json::json!({
+        "message": "Logged out of all sessions",
+        "revoked": revoked
+    })))
+}
+
 pub async fn request_password_reset(
     State(state): State<AppState>,
     Json(payload): Json<ResetPasswordRequest>,
@@ -248,60 +404,55 @@ pub async fn confirm_password_reset(
     user.password_reset_token = None;
     user.password_reset_expires = None;
     let user_id = user.id.as_ref().unwrap().clone();
-    services::user::update_user(&state.db, user_id, user).await?;
+    services::user::update_user(&state.db, user_id.clone(), user).await?;
+    // Whoever knew the old password may still hold a session
+    services::session::revoke_user_sessions(&state.db, user_id).await?;
     Ok(Json(serde_json::json!({
         "message": "Password has been reset successfully"
     })))
 }
 
 #[derive(Deserialize)]
-pub struct GoogleCallbackQuery {
+pub struct OAuthCallbackQuery {
     code: Option<String>,
     state: Option<String>,
     error: Option<String>,
     error_description: Option<String>,
 }
 
-#[derive(Deserialize)]
-pub struct GoogleUserInfo {
-    id: String,
-    email: String,
-    name: String,
-    #[serde(default)]
-    verified_email: Option<bool>,
+/// Sign-in providers enabled on this deployment
+pub async fn list_oauth_providers(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<OAuthProviderInfo>>> {
+    Ok(Json(state.oauth_service.providers()))
 }
 
-pub async fn google_login(
+pub async fn oauth_login(
     State(state): State<AppState>,
+    Path(provider): Path<String>,
 ) -> Result<(HeaderMap, Redirect), ApiError> {
-    let client = build_google_client(&state.config)?;
-    let (auth_url, csrf_token) = client
-        .authorize_url(CsrfToken::new_random)
-        .add_scope(oauth2::Scope::new("openid".to_string()))
-        .add_scope(oauth2::Scope::new("email".to_string()))
-        .add_scope(oauth2::Scope::new("profile".to_string()))
-        .url();
-    let mut headers = HeaderMap::new();
-    let cookie = build_state_cookie(
-        csrf_token.secret(),
-        state.config.oauth.state_ttl_seconds,
-        state.config.oauth.cookie_secure,
-    )?;
-    headers.insert(header::SET_COOKIE, cookie);
-    Ok((headers, Redirect::to(auth_url.as_str())))
+    let (headers, url) = begin_oauth_flow(&state, &provider, None).await?;
+    Ok((headers, Redirect::to(&url)))
 }
 
-pub async fn google_callback(
+pub async fn oauth_callback(
     State(state): State<AppState>,
-    axum::extract::Query(query): axum::extract::Query<GoogleCallbackQuery>,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuthCallbackQuery>,
     headers: HeaderMap,
 ) -> Result<(HeaderMap, Redirect), ApiError> {
+    let provider = parse_provider(&provider)?;
     if let Some(error) = query.error.as_deref() {
         let detail = query
             .error_description
             .as_deref()
             .unwrap_or("OAuth error");
-        return Err(ApiError::Auth(format!("Google OAuth error: {} ({})", error, detail)));
+        return Err(ApiError::Auth(format!(
+            "{} OAuth error: {} ({})",
+            provider.as_str(),
+            error,
+            detail
+        )));
     }
     let code = query
         .code
@@ -316,81 +467,35 @@ pub async fn google_callback(
     if cookie_state != state_value {
         return Err(ApiError::Auth("Invalid OAuth state".to_string()));
     }
-    let client = build_google_client(&state.config)?;
-    let http_client = reqwest::Client::new();
-    let token_result = client
-        .exchange_code(AuthorizationCode::new(code.to_string()))
-        .request_async(&http_client)
-        .await
-        .map_err(|e| ApiError::Auth(format!("Failed to exchange code: {}", e)))?;
-    // Get user info from Google
-    let client = reqwest::Client::new();
-    let user_info: GoogleUserInfo = client
-        .get("https://www.googleapis.com/oauth2/v2/userinfo")
-        .bearer_auth(token_result.access_token().secret())
-        .send()
-        .await
-        .map_err(|e| ApiError::Auth(format!("Failed to get user info: {}", e)))?
-        .json()
-        .await
-        .map_err(|e| ApiError::Auth(format!("Failed to parse user info: {}", e)))?;
-    if user_info.email.trim().is_empty() {
-        return Err(ApiError::Auth("Google account email missing".to_string()));
-    }
-    if user_info.verified_email != Some(true) {
-        return Err(ApiError::Forbidden(
-            "Google account email is not verified".to_string(),
-        ));
+    let flow = services::identity::consume_oauth_state(
+        &state.db,
+        &state.auth_service,
+        provider,
+        state_value,
+    )
+    .await?;
+    let client = state.oauth_service.get(provider)?;
+    let identity = client.identify(code, &flow.nonce).await?;
+
+    let frontend_url = state.config.email.frontend_url.trim_end_matches('/');
+    let mut response_headers = HeaderMap::new();
+    let cookie = state_cookie(provider, "", 0, state.config.oauth.cookie_secure)?;
+    response_headers.insert(header::SET_COOKIE, cookie);
+
+    // A signed-in user linking another account goes back to their profile
+    if let Some(user_id) = flow.link_user_id {
+        services::identity::link_identity(&state.db, user_id, &identity).await?;
+        let redirect_url = format!("{}/profile?linked={}", frontend_url, provider.as_str());
+        return Ok((response_headers, Redirect::to(&redirect_url)));
     }
-    // Check if user exists with this OAuth ID
-    let user = if let Some(existing_user) =
-        services::auth::get_user_by_oauth(&state.db, "google", &user_info.id).await?
-    {
-        existing_user
-    } else if let Some(existing_user) =
-        services::auth::get_user_by_email(&state.db, &user_info.email).await?
-    {
-        if existing_user.oauth_provider.is_some() {
-            return Err(ApiError::Conflict(
-                "Email already registered with another sign-in method".to_string(),
-            ));
-        }
-        let mut updated_user = existing_user;
-        updated_user.oauth_provider = Some(OAuthProvider::Google);
-        updated_user.oauth_id = Some(user_info.id.clone());
-        let user_id = updated_user
-            .id
-            .as_ref()
-            .ok_or_else(|| ApiError::Internal("User ID is missing".to_string()))?
-            .clone();
-        services::user::update_user(&state.db, user_id, updated_user).await?
-    } else {
-        let base_username = normalize_username(&user_info.name, &user_info.email);
-        let mut username = base_username.clone();
-        let mut attempts = 0;
-        while services::auth::get_user_by_username(&state.db, &username).await?.is_some() {
-            if attempts >= 5 {
-                return Err(ApiError::Conflict(
-                    "Unable to allocate unique username".to_string(),
-                ));
-            }
-            let suffix = rng().random_range(1000..=9999);
-            username = format!("{base_username}-{suffix}");
-            username.truncate(50);
-            attempts += 1;
-        }
-        // Create new user
-        services::user::create_user(
-            &state.db,
-            user_info.email,
-            username,
-            None,
-            state.config.app.default_location.clone(),
-            Some(OAuthProvider::Google),
-            Some(user_info.id),
-        )
-        .await?
-    };
+
+    let user = services::identity::sign_in_with_identity(
+        &state.db,
+        client,
+        &identity,
+        state.config.app.default_location.clone(),
+    )
+    .await?;
     // Check if banned
     if user.is_banned {
         return Err(ApiError::Forbidden(format!(
@@ -399,23 +504,17 @@ pub async fn google_callback(
                 .unwrap_or_else(|| "No reason provided".to_string())
         )));
     }
-    // Generate token
-    let token = state.auth_service.generate_token(&user)?;
-
-    // Get frontend URL from config
-    let frontend_url = std::env::var("FRONTEND_URL")
-        .unwrap_or_else(|_| "http://localhost:5173".to_string());
-
-    // Redirect to frontend with just the token
-    let redirect_url = format!(
-        "{}/auth/google/callback?token={}",
-        frontend_url,
-        token
-    );
-
-    let mut response_headers = HeaderMap::new();
-    let cookie = expire_state_cookie(state.config.oauth.cookie_secure)?;
-    response_headers.insert(header::SET_COOKIE, cookie);
-
+    // Redirect to frontend with the session's tokens, or to ask for the second factor.
+    // They go in the fragment, which browsers keep out of requests, logs and referrers.
+    let redirect_url = match finish_login(&state, &user).await? {
+        LoginResponse::Session(session) => format!(
+            "{}/auth/callback#token={}&refresh_token={}",
+            frontend_url, session.token, session.refresh_token
+        ),
+        LoginResponse::TwoFactorRequired(challenge) => format!(
+            "{}/auth/callback#two_factor_token={}",
+            frontend_url, challenge.two_factor_token
+        ),
+    };
     Ok((response_headers, Redirect::to(&redirect_url)))
 }
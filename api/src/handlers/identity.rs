@@ -0,0 +1,50 @@
+use crate::{
+    AppState,
+    error::{ApiError, ApiResult},
+    handlers::begin_oauth_flow,
+    models::{Claims, UserIdentityResponse},
+    services,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use surrealdb::sql::Thing;
+
+fn user_id(claims: &Claims) -> ApiResult<Thing> {
+    claims
+        .sub
+        .parse::<Thing>()
+        .map_err(|_| ApiError::Auth("Invalid user id".to_string()))
+}
+
+pub async fn list_identities(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> ApiResult<Json<Vec<UserIdentityResponse>>> {
+    let identities = services::identity::list_identities(&state.db, user_id(&claims)?).await?;
+    Ok(Json(identities.into_iter().map(Into::into).collect()))
+}
+
+/// Start linking a provider account; the browser is sent to the returned URL
+pub async fn link_identity(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(provider): Path<String>,
+) -> ApiResult<(HeaderMap, Json<serde_json::Value>)> {
+    let (headers, url) = begin_oauth_flow(&state, &provider, Some(user_id(&claims)?)).await?;
+    Ok((headers, Json(serde_json::json!({ "url": url }))))
+}
+
+pub async fn unlink_identity(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(identity_id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let identity_id = identity_id
+        .parse::<Thing>()
+        .map_err(|_| ApiError::BadRequest("Invalid identity id".to_string()))?;
+    services::identity::unlink_identity(&state.db, user_id(&claims)?, identity_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
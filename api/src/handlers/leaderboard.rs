@@ -1,20 +1,31 @@
 use crate::{
     AppState,
-    error::ApiResult,
-    models::{LeaderboardEntry, LeaderboardQuery},
+    error::{ApiError, ApiResult},
+    models::{LeaderboardMode, LeaderboardQuery, RatingHistoryQuery, RatingHistoryResponse},
     services,
 };
 use axum::{
     Json,
-    extract::{Query, State},
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
 };
 use surrealdb::sql::Thing;
 
 pub async fn get_leaderboard(
     State(state): State<AppState>,
     Query(query): Query<LeaderboardQuery>,
-) -> ApiResult<Json<Vec<LeaderboardEntry>>> {
+) -> ApiResult<Response> {
     let limit = query.limit.unwrap_or(100);
+    if query.mode.unwrap_or_default() == LeaderboardMode::Rating {
+        // Ratings are keyed by the plain game id, e.g. "rock-paper-scissors"
+        let game_id = query.game_id.ok_or_else(|| {
+            ApiError::BadRequest("game_id is required for the rating leaderboard".to_string())
+        })?;
+        let entries =
+            services::leaderboard::get_rating_leaderboard(&state.db, limit, game_id).await?;
+        return Ok(Json(entries).into_response());
+    }
+
     let tournament_id = query
         .tournament_id
         .as_deref()
@@ -34,5 +45,17 @@ pub async fn get_leaderboard(
         .transpose()?;
     let entries =
         services::leaderboard::get_leaderboard(&state.db, limit, tournament_id, game_id).await?;
-    Ok(Json(entries))
+    Ok(Json(entries).into_response())
+}
+
+pub async fn get_user_rating_history(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    Query(query): Query<RatingHistoryQuery>,
+) -> ApiResult<Json<Vec<RatingHistoryResponse>>> {
+    let user_id = user_id
+        .parse::<Thing>()
+        .map_err(|_| ApiError::BadRequest("Invalid user id".to_string()))?;
+    let history = services::rating::get_rating_history(&state.db, user_id, query.game_id).await?;
+    Ok(Json(history.into_iter().map(Into::into).collect()))
 }
@@ -1,11 +1,15 @@
 use crate::{
     AppState,
     error::ApiResult,
-    models::matches::{CreateMatchRequest, MatchResponse},
+    models::{
+        Claims, UserRole,
+        game::Replay,
+        matches::{CreateMatchRequest, MatchLogResponse, MatchResponse},
+    },
     services,
 };
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Path, Query, State},
     http::StatusCode,
 };
@@ -62,6 +66,20 @@ pub async fn get_match(
     Ok(Json(match_data.into()))
 }
 
+pub async fn get_match_replay(
+    State(state): State<AppState>,
+    Path(match_id): Path<String>,
+) -> ApiResult<Json<Replay>> {
+    let replay = services::matches::get_match_replay(
+        &state.db,
+        match_id
+            .parse::<Thing>()
+            .map_err(|_| crate::error::ApiError::BadRequest("Invalid match id".to_string()))?,
+    )
+    .await?;
+    Ok(Json(replay))
+}
+
 pub async fn list_matches(
     State(state): State<AppState>,
     Query(query): Query<ListMatchesQuery>,
@@ -102,3 +120,30 @@ pub async fn list_matches(
     .await?;
     Ok(Json(matches.into_iter().map(Into::into).collect()))
 }
+
+pub async fn get_match_logs(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(match_id): Path<String>,
+) -> ApiResult<Json<Vec<MatchLogResponse>>> {
+    // Players only see the logs of their own bots
+    let viewer = if claims.role == UserRole::Admin {
+        None
+    } else {
+        Some(
+            claims
+                .sub
+                .parse::<Thing>()
+                .map_err(|_| crate::error::ApiError::BadRequest("Invalid user id".to_string()))?,
+        )
+    };
+    let logs = services::matches::get_match_logs(
+        &state.db,
+        match_id
+            .parse::<Thing>()
+            .map_err(|_| crate::error::ApiError::BadRequest("Invalid match id".to_string()))?,
+        viewer,
+    )
+    .await?;
+    Ok(Json(logs.into_iter().map(Into::into).collect()))
+}
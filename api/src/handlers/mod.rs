@@ -1,17 +1,23 @@
 pub mod admin;
+pub mod api_token;
 pub mod auth;
 pub mod game;
+pub mod identity;
 pub mod leaderboard;
 pub mod matches;
 pub mod submission;
 pub mod tournament;
+pub mod two_factor;
 pub mod user;
 
 pub use admin::*;
+pub use api_token::*;
 pub use auth::*;
 pub use game::*;
+pub use identity::*;
 pub use leaderboard::*;
 pub use matches::*;
 pub use submission::*;
 pub use tournament::*;
+pub use two_factor::*;
 pub use user::*;
@@ -23,6 +23,11 @@ pub async fn create_submission(
     payload
         .validate()
         .map_err(|e| crate::error::ApiError::Validation(e.to_string()))?;
+    let user_id = claims
+        .sub
+        .parse::<Thing>()
+        .map_err(|_| crate::error::ApiError::BadRequest("Invalid user id".to_string()))?;
+    services::email_verification::require_verified_email(&state.db, user_id.clone()).await?;
 
     // Validate code size
     let max_bytes = state.config.app.max_code_size_mb * 1024 * 1024;
@@ -34,7 +39,7 @@ pub async fn create_submission(
     }
 
     // Validate language
-    let language = ProgrammingLanguage::from_str(&payload.language).ok_or_else(|| {
+    let language = payload.language.parse::<ProgrammingLanguage>().map_err(|_| {
         crate::error::ApiError::Validation("Invalid programming language".to_string())
     })?;
     // Get tournament to verify it exists and get game_id
@@ -62,10 +67,7 @@ pub async fn create_submission(
     // Create submission
     let submission = services::submission::create_submission(
         &state.db,
-        claims
-            .sub
-            .parse::<Thing>()
-            .map_err(|_| crate::error::ApiError::BadRequest("Invalid user id".to_string()))?,
+        user_id,
         payload
             .tournament_id
             .parse::<Thing>()
@@ -80,6 +82,8 @@ pub async fn create_submission(
         tournament_id: submission.tournament_id.id.to_string(),
         language: submission.language,
         status: submission.status,
+        error_message: submission.error_message,
+        self_test: submission.self_test,
         created_at: submission.created_at,
     };
     Ok((StatusCode::CREATED, Json(response)))
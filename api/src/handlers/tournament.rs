@@ -2,8 +2,9 @@ use crate::{
     AppState,
     error::ApiResult,
     models::{
-        Claims, CreateTournamentRequest, TournamentParticipant, TournamentResponse, TournamentStatus,
-        UpdateTournamentRequest, UserRole,
+        BracketNodeResponse, Claims, CreateTournamentRequest, StandingsSnapshotResponse,
+        TournamentParticipant, TournamentResponse, TournamentStatus, UpdateTournamentRequest,
+        UserRole,
     },
     services,
 };
@@ -50,18 +51,7 @@ pub async fn create_tournament(
         ));
     }
 
-    let tournament = services::tournament::create_tournament(
-        &state.db,
-        payload.game_id,
-        payload.name,
-        payload.description,
-        payload.min_players,
-        payload.max_players,
-        payload.start_time,
-        payload.end_time,
-        payload.match_generation_type,
-    )
-    .await?;
+    let tournament = services::tournament::create_tournament(&state.db, payload).await?;
     Ok((StatusCode::CREATED, Json(tournament.into())))
 }
 
@@ -121,16 +111,8 @@ pub async fn update_tournament(
     // Only admins can update tournaments
     ensure_tournament_owner(&state, tournament_id.clone(), &claims).await?;
 
-    let tournament = services::tournament::update_tournament(
-        &state.db,
-        tournament_id,
-        payload.name,
-        payload.description,
-        payload.status,
-        payload.start_time,
-        payload.end_time,
-    )
-    .await?;
+    let tournament =
+        services::tournament::update_tournament(&state.db, tournament_id, payload).await?;
     Ok(Json(tournament.into()))
 }
 
@@ -139,15 +121,17 @@ pub async fn join_tournament(
     Path(tournament_id): Path<String>,
     Extension(claims): Extension<Claims>,
 ) -> ApiResult<(StatusCode, Json<TournamentParticipant>)> {
+    let user_id = claims
+        .sub
+        .parse::<Thing>()
+        .map_err(|_| crate::error::ApiError::BadRequest("Invalid user id".to_string()))?;
+    services::email_verification::require_verified_email(&state.db, user_id.clone()).await?;
     let participant = services::tournament::join_tournament(
         &state.db,
         tournament_id
             .parse()
             .map_err(|_| crate::error::ApiError::BadRequest("Invalid tournament id".to_string()))?,
-        claims
-            .sub
-            .parse::<Thing>()
-            .map_err(|_| crate::error::ApiError::BadRequest("Invalid user id".to_string()))?,
+        user_id,
     )
     .await?;
     Ok((StatusCode::CREATED, Json(participant)))
@@ -186,6 +170,34 @@ pub async fn get_tournament_participants(
     Ok(Json(participants))
 }
 
+pub async fn get_tournament_bracket(
+    State(state): State<AppState>,
+    Path(tournament_id): Path<String>,
+) -> ApiResult<Json<Vec<BracketNodeResponse>>> {
+    let nodes = services::bracket::get_bracket(
+        &state.db,
+        tournament_id
+            .parse()
+            .map_err(|_| crate::error::ApiError::BadRequest("Invalid tournament id".to_string()))?,
+    )
+    .await?;
+    Ok(Json(nodes.into_iter().map(Into::into).collect()))
+}
+
+pub async fn get_tournament_standings(
+    State(state): State<AppState>,
+    Path(tournament_id): Path<String>,
+) -> ApiResult<Json<StandingsSnapshotResponse>> {
+    let snapshot = services::completion::get_standings_snapshot(
+        &state.db,
+        tournament_id
+            .parse()
+            .map_err(|_| crate::error::ApiError::BadRequest("Invalid tournament id".to_string()))?,
+    )
+    .await?;
+    Ok(Json(snapshot.into()))
+}
+
 /// Start a tournament and generate matches (admin only)
 pub async fn start_tournament(
     State(state): State<AppState>,
@@ -0,0 +1,108 @@
+use crate::{
+    AppState,
+    error::{ApiError, ApiResult},
+    models::{
+        Claims, RecoveryCodesResponse, TwoFactorCodeRequest, TwoFactorSetupResponse,
+        TwoFactorStatusResponse,
+    },
+    services,
+};
+use axum::{Extension, Json, extract::State, http::StatusCode};
+use surrealdb::sql::Thing;
+use validator::Validate;
+
+fn user_id(claims: &Claims) -> ApiResult<Thing> {
+    claims
+        .sub
+        .parse::<Thing>()
+        .map_err(|_| ApiError::Auth("Invalid user id".to_string()))
+}
+
+pub async fn get_two_factor_status(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> ApiResult<Json<TwoFactorStatusResponse>> {
+    let user = services::auth::get_user_by_id(&state.db, user_id(&claims)?).await?;
+    let status = services::two_factor::get_two_factor_status(
+        &state.db,
+        &user,
+        state.config.two_factor.require_for_admins,
+    )
+    .await?;
+    Ok(Json(status))
+}
+
+/// Start setting up an authenticator app
+pub async fn setup_two_factor(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> ApiResult<Json<TwoFactorSetupResponse>> {
+    let user = services::auth::get_user_by_id(&state.db, user_id(&claims)?).await?;
+    let setup =
+        services::two_factor::begin_enrollment(&state.db, &user, &state.config.two_factor.issuer)
+            .await?;
+    Ok(Json(setup))
+}
+
+/// Confirm the authenticator with a code from it and get the recovery codes
+pub async fn enable_two_factor(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> ApiResult<Json<RecoveryCodesResponse>> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    let recovery_codes = services::two_factor::enable_two_factor(
+        &state.db,
+        &state.auth_service,
+        user_id(&claims)?,
+        &payload.code,
+    )
+    .await?;
+    // The code just shown counts for the session it was shown in
+    let session_id = claims
+        .sid
+        .parse::<Thing>()
+        .map_err(|_| ApiError::Auth("Invalid session id".to_string()))?;
+    services::session::mark_session_two_factor(&state.db, session_id).await?;
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+pub async fn disable_two_factor(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> ApiResult<StatusCode> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    let user = services::auth::get_user_by_id(&state.db, user_id(&claims)?).await?;
+    services::two_factor::disable_two_factor(
+        &state.db,
+        &state.auth_service,
+        &user,
+        &payload.code,
+        state.config.two_factor.require_for_admins,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> ApiResult<Json<RecoveryCodesResponse>> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    let recovery_codes = services::two_factor::regenerate_recovery_codes(
+        &state.db,
+        &state.auth_service,
+        user_id(&claims)?,
+        &payload.code,
+    )
+    .await?;
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
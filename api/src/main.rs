@@ -2,7 +2,10 @@ use api::{
     AppState,
     config::Config,
     db, router,
-    services::{AuthService, EmailService, HealerService},
+    services::{
+        AuthService, CompletionService, EmailService, HealerService, OAuthService,
+        ProgressionService, RatingService, SchedulerService,
+    },
 };
 use std::sync::Arc;
 
@@ -19,6 +22,7 @@ async fn main() -> anyhow::Result<()> {
         config.jwt.expiration,
     ));
     let email_service = Arc::new(EmailService::new(config.email.clone()));
+    let oauth_service = Arc::new(OAuthService::from_config(&config.oauth).await);
     // Create seed users if user table is empty
     let admin_password_hash = auth_service.hash_password(&config.admin.password)?;
     let bob_password_hash = auth_service.hash_password(&config.bob.password)?;
@@ -40,10 +44,35 @@ async fn main() -> anyhow::Result<()> {
         healer.run().await;
     });
 
+    // Open, start and close tournaments at their scheduled times
+    let scheduler = SchedulerService::new(db.clone());
+    tokio::spawn(async move {
+        scheduler.run().await;
+    });
+
+    // Update standings and create follow-up matches as matches complete
+    let progression = ProgressionService::new(db.clone());
+    tokio::spawn(async move {
+        progression.run().await;
+    });
+
+    // Complete tournaments once all of their matches are done
+    let completion = CompletionService::new(db.clone());
+    tokio::spawn(async move {
+        completion.run().await;
+    });
+
+    // Fold completed matches into per-game ratings
+    let rating = RatingService::new(db.clone());
+    tokio::spawn(async move {
+        rating.run().await;
+    });
+
     let state = AppState {
         db,
         auth_service,
         email_service,
+        oauth_service,
         config: Arc::new(config.clone()),
     };
     let app = router::create_router(state);
@@ -1,15 +1,70 @@
 use crate::{
     AppState,
     error::{ApiError, ApiResult},
-    models::{Claims, UserRole},
+    models::{ApiTokenScope, Claims, UserRole},
+    services::API_TOKEN_PREFIX,
 };
 use axum::{
-    extract::{Request, State},
+    extract::{MatchedPath, Request, State},
+    http::Method,
     middleware::Next,
     response::Response,
 };
 use surrealdb::sql::Thing;
 
+/// Scope an API token needs for a protected route. Routes left out, such as
+/// token management and logout, only accept a login session.
+fn required_scope(method: &Method, path: &str) -> Option<ApiTokenScope> {
+    match (method.as_str(), path) {
+        ("GET", "/api/users/profile") => Some(ApiTokenScope::ProfileRead),
+        ("GET", "/api/submissions") | ("GET", "/api/submissions/{id}") => {
+            Some(ApiTokenScope::SubmissionsRead)
+        }
+        ("POST", "/api/submissions") => Some(ApiTokenScope::SubmissionsWrite),
+        ("POST", "/api/tournaments/{id}/join") | ("DELETE", "/api/tournaments/{id}/leave") => {
+            Some(ApiTokenScope::TournamentsWrite)
+        }
+        ("GET", "/api/matches/{id}/logs") => Some(ApiTokenScope::MatchesRead),
+        _ => None,
+    }
+}
+
+/// Claims for a request made with a personal API token. The scope is worked
+/// out by the caller, nothing borrowed from the request is held across awaits.
+async fn api_token_claims(
+    state: &AppState,
+    required: Option<ApiTokenScope>,
+    secret: &str,
+) -> ApiResult<Claims> {
+    let api_token =
+        crate::services::api_token::authenticate_api_token(&state.db, &state.auth_service, secret)
+            .await?;
+    let scope = required.ok_or_else(|| {
+        ApiError::Forbidden("This endpoint cannot be used with an API token".to_string())
+    })?;
+    if !api_token.scopes.contains(&scope) {
+        return Err(ApiError::Forbidden(format!(
+            "API token is missing the {} scope",
+            scope.as_str()
+        )));
+    }
+    let user = crate::services::auth::get_user_by_id(&state.db, api_token.user_id.clone()).await?;
+    let token_id = api_token
+        .id
+        .ok_or_else(|| ApiError::Internal("API token ID is missing".to_string()))?;
+    Ok(Claims {
+        sub: api_token.user_id.to_string(),
+        email: user.email,
+        role: user.role,
+        sid: token_id.to_string(),
+        exp: api_token
+            .expires_at
+            .map_or(0, |expires| expires.0.timestamp() as usize), // 0 never expires
+        iat: api_token.created_at.0.timestamp() as usize,
+        scopes: Some(api_token.scopes),
+    })
+}
+
 pub async fn auth_middleware(
     State(state): State<AppState>,
     mut req: Request,
@@ -22,8 +77,28 @@ pub async fn auth_middleware(
         .ok_or_else(|| ApiError::Auth("Missing authorization header".to_string()))?;
     let token = auth_header
         .strip_prefix("Bearer ")
-        .ok_or_else(|| ApiError::Auth("Invalid authorization format".to_string()))?;
-    let claims = state.auth_service.validate_token(token)?;
+        .ok_or_else(|| ApiError::Auth("Invalid authorization format".to_string()))?
+        .to_string();
+    let claims = if token.starts_with(API_TOKEN_PREFIX) {
+        let path = req
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|p| p.as_str())
+            .unwrap_or_else(|| req.uri().path());
+        let scope = required_scope(req.method(), path);
+        api_token_claims(&state, scope, &token).await?
+    } else {
+        let claims = state.auth_service.validate_token(&token)?;
+        // Tokens of a logged out or revoked session stop working before they expire
+        let session_id = claims
+            .sid
+            .parse::<Thing>()
+            .map_err(|_| ApiError::Auth("Invalid session id".to_string()))?;
+        if !crate::services::session::is_session_active(&state.db, session_id).await? {
+            return Err(ApiError::Auth("Session has been revoked".to_string()));
+        }
+        claims
+    };
     // Check if user is banned
     let user = crate::services::auth::get_user_by_id(
         &state.db,
@@ -42,7 +117,7 @@ pub async fn auth_middleware(
 }
 
 pub async fn admin_middleware(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     req: Request,
     next: Next,
 ) -> Result<Response, ApiError> {
@@ -50,9 +125,23 @@ pub async fn admin_middleware(
         .extensions()
         .get::<Claims>()
         .ok_or_else(|| ApiError::Auth("Unauthorized".to_string()))?;
-    if claims.role != UserRole::Admin {
+    // Admin work always needs a login session, never an API token
+    if claims.role != UserRole::Admin || claims.scopes.is_some() {
         return Err(ApiError::Forbidden("Admin access required".to_string()));
     }
+    // The instance may insist the session was started with a second factor
+    if state.config.two_factor.require_for_admins {
+        let session_id = claims
+            .sid
+            .parse::<Thing>()
+            .map_err(|_| ApiError::Auth("Invalid session id".to_string()))?;
+        if !crate::services::session::is_two_factor_session(&state.db, session_id).await? {
+            return Err(ApiError::Forbidden(
+                "Admin access requires two-factor authentication, enable it from your profile"
+                    .to_string(),
+            ));
+        }
+    }
     Ok(next.run(req).await)
 }
 
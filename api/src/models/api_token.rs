@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::{Datetime, Thing};
+use validator::Validate;
+
+/// A personal token for scripts, allowed only what its scopes cover
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: Option<Thing>,
+    pub user_id: Thing,
+    pub name: String,
+    pub token_hash: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub expires_at: Option<Datetime>, // None never expires
+    pub last_used_at: Option<Datetime>,
+    pub created_at: Datetime,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ApiTokenScope {
+    #[serde(rename = "profile:read")]
+    ProfileRead,
+    #[serde(rename = "submissions:read")]
+    SubmissionsRead,
+    #[serde(rename = "submissions:write")]
+    SubmissionsWrite,
+    #[serde(rename = "tournaments:write")]
+    TournamentsWrite,
+    #[serde(rename = "matches:read")]
+    MatchesRead,
+}
+
+impl ApiTokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiTokenScope::ProfileRead => "profile:read",
+            ApiTokenScope::SubmissionsRead => "submissions:read",
+            ApiTokenScope::SubmissionsWrite => "submissions:write",
+            ApiTokenScope::TournamentsWrite => "tournaments:write",
+            ApiTokenScope::MatchesRead => "matches:read",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateApiTokenRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<ApiTokenScope>,
+    #[validate(range(min = 1, max = 365, message = "Expiry must be 1-365 days"))]
+    pub expires_in_days: Option<i64>, // None never expires
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiTokenResponse {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub expires_at: Option<Datetime>,
+    pub last_used_at: Option<Datetime>,
+    pub created_at: Datetime,
+}
+
+impl From<ApiToken> for ApiTokenResponse {
+    fn from(token: ApiToken) -> Self {
+        Self {
+            id: token.id.map(|t| t.to_string()).unwrap_or_default(),
+            name: token.name,
+            scopes: token.scopes,
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+/// A new token; the only time its secret is shown
+#[derive(Debug, Serialize)]
+pub struct CreatedApiTokenResponse {
+    pub token: String,
+    pub api_token: ApiTokenResponse,
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::{Datetime, Thing};
+
+/// One slot of an elimination bracket. Its winner (and, in the winners bracket
+/// of a double elimination, its loser) move on along the stored links.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BracketNode {
+    pub id: Option<Thing>,
+    pub tournament_id: Thing,
+    pub bracket: BracketSide,
+    pub round: u32,    // 1-based
+    pub position: u32, // 0-based index within the round
+    pub player1: Option<BracketEntrant>,
+    pub player2: Option<BracketEntrant>,
+    pub pending_feeds: u32, // Source nodes that have not resolved yet
+    pub winner_to: Option<BracketLink>,
+    pub loser_to: Option<BracketLink>,
+    pub match_id: Option<Thing>,
+    pub winner: Option<BracketEntrant>,
+    pub resolved: bool, // Also true for byes and skipped nodes, which have no match
+    pub created_at: Datetime,
+    pub updated_at: Datetime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BracketEntrant {
+    pub user_id: Thing,
+    pub submission_id: Thing,
+    pub seed: u32, // 1 is the strongest seed
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BracketLink {
+    pub node_id: Thing,
+    pub slot: u32, // 1 for player1, 2 for player2
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum BracketSide {
+    Winners,
+    Losers,
+    /// Round 1 is the grand final, round 2 the reset played only if the
+    /// losers bracket champion wins round 1
+    GrandFinal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BracketEntrantResponse {
+    pub user_id: String,
+    pub submission_id: String,
+    pub seed: u32,
+}
+
+impl From<BracketEntrant> for BracketEntrantResponse {
+    fn from(entrant: BracketEntrant) -> Self {
+        Self {
+            user_id: entrant.user_id.to_string(),
+            submission_id: entrant.submission_id.to_string(),
+            seed: entrant.seed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BracketNodeResponse {
+    pub id: String,
+    pub bracket: BracketSide,
+    pub round: u32,
+    pub position: u32,
+    pub player1: Option<BracketEntrantResponse>,
+    pub player2: Option<BracketEntrantResponse>,
+    pub winner_to: Option<String>,
+    pub loser_to: Option<String>,
+    pub match_id: Option<String>,
+    pub winner: Option<BracketEntrantResponse>,
+    pub resolved: bool,
+}
+
+impl From<BracketNode> for BracketNodeResponse {
+    fn from(node: BracketNode) -> Self {
+        Self {
+            id: node.id.map(|t| t.to_string()).unwrap_or_default(),
+            bracket: node.bracket,
+            round: node.round,
+            position: node.position,
+            player1: node.player1.map(Into::into),
+            player2: node.player2.map(Into::into),
+            winner_to: node.winner_to.map(|l| l.node_id.to_string()),
+            loser_to: node.loser_to.map(|l| l.node_id.to_string()),
+            match_id: node.match_id.map(|t| t.to_string()),
+            winner: node.winner.map(Into::into),
+            resolved: node.resolved,
+        }
+    }
+}
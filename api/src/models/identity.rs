@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::{Datetime, Thing};
+
+use super::user::OAuthProvider;
+
+/// An external account a user can sign in with. A user may link several.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserIdentity {
+    pub id: Option<Thing>,
+    pub user_id: Thing,
+    pub provider: OAuthProvider,
+    pub subject: String, // The provider's id for the account
+    pub email: Option<String>,
+    pub created_at: Datetime,
+    pub last_login_at: Option<Datetime>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserIdentityResponse {
+    pub id: String,
+    pub provider: OAuthProvider,
+    pub email: Option<String>,
+    pub created_at: Datetime,
+    pub last_login_at: Option<Datetime>,
+}
+
+impl From<UserIdentity> for UserIdentityResponse {
+    fn from(identity: UserIdentity) -> Self {
+        Self {
+            id: identity.id.map(|t| t.to_string()).unwrap_or_default(),
+            provider: identity.provider,
+            email: identity.email,
+            created_at: identity.created_at,
+            last_login_at: identity.last_login_at,
+        }
+    }
+}
+
+/// An OAuth flow waiting for the provider to redirect back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthState {
+    pub id: Option<Thing>,
+    pub state_hash: String,
+    pub provider: OAuthProvider,
+    pub nonce: String,
+    pub link_user_id: Option<Thing>, // Set when a signed-in user is linking a provider
+    pub expires_at: Datetime,
+}
+
+/// A sign-in option for the login page
+#[derive(Debug, Serialize)]
+pub struct OAuthProviderInfo {
+    pub id: OAuthProvider,
+    pub name: String,
+}
@@ -11,9 +11,30 @@ pub struct LeaderboardEntry {
     pub tournament_id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RatingLeaderboardEntry {
+    pub rank: u32,
+    pub user_id: String,
+    pub username: String,
+    pub location: String,
+    pub game_id: String,
+    pub rating: f64,
+    pub deviation: f64,
+    pub matches_played: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaderboardMode {
+    #[default]
+    Score, // Tournament participant scores
+    Rating, // Per-game Glicko-2 ratings, requires game_id
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LeaderboardQuery {
     pub limit: Option<u32>, // Top K players
     pub tournament_id: Option<String>,
     pub game_id: Option<String>,
+    pub mode: Option<LeaderboardMode>,
 }
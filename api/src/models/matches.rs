@@ -11,7 +11,6 @@ pub struct Match {
     pub participants: Vec<MatchParticipant>,
     pub metadata: Option<serde_json::Value>, // For game-specific replay data or logs
     pub room_id: Option<Thing>, // For interactive matches
-    pub game_event_source: Option<String>, // Game state history for reconnection
     pub judge_server_name: Option<String>, // Which judge server claimed this match
     pub created_at: Datetime,
     pub updated_at: Datetime,
@@ -28,7 +27,6 @@ pub struct MatchResponse {
     pub participants: Vec<MatchParticipantResponse>,
     pub metadata: Option<serde_json::Value>,
     pub room_id: Option<String>,
-    pub game_event_source: Option<String>,
     pub judge_server_name: Option<String>,
     pub created_at: Datetime,
     pub updated_at: Datetime,
@@ -46,7 +44,6 @@ impl From<Match> for MatchResponse {
             participants: match_data.participants.into_iter().map(Into::into).collect(),
             metadata: match_data.metadata,
             room_id: match_data.room_id.map(|t| t.to_string()),
-            game_event_source: match_data.game_event_source,
             judge_server_name: match_data.judge_server_name,
             created_at: match_data.created_at,
             updated_at: match_data.updated_at,
@@ -67,11 +64,29 @@ pub enum MatchStatus {
     Cancelled,
 }
 
+/// Why a bot got the score it did, as judged at the end of an automated match
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Accepted,
+    TimeLimitExceeded,
+    WrongAnswer,
+    RuntimeError,
+    MemoryLimitExceeded,
+    Killed, // See the participant's signal
+    SeccompViolation,
+    OutputLimitExceeded,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchParticipant {
     pub user_id: Thing,
     pub submission_id: Option<Thing>, // For automated matches
     pub score: Option<f64>,
+    #[serde(default)]
+    pub verdict: Option<Verdict>, // Set by the judge once the match is played
+    #[serde(default)]
+    pub signal: Option<i32>, // Signal that ended a killed bot
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -79,6 +94,8 @@ pub struct MatchParticipantResponse {
     pub user_id: String,
     pub submission_id: Option<String>,
     pub score: Option<f64>,
+    pub verdict: Option<Verdict>,
+    pub signal: Option<i32>,
 }
 
 impl From<MatchParticipant> for MatchParticipantResponse {
@@ -87,6 +104,41 @@ impl From<MatchParticipant> for MatchParticipantResponse {
             user_id: participant.user_id.to_string(),
             submission_id: participant.submission_id.map(|t| t.to_string()),
             score: participant.score,
+            verdict: participant.verdict,
+            signal: participant.signal,
+        }
+    }
+}
+
+/// What one bot wrote to stderr during a match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchLog {
+    pub id: Option<Thing>,
+    pub match_id: Thing,
+    pub submission_id: Thing,
+    pub user_id: Thing,
+    pub stderr: String,
+    pub truncated: bool, // Output went past the judge's cap
+    pub created_at: Datetime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchLogResponse {
+    pub submission_id: String,
+    pub user_id: String,
+    pub stderr: String,
+    pub truncated: bool,
+    pub created_at: Datetime,
+}
+
+impl From<MatchLog> for MatchLogResponse {
+    fn from(log: MatchLog) -> Self {
+        Self {
+            submission_id: log.submission_id.to_string(),
+            user_id: log.user_id.to_string(),
+            stderr: log.stderr,
+            truncated: log.truncated,
+            created_at: log.created_at,
         }
     }
 }
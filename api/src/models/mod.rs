@@ -1,15 +1,29 @@
+pub mod api_token;
+pub mod bracket;
 pub mod game;
 pub mod game_template;
+pub mod identity;
 pub mod leaderboard;
 pub mod matches;
+pub mod rating;
+pub mod session;
+pub mod standings;
 pub mod submission;
 pub mod tournament;
+pub mod two_factor;
 pub mod user;
 
+pub use api_token::*;
+pub use bracket::*;
 pub use game::*;
 pub use game_template::*;
+pub use identity::*;
 pub use leaderboard::*;
 pub use matches::*;
+pub use rating::*;
+pub use session::*;
+pub use standings::*;
 pub use submission::*;
 pub use tournament::*;
+pub use two_factor::*;
 pub use user::*;
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::{Datetime, Thing};
+
+/// A player's current Glicko-2 rating in one game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rating {
+    pub id: Option<Thing>,
+    pub user_id: Thing,
+    pub game_id: String,
+    pub rating: f64,
+    pub deviation: f64, // Rating deviation (RD), lower means more certain
+    pub volatility: f64,
+    pub matches_played: u32,
+    pub updated_at: Datetime,
+}
+
+/// Rating of a player right after a rated match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RatingHistoryEntry {
+    pub id: Option<Thing>,
+    pub user_id: Thing,
+    pub game_id: String,
+    pub match_id: Thing,
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+    pub created_at: Datetime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RatingHistoryResponse {
+    pub game_id: String,
+    pub match_id: String,
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+    pub created_at: Datetime,
+}
+
+impl From<RatingHistoryEntry> for RatingHistoryResponse {
+    fn from(entry: RatingHistoryEntry) -> Self {
+        Self {
+            game_id: entry.game_id,
+            match_id: entry.match_id.to_string(),
+            rating: entry.rating,
+            deviation: entry.deviation,
+            volatility: entry.volatility,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RatingHistoryQuery {
+    pub game_id: Option<String>,
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::{Datetime, Thing};
+
+/// A login, kept alive by rotating its refresh token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub id: Option<Thing>,
+    pub user_id: Thing,
+    pub refresh_token_hash: String,
+    pub previous_token_hash: Option<String>, // Replaced token, kept to spot reuse
+    pub expires_at: Datetime,
+    pub revoked_at: Option<Datetime>,
+    pub created_at: Datetime,
+    pub last_used_at: Datetime,
+    pub two_factor: bool, // Started with a second factor
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::{Datetime, Thing};
+
+/// Final standings of a completed tournament, written once and never updated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandingsSnapshot {
+    pub id: Option<Thing>,
+    pub tournament_id: Thing,
+    pub entries: Vec<StandingEntry>,
+    pub created_at: Datetime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandingEntry {
+    pub rank: u32,
+    pub user_id: Thing,
+    pub username: String, // Copied so later renames don't rewrite history
+    pub score: f64,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub opponent_score: f64, // Sum of the final scores of everyone faced
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StandingsSnapshotResponse {
+    pub tournament_id: String,
+    pub entries: Vec<StandingEntryResponse>,
+    pub created_at: Datetime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StandingEntryResponse {
+    pub rank: u32,
+    pub user_id: String,
+    pub username: String,
+    pub score: f64,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub opponent_score: f64,
+}
+
+impl From<StandingEntry> for StandingEntryResponse {
+    fn from(entry: StandingEntry) -> Self {
+        Self {
+            rank: entry.rank,
+            user_id: entry.user_id.to_string(),
+            username: entry.username,
+            score: entry.score,
+            wins: entry.wins,
+            draws: entry.draws,
+            losses: entry.losses,
+            opponent_score: entry.opponent_score,
+        }
+    }
+}
+
+impl From<StandingsSnapshot> for StandingsSnapshotResponse {
+    fn from(snapshot: StandingsSnapshot) -> Self {
+        Self {
+            tournament_id: snapshot.tournament_id.to_string(),
+            entries: snapshot.entries.into_iter().map(Into::into).collect(),
+            created_at: snapshot.created_at,
+        }
+    }
+}
@@ -15,9 +15,26 @@ pub struct Submission {
     pub status: SubmissionStatus,
     pub error_message: Option<String>,
     pub compiled_binary_path: Option<String>, // Path to compiled binary
+    pub self_test: Option<SelfTestReport>, // Games against the reference bots, once compiled
     pub created_at: Datetime,
 }
 
+/// How a compiled submission fared against the judge's reference bots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub games: Vec<SelfTestGame>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestGame {
+    pub opponent: String,
+    pub seat: usize, // Seat the submission played in
+    pub verdict: String,
+    pub score: Option<i32>,
+    pub trace: Option<String>, // Protocol trace, only kept for failed games
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum SubmissionStatus {
@@ -41,6 +58,8 @@ pub struct SubmissionResponse {
     pub tournament_id: String,
     pub language: ProgrammingLanguage,
     pub status: SubmissionStatus,
+    pub error_message: Option<String>, // Compiler output or self-test trace when the submission failed
+    pub self_test: Option<SelfTestReport>,
     pub created_at: Datetime,
 }
 
@@ -51,6 +70,8 @@ impl From<Submission> for SubmissionResponse {
             tournament_id: submission.tournament_id.to_string(),
             language: submission.language,
             status: submission.status,
+            error_message: submission.error_message,
+            self_test: submission.self_test,
             created_at: submission.created_at,
         }
     }
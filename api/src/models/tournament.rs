@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::{Datetime, Thing};
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tournament {
@@ -12,13 +12,37 @@ pub struct Tournament {
     pub status: TournamentStatus,
     pub min_players: u32,
     pub max_players: u32,
+    pub registration_time: Option<Datetime>, // When a scheduled tournament opens registration
     pub start_time: Option<Datetime>,
     pub end_time: Option<Datetime>,
     pub match_generation_type: MatchGenerationType,
+    pub swiss_rounds: Option<u32>, // Swiss only, filled in at start when left empty
+    #[serde(default)]
+    pub current_round: u32, // Rounds generated so far (Swiss)
+    #[serde(default)]
+    pub resource_limits: Option<ResourceLimitOverrides>, // Game's bot limits when unset
     pub created_at: Datetime,
     pub updated_at: Datetime,
 }
 
+/// Sandbox limits a tournament sets for its bots instead of the game's own.
+/// Every field is optional and falls back to the game.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate)]
+pub struct ResourceLimitOverrides {
+    #[validate(range(min = 0.1, max = 4.0, message = "CPU limit must be 0.1-4 CPUs"))]
+    pub cpu_limit: Option<f64>,
+    #[validate(range(min = 16, max = 2048, message = "Memory limit must be 16-2048 MB"))]
+    pub memory_limit_mb: Option<u64>,
+    #[validate(range(min = 1, max = 256, message = "Process limit must be 1-256"))]
+    pub max_pids: Option<u32>,
+    #[validate(range(
+        min = 1000,
+        max = 3600000,
+        message = "Wall-clock budget must be 1 second to 1 hour"
+    ))]
+    pub wall_clock_ms: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct TournamentResponse {
     pub id: String,
@@ -28,9 +52,13 @@ pub struct TournamentResponse {
     pub status: TournamentStatus,
     pub min_players: u32,
     pub max_players: u32,
+    pub registration_time: Option<Datetime>,
     pub start_time: Option<Datetime>,
     pub end_time: Option<Datetime>,
     pub match_generation_type: MatchGenerationType,
+    pub swiss_rounds: Option<u32>,
+    pub current_round: u32,
+    pub resource_limits: Option<ResourceLimitOverrides>,
     pub created_at: Datetime,
     pub updated_at: Datetime,
 }
@@ -45,9 +73,13 @@ impl From<Tournament> for TournamentResponse {
             status: tournament.status,
             min_players: tournament.min_players,
             max_players: tournament.max_players,
+            registration_time: tournament.registration_time,
             start_time: tournament.start_time,
             end_time: tournament.end_time,
             match_generation_type: tournament.match_generation_type,
+            swiss_rounds: tournament.swiss_rounds,
+            current_round: tournament.current_round,
+            resource_limits: tournament.resource_limits,
             created_at: tournament.created_at,
             updated_at: tournament.updated_at,
         }
@@ -67,6 +99,12 @@ pub enum MatchGenerationType {
     SingleElimination,
     /// Double elimination bracket
     DoubleElimination,
+    /// Swiss system: a fixed number of rounds, pairing players with similar scores
+    /// For N players: N / 2 matches per round
+    Swiss,
+    /// Players split into tables filling the game's seats, one match per table
+    /// For N players and K seats: ceil(N / K) matches
+    FreeForAll,
 }
 
 impl Default for MatchGenerationType {
@@ -94,10 +132,14 @@ pub struct TournamentParticipant {
     pub submission_id: Option<Thing>, // Latest submission for this tournament
     pub score: f64,
     pub rank: Option<u32>,
+    #[serde(default)]
+    pub byes: u32, // Swiss rounds sat out
     pub joined_at: Datetime,
 }
 
 #[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_swiss_rounds"))]
+#[validate(schema(function = "validate_schedule"))]
 pub struct CreateTournamentRequest {
     pub game_id: String, // Will be converted to Thing
     #[validate(length(
@@ -112,9 +154,50 @@ pub struct CreateTournamentRequest {
     pub min_players: u32,
     #[validate(range(min = 2, max = 500, message = "Maximum players must be 2-500"))]
     pub max_players: u32,
+    pub registration_time: Option<DateTime<Utc>>,
     pub start_time: Option<DateTime<Utc>>,
     pub end_time: Option<DateTime<Utc>>,
     pub match_generation_type: Option<MatchGenerationType>, // Defaults to AllVsAll if not provided
+    #[validate(range(min = 1, max = 50, message = "Swiss rounds must be 1-50"))]
+    pub swiss_rounds: Option<u32>,
+    #[validate(nested)]
+    pub resource_limits: Option<ResourceLimitOverrides>,
+}
+
+/// A round count only means something for Swiss tournaments
+fn validate_swiss_rounds(request: &CreateTournamentRequest) -> Result<(), ValidationError> {
+    if request.swiss_rounds.is_some()
+        && request.match_generation_type != Some(MatchGenerationType::Swiss)
+    {
+        return Err(ValidationError::new("swiss_rounds")
+            .with_message("Swiss rounds can only be set for Swiss tournaments".into()));
+    }
+    Ok(())
+}
+
+fn validate_schedule(request: &CreateTournamentRequest) -> Result<(), ValidationError> {
+    if !schedule_in_order(request.registration_time, request.start_time, request.end_time) {
+        return Err(ValidationError::new("schedule").with_message(SCHEDULE_ORDER_MESSAGE.into()));
+    }
+    Ok(())
+}
+
+pub const SCHEDULE_ORDER_MESSAGE: &str =
+    "Registration must open no later than the start time, which must not be after the end time";
+
+/// Registration opens before the tournament starts, and it starts before it
+/// ends. Times that are not set are skipped, so registration still has to open
+/// before the end time when there is no start time.
+pub fn schedule_in_order(
+    registration_time: Option<DateTime<Utc>>,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+) -> bool {
+    let times: Vec<DateTime<Utc>> = [registration_time, start_time, end_time]
+        .into_iter()
+        .flatten()
+        .collect();
+    times.windows(2).all(|pair| pair[0] <= pair[1])
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -128,8 +211,11 @@ pub struct UpdateTournamentRequest {
     #[validate(length(min = 1, max = 1000, message = "Description must be 1-1000 characters"))]
     pub description: Option<String>,
     pub status: Option<TournamentStatus>,
+    pub registration_time: Option<DateTime<Utc>>,
     pub start_time: Option<DateTime<Utc>>,
     pub end_time: Option<DateTime<Utc>>,
+    #[validate(nested)]
+    pub resource_limits: Option<ResourceLimitOverrides>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::{Datetime, Thing};
+use validator::Validate;
+
+use super::user::AuthResponse;
+
+/// A user's authenticator. It only counts once a code from it was confirmed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserTotp {
+    pub id: Option<Thing>,
+    pub user_id: Thing,
+    pub secret: String,               // Base32, as shown to authenticator apps
+    pub enabled_at: Option<Datetime>, // None while enrollment is pending
+    pub last_used_step: Option<i64>,  // Time step of the last accepted code, so none is used twice
+    pub recovery_code_hashes: Vec<String>,
+    #[serde(default)]
+    pub failed_attempts: i64, // Wrong codes at login since the last accepted one
+    #[serde(default)]
+    pub locked_until: Option<Datetime>, // Logins are held back until then after too many wrong codes
+    pub created_at: Datetime,
+}
+
+/// A password login waiting for its second factor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwoFactorChallenge {
+    pub id: Option<Thing>,
+    pub token_hash: String,
+    pub user_id: Thing,
+    pub attempts: i64,
+    pub expires_at: Datetime,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct TwoFactorCodeRequest {
+    #[validate(length(min = 1, max = 32, message = "Code is required"))]
+    pub code: String, // Authenticator code, or a recovery code
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct TwoFactorLoginRequest {
+    #[validate(length(min = 1, message = "Two-factor token is required"))]
+    pub two_factor_token: String,
+    #[validate(length(min = 1, max = 32, message = "Code is required"))]
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorSetupResponse {
+    pub secret: String, // For typing into an authenticator by hand
+    pub otpauth_uri: String,
+    pub qr_code_svg: String, // The URI as a scannable QR code
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorStatusResponse {
+    pub enabled: bool,
+    pub required: bool, // Admins cannot turn it off when the instance requires it
+    pub recovery_codes_remaining: usize,
+}
+
+/// Shown once; only their hashes are kept
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorChallengeResponse {
+    pub two_factor_required: bool, // Always true, tells this apart from a session
+    pub two_factor_token: String,
+    pub expires_in: i64,
+}
+
+/// A session, or the challenge to answer before one is started
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Session(AuthResponse),
+    TwoFactorRequired(TwoFactorChallengeResponse),
+}
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use surrealdb::sql::{Datetime, Thing};
 use validator::Validate;
 
+use super::api_token::ApiTokenScope;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub id: Option<Thing>,
@@ -10,7 +12,7 @@ pub struct User {
     pub password_hash: Option<String>, // None for OAuth users
     pub role: UserRole,
     pub location: String, // ISO country code (e.g., "US")
-    pub oauth_provider: Option<OAuthProvider>,
+    pub oauth_provider: Option<OAuthProvider>, // Legacy single link, see UserIdentity
     pub oauth_id: Option<String>,
     pub is_banned: bool,
     pub ban_reason: Option<String>,
@@ -18,6 +20,8 @@ pub struct User {
     pub updated_at: Datetime,
     pub password_reset_token: Option<String>,
     pub password_reset_expires: Option<Datetime>,
+    pub email_verified: bool,
+    pub email_verification_sent_at: Option<Datetime>, // Last verification link, for rate limiting
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -27,10 +31,35 @@ pub enum UserRole {
     Player,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum OAuthProvider {
     Google,
+    Github,
+    Oidc, // The configured OpenID Connect issuer
+}
+
+impl OAuthProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "google",
+            OAuthProvider::Github => "github",
+            OAuthProvider::Oidc => "oidc",
+        }
+    }
+}
+
+impl std::str::FromStr for OAuthProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "google" => Ok(OAuthProvider::Google),
+            "github" => Ok(OAuthProvider::Github),
+            "oidc" => Ok(OAuthProvider::Oidc),
+            _ => Err(format!("Unknown sign-in provider: {}", s)),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -66,9 +95,23 @@ pub struct ConfirmResetPasswordRequest {
     pub new_password: String,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct RefreshTokenRequest {
+    #[validate(length(min = 1, message = "Refresh token is required"))]
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct VerifyEmailRequest {
+    #[validate(length(min = 1, message = "Verification token is required"))]
+    pub token: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
-    pub token: String,
+    pub token: String, // Short-lived access token
+    pub refresh_token: String,
+    pub expires_in: i64, // Seconds until the access token expires
     pub user: UserInfo,
 }
 
@@ -80,6 +123,7 @@ pub struct UserInfo {
     pub role: UserRole,
     pub location: String,
     pub is_banned: bool,
+    pub email_verified: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,6 +131,20 @@ pub struct Claims {
     pub sub: String, // user id
     pub email: String,
     pub role: UserRole,
+    pub sid: String, // session the token was issued for, or the API token used
+    pub exp: usize,
+    pub iat: usize,
+    /// What an API token may do; None for a login session, which may do anything
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<ApiTokenScope>>,
+}
+
+/// Signed into email verification links
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailVerificationClaims {
+    pub sub: String, // user id
+    pub email: String, // the address the link was sent to
+    pub purpose: String, // always "verify_email", so no other token passes as one
     pub exp: usize,
     pub iat: usize,
 }
@@ -40,6 +40,9 @@ pub fn create_router(state: AppState) -> Router {
         .route("/health", get(|| async { "OK" }))
         .route("/api/auth/register", post(handlers::register))
         .route("/api/auth/login", post(handlers::login))
+        .route("/api/auth/login/2fa", post(handlers::login_two_factor))
+        .route("/api/auth/refresh", post(handlers::refresh_token))
+        .route("/api/auth/verify-email", post(handlers::verify_email))
         .route(
             "/api/auth/reset-password",
             post(handlers::request_password_reset),
@@ -48,8 +51,9 @@ pub fn create_router(state: AppState) -> Router {
             "/api/auth/confirm-reset",
             post(handlers::confirm_password_reset),
         )
-        .route("/api/auth/google", get(handlers::google_login))
-        .route("/api/auth/google/callback", get(handlers::google_callback))
+        .route("/api/auth/providers", get(handlers::list_oauth_providers))
+        .route("/api/auth/{provider}", get(handlers::oauth_login))
+        .route("/api/auth/{provider}/callback", get(handlers::oauth_callback))
         .route("/api/games", get(handlers::list_games))
         .route("/api/games/{id}", get(handlers::get_game))
         .route("/api/tournaments", get(handlers::list_tournaments))
@@ -58,13 +62,54 @@ pub fn create_router(state: AppState) -> Router {
             "/api/tournaments/{id}/participants",
             get(handlers::get_tournament_participants),
         )
+        .route(
+            "/api/tournaments/{id}/bracket",
+            get(handlers::get_tournament_bracket),
+        )
+        .route(
+            "/api/tournaments/{id}/standings",
+            get(handlers::get_tournament_standings),
+        )
         .route("/api/matches", get(handlers::list_matches))
         .route("/api/matches/{id}", get(handlers::get_match))
-        .route("/api/leaderboard", get(handlers::get_leaderboard));
+        .route("/api/matches/{id}/replay", get(handlers::get_match_replay))
+        .route("/api/leaderboard", get(handlers::get_leaderboard))
+        .route(
+            "/api/users/{id}/ratings",
+            get(handlers::get_user_rating_history),
+        );
     // Protected routes (require authentication)
     let protected_routes = Router::new()
+        .route("/api/auth/logout", post(handlers::logout))
+        .route("/api/auth/logout-all", post(handlers::logout_all))
+        .route(
+            "/api/auth/resend-verification",
+            post(handlers::resend_verification),
+        )
         .route("/api/users/profile", get(handlers::get_profile))
         .route("/api/users/location", patch(handlers::update_location))
+        .route(
+            "/api/users/tokens",
+            get(handlers::list_api_tokens).post(handlers::create_api_token),
+        )
+        .route(
+            "/api/users/tokens/{id}",
+            delete(handlers::delete_api_token),
+        )
+        .route("/api/users/2fa", get(handlers::get_two_factor_status))
+        .route("/api/users/2fa/setup", post(handlers::setup_two_factor))
+        .route("/api/users/2fa/enable", post(handlers::enable_two_factor))
+        .route("/api/users/2fa/disable", post(handlers::disable_two_factor))
+        .route(
+            "/api/users/2fa/recovery-codes",
+            post(handlers::regenerate_recovery_codes),
+        )
+        .route("/api/auth/{provider}/link", post(handlers::link_identity))
+        .route("/api/users/identities", get(handlers::list_identities))
+        .route(
+            "/api/users/identities/{id}",
+            delete(handlers::unlink_identity),
+        )
         .route(
             "/api/tournaments/{id}/join",
             post(handlers::join_tournament),
@@ -76,6 +121,7 @@ pub fn create_router(state: AppState) -> Router {
         .route("/api/submissions", post(handlers::create_submission))
         .route("/api/submissions", get(handlers::list_submissions))
         .route("/api/submissions/{id}", get(handlers::get_submission))
+        .route("/api/matches/{id}/logs", get(handlers::get_match_logs))
         .route_layer(axum_middleware::from_fn_with_state(
             state.clone(),
             middleware::auth_middleware,
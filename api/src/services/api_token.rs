@@ -0,0 +1,99 @@
+use crate::{
+    db::Database,
+    error::{ApiError, ApiResult},
+    models::{ApiToken, ApiTokenScope},
+    services::AuthService,
+};
+use chrono::{Duration, Utc};
+use surrealdb::sql::{Datetime, Thing};
+
+/// Tokens a user may hold at once
+const MAX_TOKENS_PER_USER: usize = 20;
+
+/// Create a token for a user, returning it with its secret
+pub async fn create_api_token(
+    db: &Database,
+    auth_service: &AuthService,
+    user_id: Thing,
+    name: String,
+    scopes: Vec<ApiTokenScope>,
+    expires_in_days: Option<i64>,
+) -> ApiResult<(ApiToken, String)> {
+    if list_api_tokens(db, user_id.clone()).await?.len() >= MAX_TOKENS_PER_USER {
+        return Err(ApiError::BadRequest(format!(
+            "A user can hold at most {} API tokens",
+            MAX_TOKENS_PER_USER
+        )));
+    }
+    let mut unique_scopes = Vec::with_capacity(scopes.len());
+    for scope in scopes {
+        if !unique_scopes.contains(&scope) {
+            unique_scopes.push(scope);
+        }
+    }
+    let secret = auth_service.generate_api_token();
+    let now = Utc::now();
+    let token = ApiToken {
+        id: None,
+        user_id,
+        name,
+        token_hash: auth_service.hash_api_token(&secret),
+        scopes: unique_scopes,
+        expires_at: expires_in_days.map(|days| (now + Duration::days(days)).into()),
+        last_used_at: None,
+        created_at: now.into(),
+    };
+    let created: Option<ApiToken> = db.create("api_token").content(token).await?;
+    let token =
+        created.ok_or_else(|| ApiError::Internal("Failed to create API token".to_string()))?;
+    Ok((token, secret))
+}
+
+pub async fn list_api_tokens(db: &Database, user_id: Thing) -> ApiResult<Vec<ApiToken>> {
+    let mut result = db
+        .query("SELECT * FROM api_token WHERE user_id = $user_id ORDER BY created_at DESC")
+        .bind(("user_id", user_id))
+        .await?;
+    let tokens: Vec<ApiToken> = result.take(0)?;
+    Ok(tokens)
+}
+
+/// Revoke one of a user's tokens
+pub async fn delete_api_token(db: &Database, user_id: Thing, token_id: Thing) -> ApiResult<()> {
+    if token_id.tb != "api_token" {
+        return Err(ApiError::BadRequest("Invalid API token id".to_string()));
+    }
+    let mut result = db
+        .query("DELETE $token_id WHERE user_id = $user_id RETURN BEFORE")
+        .bind(("token_id", token_id))
+        .bind(("user_id", user_id))
+        .await?;
+    let deleted: Vec<ApiToken> = result.take(0)?;
+    if deleted.is_empty() {
+        return Err(ApiError::NotFound("API token not found".to_string()));
+    }
+    Ok(())
+}
+
+/// Look up the token a request was made with and mark it used
+pub async fn authenticate_api_token(
+    db: &Database,
+    auth_service: &AuthService,
+    secret: &str,
+) -> ApiResult<ApiToken> {
+    let now: Datetime = Utc::now().into();
+    let mut result = db
+        .query(
+            "UPDATE api_token SET last_used_at = $now
+             WHERE token_hash = $token_hash AND (expires_at = NONE OR expires_at > $now)
+             RETURN AFTER",
+        )
+        .bind(("token_hash", auth_service.hash_api_token(secret)))
+        .bind(("now", now))
+        .await?;
+    let tokens: Vec<ApiToken> = result.take(0)?;
+    tokens
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::Auth("Invalid or expired API token".to_string()))
+}
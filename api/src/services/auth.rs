@@ -1,7 +1,7 @@
 use crate::{
     db::Database,
     error::{ApiError, ApiResult},
-    models::{Claims, User, UserInfo},
+    models::{Claims, EmailVerificationClaims, User, UserInfo},
 };
 use argon2::{
     Argon2,
@@ -12,6 +12,9 @@ use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode}
 use sha2::{Digest, Sha256};
 use surrealdb::sql::Thing;
 
+pub const API_TOKEN_PREFIX: &str = "axt_";
+const EMAIL_VERIFICATION_PURPOSE: &str = "verify_email";
+
 pub struct AuthService {
     jwt_secret: String,
     jwt_expiration: i64,
@@ -39,7 +42,12 @@ impl AuthService {
             .verify_password(password.as_bytes(), &parsed_hash)
             .is_ok())
     }
-    pub fn generate_token(&self, user: &User) -> ApiResult<String> {
+    /// Seconds an access token stays valid
+    pub fn access_token_ttl(&self) -> i64 {
+        self.jwt_expiration
+    }
+    /// Short-lived access token for a user's session
+    pub fn generate_token(&self, user: &User, session_id: &Thing) -> ApiResult<String> {
         let now = Utc::now().timestamp() as usize;
         let user_id = user
             .id
@@ -50,8 +58,10 @@ impl AuthService {
             sub: user_id,
             email: user.email.clone(),
             role: user.role.clone(),
+            sid: session_id.to_string(),
             exp: (now as i64 + self.jwt_expiration) as usize,
             iat: now,
+            scopes: None,
         };
         encode(
             &Header::default(),
@@ -69,17 +79,96 @@ impl AuthService {
         .map(|data| data.claims)
         .map_err(ApiError::from)
     }
+    /// Signed link token proving the holder received mail at the user's address
+    pub fn generate_email_verification_token(
+        &self,
+        user: &User,
+        ttl_seconds: i64,
+    ) -> ApiResult<String> {
+        let now = Utc::now().timestamp() as usize;
+        let user_id = user
+            .id
+            .as_ref()
+            .ok_or_else(|| ApiError::Internal("User ID is missing".to_string()))?
+            .to_string();
+        let claims = EmailVerificationClaims {
+            sub: user_id,
+            email: user.email.clone(),
+            purpose: EMAIL_VERIFICATION_PURPOSE.to_string(),
+            exp: (now as i64 + ttl_seconds) as usize,
+            iat: now,
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.jwt_secret.as_bytes()),
+        )
+        .map_err(ApiError::from)
+    }
+    pub fn validate_email_verification_token(
+        &self,
+        token: &str,
+    ) -> ApiResult<EmailVerificationClaims> {
+        let claims = decode::<EmailVerificationClaims>(
+            token,
+            &DecodingKey::from_secret(self.jwt_secret.as_bytes()),
+            &Validation::default(),
+        )
+        .map(|data| data.claims)
+        .map_err(|_| ApiError::BadRequest("Invalid or expired verification link".to_string()))?;
+        if claims.purpose != EMAIL_VERIFICATION_PURPOSE {
+            return Err(ApiError::BadRequest(
+                "Invalid or expired verification link".to_string(),
+            ));
+        }
+        Ok(claims)
+    }
     pub fn generate_reset_token(&self) -> String {
-        use rand::{Rng, distr::Alphanumeric};
-        rand::rng()
-            .sample_iter(&Alphanumeric)
-            .take(32)
-            .map(char::from)
-            .collect()
+        random_token(32)
     }
     pub fn hash_reset_token(&self, token: &str) -> String {
-        let digest = Sha256::digest(token.as_bytes());
-        format!("{:x}", digest)
+        hash_token(token)
+    }
+    pub fn generate_refresh_token(&self) -> String {
+        random_token(64)
+    }
+    pub fn hash_refresh_token(&self, token: &str) -> String {
+        hash_token(token)
+    }
+    /// Personal API token, prefixed so it can be told apart from a JWT
+    pub fn generate_api_token(&self) -> String {
+        format!("{}{}", API_TOKEN_PREFIX, random_token(48))
+    }
+    pub fn hash_api_token(&self, token: &str) -> String {
+        hash_token(token)
+    }
+    /// State and nonce tying an OAuth redirect to the flow that started it
+    pub fn generate_oauth_state(&self) -> String {
+        random_token(32)
+    }
+    pub fn hash_oauth_state(&self, state: &str) -> String {
+        hash_token(state)
+    }
+    /// Names a password login that still has to pass its second factor
+    pub fn generate_two_factor_token(&self) -> String {
+        random_token(32)
+    }
+    pub fn hash_two_factor_token(&self, token: &str) -> String {
+        hash_token(token)
+    }
+    /// Single-use fallback for a lost authenticator, split in two to be easier to copy
+    pub fn generate_recovery_code(&self) -> String {
+        let code = random_token(10).to_lowercase();
+        format!("{}-{}", &code[..5], &code[5..])
+    }
+    /// Case, dashes and spaces do not matter when a recovery code is typed back
+    pub fn hash_recovery_code(&self, code: &str) -> String {
+        let normalized: String = code
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        hash_token(&normalized)
     }
     pub fn user_to_info(user: &User) -> ApiResult<UserInfo> {
         let id = user
@@ -94,10 +183,26 @@ impl AuthService {
             role: user.role.clone(),
             location: user.location.clone(),
             is_banned: user.is_banned,
+            email_verified: user.email_verified,
         })
     }
 }
 
+fn random_token(len: usize) -> String {
+    use rand::{Rng, distr::Alphanumeric};
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Tokens are only stored hashed, a leaked table cannot be replayed
+fn hash_token(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    format!("{:x}", digest)
+}
+
 pub async fn get_user_by_id(db: &Database, user_id: Thing) -> ApiResult<User> {
     let key = (user_id.tb.as_str(), user_id.id.to_string());
     let user: Option<User> = db.select(key).await?;
@@ -0,0 +1,517 @@
+use crate::{
+    db::Database,
+    error::{ApiError, ApiResult},
+    models::{
+        bracket::{BracketEntrant, BracketLink, BracketNode, BracketSide},
+        matches::{Match, MatchParticipant, MatchStatus},
+        tournament::{Tournament, TournamentParticipant},
+    },
+    services::{matches::get_match, tournament::get_tournament},
+};
+use serde::Deserialize;
+use std::{cmp::Ordering, collections::HashMap};
+use surrealdb::sql::{Datetime, Thing};
+
+type NodeKey = (BracketSide, u32, u32);
+
+/// Build a seeded single elimination bracket and schedule every first round
+/// match. Later rounds are filled in by `advance_bracket` as results arrive.
+pub async fn generate_single_elimination(
+    db: &Database,
+    tournament: &Tournament,
+    participants: &[TournamentParticipant],
+) -> ApiResult<usize> {
+    build_bracket(db, tournament, participants, false).await
+}
+
+/// Build a seeded double elimination bracket: a winners bracket, a losers
+/// bracket fed by first losses, and a grand final with a possible reset.
+pub async fn generate_double_elimination(
+    db: &Database,
+    tournament: &Tournament,
+    participants: &[TournamentParticipant],
+) -> ApiResult<usize> {
+    build_bracket(db, tournament, participants, true).await
+}
+
+/// Record winners of completed bracket matches and schedule the matches they unlock.
+/// Safe to call repeatedly; returns the number of new matches created.
+pub async fn advance_bracket(db: &Database, tournament_id: Thing) -> ApiResult<usize> {
+    let tournament = get_tournament(db, tournament_id.clone()).await?;
+    let mut result = db
+        .query(
+            "SELECT * FROM bracket_node
+             WHERE tournament_id = $tournament_id AND match_id != NONE AND resolved = false
+             ORDER BY round ASC, position ASC",
+        )
+        .bind(("tournament_id", tournament_id))
+        .await?;
+    let nodes: Vec<BracketNode> = result.take(0)?;
+
+    let mut matches_created = 0;
+    for node in nodes {
+        let Some(match_id) = node.match_id.clone() else {
+            continue;
+        };
+        let match_data = get_match(db, match_id).await?;
+        if match_data.status != MatchStatus::Completed {
+            continue;
+        }
+        let Some((winner, loser)) = pick_winner(&node, &match_data) else {
+            continue;
+        };
+        for ready in finish_node(db, &node, Some(winner), Some(loser)).await? {
+            matches_created += resolve_node(db, &tournament, ready).await?;
+        }
+    }
+    Ok(matches_created)
+}
+
+/// Whether the bracket can no longer change: its last node has been decided, or
+/// a failed match has stalled it and every other result is already recorded.
+pub async fn is_bracket_settled(db: &Database, tournament_id: Thing) -> ApiResult<bool> {
+    let mut result = db
+        .query(
+            "SELECT VALUE resolved FROM bracket_node
+             WHERE tournament_id = $tournament_id AND winner_to = NONE;
+             SELECT VALUE id FROM bracket_node
+             WHERE tournament_id = $tournament_id AND resolved = false
+               AND match_id.status = 'completed';
+             SELECT VALUE id FROM bracket_node
+             WHERE tournament_id = $tournament_id AND resolved = false
+               AND match_id.status = 'failed';",
+        )
+        .bind(("tournament_id", tournament_id))
+        .await?;
+    let terminal: Vec<bool> = result.take(0)?;
+    if !terminal.is_empty() && terminal.iter().all(|resolved| *resolved) {
+        return Ok(true);
+    }
+    let awaiting: Vec<Thing> = result.take(1)?;
+    let stalled: Vec<Thing> = result.take(2)?;
+    Ok(awaiting.is_empty() && !stalled.is_empty())
+}
+
+pub async fn get_bracket(db: &Database, tournament_id: Thing) -> ApiResult<Vec<BracketNode>> {
+    get_tournament(db, tournament_id.clone()).await?;
+    let mut result = db
+        .query(
+            "SELECT * FROM bracket_node
+             WHERE tournament_id = $tournament_id
+             ORDER BY bracket ASC, round ASC, position ASC",
+        )
+        .bind(("tournament_id", tournament_id))
+        .await?;
+    let nodes: Vec<BracketNode> = result.take(0)?;
+    Ok(nodes)
+}
+
+async fn build_bracket(
+    db: &Database,
+    tournament: &Tournament,
+    participants: &[TournamentParticipant],
+    double: bool,
+) -> ApiResult<usize> {
+    if participants.len() < 2 {
+        return Err(ApiError::BadRequest(
+            "Elimination brackets need at least 2 participants with accepted submissions".to_string(),
+        ));
+    }
+    let tournament_id = tournament
+        .id
+        .clone()
+        .ok_or_else(|| ApiError::Internal("Tournament missing id".to_string()))?;
+    let entrants = seed_entrants(db, tournament, participants).await?;
+    let size = entrants.len().next_power_of_two();
+    let rounds = size.trailing_zeros();
+    let losers_rounds = if double { 2 * (rounds - 1) } else { 0 };
+
+    // Nodes are created from the final backwards so every link target already exists
+    let mut ids: HashMap<NodeKey, Thing> = HashMap::new();
+    if double {
+        let reset = new_node(&tournament_id, BracketSide::GrandFinal, 2, 0);
+        insert_node(db, &mut ids, reset).await?;
+        let mut grand_final = new_node(&tournament_id, BracketSide::GrandFinal, 1, 0);
+        grand_final.winner_to = link(&ids, (BracketSide::GrandFinal, 2, 0), 1);
+        grand_final.loser_to = link(&ids, (BracketSide::GrandFinal, 2, 0), 2);
+        insert_node(db, &mut ids, grand_final).await?;
+
+        // Odd losers rounds pair up survivors, even rounds take in the
+        // losers dropping down from the next winners round
+        for round in (1..=losers_rounds).rev() {
+            let count = (size >> (round.div_ceil(2) + 1)) as u32;
+            for position in 0..count {
+                let mut node = new_node(&tournament_id, BracketSide::Losers, round, position);
+                node.winner_to = if round == losers_rounds {
+                    link(&ids, (BracketSide::GrandFinal, 1, 0), 2)
+                } else if round % 2 == 1 {
+                    link(&ids, (BracketSide::Losers, round + 1, position), 1)
+                } else {
+                    link(
+                        &ids,
+                        (BracketSide::Losers, round + 1, position / 2),
+                        position % 2 + 1,
+                    )
+                };
+                insert_node(db, &mut ids, node).await?;
+            }
+        }
+    }
+
+    let order = seed_order(size);
+    let mut first_round = Vec::new();
+    for round in (1..=rounds).rev() {
+        let count = (size >> round) as u32;
+        for position in 0..count {
+            let mut node = new_node(&tournament_id, BracketSide::Winners, round, position);
+            node.winner_to = if round < rounds {
+                link(
+                    &ids,
+                    (BracketSide::Winners, round + 1, position / 2),
+                    position % 2 + 1,
+                )
+            } else if double {
+                link(&ids, (BracketSide::GrandFinal, 1, 0), 1)
+            } else {
+                None
+            };
+            if double {
+                node.loser_to = if rounds == 1 {
+                    link(&ids, (BracketSide::GrandFinal, 1, 0), 2)
+                } else if round == 1 {
+                    link(
+                        &ids,
+                        (BracketSide::Losers, 1, position / 2),
+                        position % 2 + 1,
+                    )
+                } else {
+                    // Alternate the drop order so players don't meet again right away
+                    let target = if (round - 1) % 2 == 1 {
+                        count - 1 - position
+                    } else {
+                        position
+                    };
+                    link(&ids, (BracketSide::Losers, 2 * (round - 1), target), 2)
+                };
+            }
+            if round == 1 {
+                let index = position as usize * 2;
+                node.player1 = entrants.get(order[index] - 1).cloned();
+                node.player2 = entrants.get(order[index + 1] - 1).cloned();
+                node.pending_feeds = 0;
+            }
+            let created = insert_node(db, &mut ids, node).await?;
+            if round == 1 {
+                first_round.push(created);
+            }
+        }
+    }
+
+    let mut matches_created = 0;
+    for node in first_round {
+        matches_created += resolve_node(db, tournament, node).await?;
+    }
+    Ok(matches_created)
+}
+
+/// Seeds come from the score each player earned in earlier tournaments of the
+/// same game; ties go to whoever joined first.
+async fn seed_entrants(
+    db: &Database,
+    tournament: &Tournament,
+    participants: &[TournamentParticipant],
+) -> ApiResult<Vec<BracketEntrant>> {
+    #[derive(Deserialize)]
+    struct PriorScore {
+        user_id: Thing,
+        score: f64,
+    }
+    let user_ids: Vec<Thing> = participants.iter().map(|p| p.user_id.clone()).collect();
+    let mut result = db
+        .query(
+            "SELECT user_id, math::sum(score) AS score FROM tournament_participant
+             WHERE user_id IN $user_ids
+               AND tournament_id != $tournament_id
+               AND tournament_id.game_id = $game_id
+             GROUP BY user_id",
+        )
+        .bind(("user_ids", user_ids))
+        .bind(("tournament_id", tournament.id.clone()))
+        .bind(("game_id", tournament.game_id.clone()))
+        .await?;
+    let prior: Vec<PriorScore> = result.take(0)?;
+    let prior: HashMap<String, f64> = prior
+        .into_iter()
+        .map(|p| (p.user_id.to_string(), p.score))
+        .collect();
+
+    let mut ranked: Vec<&TournamentParticipant> = participants.iter().collect();
+    ranked.sort_by(|a, b| {
+        let score_a = prior.get(&a.user_id.to_string()).copied().unwrap_or(0.0);
+        let score_b = prior.get(&b.user_id.to_string()).copied().unwrap_or(0.0);
+        score_b
+            .partial_cmp(&score_a)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.joined_at.cmp(&b.joined_at))
+    });
+    ranked
+        .into_iter()
+        .enumerate()
+        .map(|(idx, p)| {
+            let submission_id = p
+                .submission_id
+                .clone()
+                .ok_or_else(|| ApiError::Internal("Participant missing submission".to_string()))?;
+            Ok(BracketEntrant {
+                user_id: p.user_id.clone(),
+                submission_id,
+                seed: idx as u32 + 1,
+            })
+        })
+        .collect()
+}
+
+/// Standard bracket order for `size` slots, so that seed 1 and seed 2 can
+/// only meet in the final. For 8 slots: 1, 8, 4, 5, 2, 7, 3, 6.
+fn seed_order(size: usize) -> Vec<usize> {
+    let mut order = vec![1];
+    while order.len() < size {
+        let sum = order.len() * 2 + 1;
+        order = order.iter().flat_map(|&seed| [seed, sum - seed]).collect();
+    }
+    order
+}
+
+/// Higher score wins; a draw goes to the better seed. Returns (winner, loser).
+fn pick_winner(node: &BracketNode, match_data: &Match) -> Option<(BracketEntrant, BracketEntrant)> {
+    let player1 = node.player1.clone()?;
+    let player2 = node.player2.clone()?;
+    let score_of = |entrant: &BracketEntrant| {
+        match_data
+            .participants
+            .iter()
+            .find(|p| p.user_id == entrant.user_id)
+            .and_then(|p| p.score)
+            .unwrap_or(0.0)
+    };
+    let (score1, score2) = (score_of(&player1), score_of(&player2));
+    if score1 > score2 || (score1 == score2 && player1.seed < player2.seed) {
+        Some((player1, player2))
+    } else {
+        Some((player2, player1))
+    }
+}
+
+fn new_node(tournament_id: &Thing, bracket: BracketSide, round: u32, position: u32) -> BracketNode {
+    BracketNode {
+        id: None,
+        tournament_id: tournament_id.clone(),
+        bracket,
+        round,
+        position,
+        player1: None,
+        player2: None,
+        pending_feeds: 2,
+        winner_to: None,
+        loser_to: None,
+        match_id: None,
+        winner: None,
+        resolved: false,
+        created_at: Datetime::default(),
+        updated_at: Datetime::default(),
+    }
+}
+
+async fn insert_node(
+    db: &Database,
+    ids: &mut HashMap<NodeKey, Thing>,
+    node: BracketNode,
+) -> ApiResult<BracketNode> {
+    let key = (node.bracket, node.round, node.position);
+    let created: Option<BracketNode> = db.create("bracket_node").content(node).await?;
+    let created =
+        created.ok_or_else(|| ApiError::Internal("Failed to create bracket node".to_string()))?;
+    if let Some(id) = created.id.clone() {
+        ids.insert(key, id);
+    }
+    Ok(created)
+}
+
+fn link(ids: &HashMap<NodeKey, Thing>, key: NodeKey, slot: u32) -> Option<BracketLink> {
+    ids.get(&key).map(|node_id| BracketLink {
+        node_id: node_id.clone(),
+        slot,
+    })
+}
+
+/// Act on a node whose sources have all resolved: schedule its match, or pass
+/// a lone player through as a bye. Returns the number of matches created.
+async fn resolve_node(
+    db: &Database,
+    tournament: &Tournament,
+    node: BracketNode,
+) -> ApiResult<usize> {
+    let mut ready = vec![node];
+    let mut matches_created = 0;
+    while let Some(node) = ready.pop() {
+        match (node.player1.clone(), node.player2.clone()) {
+            (Some(_), Some(_)) => {
+                matches_created += start_node_match(db, tournament, &node).await?;
+            }
+            (Some(entrant), None) | (None, Some(entrant)) => {
+                ready.extend(finish_node(db, &node, Some(entrant), None).await?);
+            }
+            (None, None) => {
+                ready.extend(finish_node(db, &node, None, None).await?);
+            }
+        }
+    }
+    Ok(matches_created)
+}
+
+/// Create the match for a node once both of its slots are filled.
+async fn start_node_match(
+    db: &Database,
+    tournament: &Tournament,
+    node: &BracketNode,
+) -> ApiResult<usize> {
+    let (Some(player1), Some(player2)) = (&node.player1, &node.player2) else {
+        return Ok(0);
+    };
+    if node.match_id.is_some() || node.resolved {
+        return Ok(0);
+    }
+    let node_id = node
+        .id
+        .clone()
+        .ok_or_else(|| ApiError::Internal("Bracket node missing id".to_string()))?;
+    let match_data = Match {
+        id: None,
+        tournament_id: tournament.id.clone(),
+        game_id: tournament.game_id.clone(),
+        status: MatchStatus::Pending,
+        participants: [player1, player2]
+            .into_iter()
+            .map(|entrant| MatchParticipant {
+                user_id: entrant.user_id.clone(),
+                submission_id: Some(entrant.submission_id.clone()),
+                score: None,
+                verdict: None,
+                signal: None,
+            })
+            .collect(),
+        metadata: None,
+        room_id: None,
+        judge_server_name: None,
+        created_at: Datetime::default(),
+        updated_at: Datetime::default(),
+        started_at: None,
+        completed_at: None,
+    };
+    let created: Option<Match> = db.create("match").content(match_data).await?;
+    let match_id = created
+        .and_then(|m| m.id)
+        .ok_or_else(|| ApiError::Internal("Failed to create match".to_string()))?;
+
+    let mut claimed = db
+        .query(
+            "UPDATE $node_id SET match_id = $match_id, updated_at = time::now()
+             WHERE match_id = NONE
+             RETURN AFTER",
+        )
+        .bind(("node_id", node_id))
+        .bind(("match_id", match_id.clone()))
+        .await?;
+    let claimed_rows: Vec<BracketNode> = claimed.take(0)?;
+    if claimed_rows.is_empty() {
+        // Another worker scheduled this node first
+        let key = (match_id.tb.as_str(), match_id.id.to_string());
+        let _: Option<Match> = db.delete(key).await?;
+        return Ok(0);
+    }
+    Ok(1)
+}
+
+/// Mark a node resolved and pass its winner and loser along their links.
+/// Returns the linked nodes that became ready as a result.
+async fn finish_node(
+    db: &Database,
+    node: &BracketNode,
+    winner: Option<BracketEntrant>,
+    loser: Option<BracketEntrant>,
+) -> ApiResult<Vec<BracketNode>> {
+    let node_id = node
+        .id
+        .clone()
+        .ok_or_else(|| ApiError::Internal("Bracket node missing id".to_string()))?;
+    let mut claimed = db
+        .query(
+            "UPDATE $node_id SET resolved = true, winner = $winner, updated_at = time::now()
+             WHERE resolved = false
+             RETURN AFTER",
+        )
+        .bind(("node_id", node_id))
+        .bind(("winner", winner.clone()))
+        .await?;
+    let claimed_rows: Vec<BracketNode> = claimed.take(0)?;
+    if claimed_rows.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // The winners bracket champion taking the grand final ends the
+    // tournament, so the reset is resolved without any players
+    let no_reset = node.bracket == BracketSide::GrandFinal
+        && node.round == 1
+        && winner.is_some()
+        && winner == node.player1;
+    let (winner, loser) = if no_reset {
+        (None, None)
+    } else {
+        (winner, loser)
+    };
+
+    let mut ready = Vec::new();
+    if let Some(target) = &node.winner_to {
+        ready.extend(feed(db, target, winner).await?);
+    }
+    if let Some(target) = &node.loser_to {
+        ready.extend(feed(db, target, loser).await?);
+    }
+    Ok(ready)
+}
+
+/// Fill one slot of a node (or record that it stays empty). Returns the node
+/// once all of its sources have resolved.
+async fn feed(
+    db: &Database,
+    target: &BracketLink,
+    entrant: Option<BracketEntrant>,
+) -> ApiResult<Option<BracketNode>> {
+    let slot = if target.slot == 1 {
+        "player1"
+    } else {
+        "player2"
+    };
+    let mut result = match entrant {
+        Some(entrant) => {
+            db.query(format!(
+                "UPDATE $node_id SET {} = $entrant, pending_feeds -= 1, updated_at = time::now()
+                 RETURN AFTER",
+                slot
+            ))
+            .bind(("node_id", target.node_id.clone()))
+            .bind(("entrant", entrant))
+            .await?
+        }
+        None => {
+            db.query(
+                "UPDATE $node_id SET pending_feeds -= 1, updated_at = time::now()
+                 RETURN AFTER",
+            )
+            .bind(("node_id", target.node_id.clone()))
+            .await?
+        }
+    };
+    let rows: Vec<BracketNode> = result.take(0)?;
+    Ok(rows.into_iter().find(|n| n.pending_feeds == 0))
+}
@@ -0,0 +1,224 @@
+use crate::{
+    db::Database,
+    error::{ApiError, ApiResult},
+    models::{
+        standings::StandingsSnapshot,
+        tournament::{MatchGenerationType, Tournament, TournamentStatus},
+    },
+    services::{
+        bracket::is_bracket_settled, standings::freeze_standings, tournament::get_tournament,
+    },
+};
+use std::time::Duration;
+use surrealdb::sql::{Datetime, Thing};
+use tracing::{error, info};
+
+/// Complete a running tournament once nothing is left to play: flip the
+/// status, freeze the final ranks and store the standings snapshot.
+/// Returns the snapshot when the tournament was completed by this call.
+pub async fn complete_tournament_if_finished(
+    db: &Database,
+    tournament_id: Thing,
+) -> ApiResult<Option<StandingsSnapshot>> {
+    let tournament = get_tournament(db, tournament_id).await?;
+    if tournament.status != TournamentStatus::Running {
+        return Ok(None);
+    }
+    if !is_finished(db, &tournament).await? {
+        return Ok(None);
+    }
+
+    close_tournament(db, &tournament).await
+}
+
+/// Mark a running tournament completed, then freeze its final standings and
+/// store the snapshot, whether or not everything was played. The status is
+/// claimed first, so progression stops touching the ranks and only one caller
+/// freezes them. Returns None when the tournament was no longer running.
+pub async fn close_tournament(
+    db: &Database,
+    tournament: &Tournament,
+) -> ApiResult<Option<StandingsSnapshot>> {
+    let tournament_id = tournament
+        .id
+        .clone()
+        .ok_or_else(|| ApiError::Internal("Tournament missing id".to_string()))?;
+    let mut claimed = db
+        .query(
+            "UPDATE $tournament_id
+             SET status = 'completed', updated_at = time::now()
+             WHERE status = 'running'
+             RETURN AFTER",
+        )
+        .bind(("tournament_id", tournament_id))
+        .await?;
+    let claimed_rows: Vec<Tournament> = claimed.take(0)?;
+    if claimed_rows.is_empty() {
+        return Ok(None);
+    }
+    store_final_standings(db, tournament).await.map(Some)
+}
+
+/// Freeze the final ranks of a completed tournament and store the snapshot.
+/// A snapshot that already exists is returned as is, so a pass that failed
+/// after the status was claimed can simply be run again.
+pub async fn store_final_standings(
+    db: &Database,
+    tournament: &Tournament,
+) -> ApiResult<StandingsSnapshot> {
+    let tournament_id = tournament
+        .id
+        .clone()
+        .ok_or_else(|| ApiError::Internal("Tournament missing id".to_string()))?;
+    if let Some(existing) = find_standings_snapshot(db, tournament_id.clone()).await? {
+        return Ok(existing);
+    }
+    let entries = freeze_standings(db, tournament).await?;
+    let snapshot = StandingsSnapshot {
+        id: None,
+        tournament_id: tournament_id.clone(),
+        entries,
+        created_at: Datetime::default(),
+    };
+    // The unique index on tournament_id keeps a second snapshot from being written
+    let created: Result<Option<StandingsSnapshot>, _> =
+        db.create("standings_snapshot").content(snapshot).await;
+    match created {
+        Ok(Some(created)) => Ok(created),
+        Ok(None) => Err(ApiError::Internal(
+            "Failed to create standings snapshot".to_string(),
+        )),
+        Err(err) => find_standings_snapshot(db, tournament_id)
+            .await?
+            .ok_or_else(|| err.into()),
+    }
+}
+
+async fn find_standings_snapshot(
+    db: &Database,
+    tournament_id: Thing,
+) -> ApiResult<Option<StandingsSnapshot>> {
+    let mut result = db
+        .query("SELECT * FROM standings_snapshot WHERE tournament_id = $tournament_id LIMIT 1")
+        .bind(("tournament_id", tournament_id))
+        .await?;
+    let snapshots: Vec<StandingsSnapshot> = result.take(0)?;
+    Ok(snapshots.into_iter().next())
+}
+
+pub async fn get_standings_snapshot(
+    db: &Database,
+    tournament_id: Thing,
+) -> ApiResult<StandingsSnapshot> {
+    find_standings_snapshot(db, tournament_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound("Standings are available once the tournament completes".to_string())
+        })
+}
+
+/// Every match is completed or failed and the format has nothing left to generate
+async fn is_finished(db: &Database, tournament: &Tournament) -> ApiResult<bool> {
+    let tournament_id = tournament
+        .id
+        .clone()
+        .ok_or_else(|| ApiError::Internal("Tournament missing id".to_string()))?;
+    let mut result = db
+        .query(
+            "SELECT VALUE id FROM match
+             WHERE tournament_id = $tournament_id AND status NOT IN ['completed', 'failed']
+             LIMIT 1;
+             SELECT VALUE id FROM match WHERE tournament_id = $tournament_id LIMIT 1;",
+        )
+        .bind(("tournament_id", tournament_id.clone()))
+        .await?;
+    let unfinished: Vec<Thing> = result.take(0)?;
+    let any_match: Vec<Thing> = result.take(1)?;
+    if !unfinished.is_empty() || any_match.is_empty() {
+        return Ok(false);
+    }
+
+    match tournament.match_generation_type {
+        MatchGenerationType::Swiss => {
+            Ok(tournament.current_round >= tournament.swiss_rounds.unwrap_or(0))
+        }
+        MatchGenerationType::SingleElimination | MatchGenerationType::DoubleElimination => {
+            is_bracket_settled(db, tournament_id).await
+        }
+        MatchGenerationType::AllVsAll
+        | MatchGenerationType::RoundRobin
+        | MatchGenerationType::FreeForAll => Ok(true),
+    }
+}
+
+pub struct CompletionService {
+    db: Database,
+}
+
+impl CompletionService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn run(self) {
+        info!("Completion service started");
+        loop {
+            let running_result = self
+                .db
+                .query("SELECT VALUE id FROM tournament WHERE status = 'running'")
+                .await;
+            let tournament_ids: Vec<Thing> = match running_result {
+                Ok(mut response) => response.take(0).unwrap_or_else(|err| {
+                    error!("Failed to read running tournaments: {}", err);
+                    Vec::new()
+                }),
+                Err(err) => {
+                    error!("Failed to load running tournaments: {}", err);
+                    Vec::new()
+                }
+            };
+
+            // Tournaments whose snapshot failed after the status was claimed
+            let unfrozen_result = self
+                .db
+                .query(
+                    "SELECT * FROM tournament
+                     WHERE status = 'completed'
+                       AND id NOT IN (SELECT VALUE tournament_id FROM standings_snapshot)",
+                )
+                .await;
+            let unfrozen: Vec<Tournament> = match unfrozen_result {
+                Ok(mut response) => response.take(0).unwrap_or_else(|err| {
+                    error!("Failed to read completed tournaments: {}", err);
+                    Vec::new()
+                }),
+                Err(err) => {
+                    error!("Failed to load completed tournaments: {}", err);
+                    Vec::new()
+                }
+            };
+            for tournament in unfrozen {
+                if let Err(err) = store_final_standings(&self.db, &tournament).await {
+                    error!(
+                        "Failed to store final standings of {:?}: {}",
+                        tournament.id, err
+                    );
+                }
+            }
+
+            for tournament_id in tournament_ids {
+                match complete_tournament_if_finished(&self.db, tournament_id.clone()).await {
+                    Ok(Some(_)) => {
+                        info!("Tournament {} completed", tournament_id);
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        error!("Failed to complete tournament {}: {}", tournament_id, err);
+                    }
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        }
+    }
+}
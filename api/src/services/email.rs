@@ -17,6 +17,29 @@ impl EmailService {
         Self { config }
     }
     pub async fn send_password_reset(&self, to_email: &str, reset_token: &str) -> ApiResult<()> {
+        let base_url = self.config.frontend_url.trim_end_matches('/');
+        let reset_link = format!("{}/reset-password/confirm?token={}", base_url, reset_token);
+        let email_body = format!(
+            "Hello,\n\nYou requested a password reset. Click the link below to reset your password:\n\n{}\n\nThis link will expire in 1 hour.\n\nIf you didn't request this, please ignore this email.\n\nBest regards,\nAxel Tournament Team",
+            reset_link
+        );
+        self.send(to_email, "Password Reset Request", email_body)
+    }
+    pub async fn send_email_verification(
+        &self,
+        to_email: &str,
+        verification_token: &str,
+    ) -> ApiResult<()> {
+        let base_url = self.config.frontend_url.trim_end_matches('/');
+        let verification_link = format!("{}/verify-email?token={}", base_url, verification_token);
+        let hours = (self.config.verification_ttl_seconds / 3600).max(1);
+        let email_body = format!(
+            "Hello,\n\nPlease confirm your email address by clicking the link below:\n\n{}\n\nThis link will expire in {} hour(s). Until then you cannot join tournaments or submit bots.\n\nIf you didn't create an account, please ignore this email.\n\nBest regards,\nAxel Tournament Team",
+            verification_link, hours
+        );
+        self.send(to_email, "Confirm Your Email Address", email_body)
+    }
+    fn send(&self, to_email: &str, subject: &str, email_body: String) -> ApiResult<()> {
         if self.config.smtp_username.trim().is_empty()
             || self.config.smtp_password.trim().is_empty()
         {
@@ -24,12 +47,6 @@ impl EmailService {
                 "SMTP credentials are not configured".to_string(),
             ));
         }
-        let base_url = self.config.frontend_url.trim_end_matches('/');
-        let reset_link = format!("{}/reset-password/confirm?token={}", base_url, reset_token);
-        let email_body = format!(
-            "Hello,\n\nYou requested a password reset. Click the link below to reset your password:\n\n{}\n\nThis link will expire in 1 hour.\n\nIf you didn't request this, please ignore this email.\n\nBest regards,\nAxel Tournament Team",
-            reset_link
-        );
         let email = Message::builder()
             .from(
                 self.config
@@ -40,7 +57,7 @@ impl EmailService {
             .to(to_email.parse().map_err(|e| {
                 ApiError::Internal(format!("Invalid recipient address: {}", e))
             })?)
-            .subject("Password Reset Request")
+            .subject(subject)
             .header(ContentType::TEXT_PLAIN)
             .body(email_body)
             .map_err(|e| ApiError::Internal(format!("Failed to build email: {}", e)))?;
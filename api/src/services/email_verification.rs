@@ -0,0 +1,103 @@
+use crate::{
+    db::Database,
+    error::{ApiError, ApiResult},
+    models::User,
+    services::{self, AuthService, EmailService},
+};
+use chrono::{Duration, Utc};
+use surrealdb::sql::{Datetime, Thing};
+
+/// Email a verification link to a user, at most once per `resend_seconds`
+pub async fn send_verification_email(
+    db: &Database,
+    auth_service: &AuthService,
+    email_service: &EmailService,
+    user: &User,
+    ttl_seconds: i64,
+    resend_seconds: i64,
+) -> ApiResult<()> {
+    if user.email_verified {
+        return Err(ApiError::BadRequest(
+            "Email is already verified".to_string(),
+        ));
+    }
+    let user_id = user
+        .id
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("User ID is missing".to_string()))?
+        .clone();
+    // Claim the send before sending, so concurrent requests cannot both get through
+    let now = Utc::now();
+    let mut result = db
+        .query(
+            "UPDATE $user_id SET email_verification_sent_at = $now
+             WHERE email_verification_sent_at = NONE OR email_verification_sent_at < $cutoff
+             RETURN AFTER",
+        )
+        .bind(("user_id", user_id))
+        .bind(("now", Datetime::from(now)))
+        .bind((
+            "cutoff",
+            Datetime::from(now - Duration::seconds(resend_seconds)),
+        ))
+        .await?;
+    let claimed: Vec<User> = result.take(0)?;
+    if claimed.is_empty() {
+        return Err(ApiError::TooManyRequests(format!(
+            "A verification email was sent recently, try again in {} seconds",
+            resend_seconds
+        )));
+    }
+    let token = auth_service.generate_email_verification_token(user, ttl_seconds)?;
+    email_service
+        .send_email_verification(&user.email, &token)
+        .await
+}
+
+/// Mark the address a verification link was sent to as verified
+pub async fn verify_email(
+    db: &Database,
+    auth_service: &AuthService,
+    token: &str,
+) -> ApiResult<User> {
+    let claims = auth_service.validate_email_verification_token(token)?;
+    let user_id = claims
+        .sub
+        .parse::<Thing>()
+        .map_err(|_| ApiError::BadRequest("Invalid or expired verification link".to_string()))?;
+    let user = services::auth::get_user_by_id(db, user_id.clone()).await?;
+    // A link sent before the address changed says nothing about the new one
+    if user.email != claims.email {
+        return Err(ApiError::BadRequest(
+            "Invalid or expired verification link".to_string(),
+        ));
+    }
+    if user.email_verified {
+        return Ok(user);
+    }
+    mark_email_verified(db, user_id).await
+}
+
+pub async fn mark_email_verified(db: &Database, user_id: Thing) -> ApiResult<User> {
+    let mut result = db
+        .query("UPDATE $user_id SET email_verified = true, updated_at = $now RETURN AFTER")
+        .bind(("user_id", user_id))
+        .bind(("now", Datetime::default()))
+        .await?;
+    let users: Vec<User> = result.take(0)?;
+    users
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::NotFound("User not found".to_string()))
+}
+
+/// Tournaments and submissions are closed to accounts nobody has shown to own
+pub async fn require_verified_email(db: &Database, user_id: Thing) -> ApiResult<()> {
+    let user = services::auth::get_user_by_id(db, user_id).await?;
+    if !user.email_verified {
+        return Err(ApiError::Forbidden(
+            "Verify your email address first".to_string(),
+        ));
+    }
+    Ok(())
+}
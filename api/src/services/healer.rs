@@ -38,6 +38,19 @@ impl HealerService {
                 }
             }
 
+            // Hand back submissions whose compile never finished (judge died mid-compile).
+            // A live judge refreshes compile_started_at every minute, however long the self-test runs.
+            let recompile_result = self
+                .db
+                .query("UPDATE submission SET status = 'pending', compile_started_at = NONE WHERE status = 'compiling' AND compile_started_at < time::now() - 5m")
+                .await;
+            match recompile_result {
+                Ok(_) => {}
+                Err(err) => {
+                    error!("Failed to re-queue stale compiling submissions: {}", err);
+                }
+            }
+
             // Clean up stale rooms (no activity for more than 30 minutes)
             let cleanup_rooms_result = self
                 .db
@@ -0,0 +1,285 @@
+use crate::{
+    db::Database,
+    error::{ApiError, ApiResult},
+    models::{OAuthProvider, OAuthState, User, UserIdentity},
+    services::{self, AuthService, OAuthClient, OAuthIdentity},
+};
+use chrono::{Duration, Utc};
+use rand::{Rng, rng};
+use surrealdb::sql::{Datetime, Thing};
+
+/// Remember a flow before redirecting to the provider, returning its state and nonce
+pub async fn create_oauth_state(
+    db: &Database,
+    auth_service: &AuthService,
+    provider: OAuthProvider,
+    link_user_id: Option<Thing>,
+    ttl_seconds: i64,
+) -> ApiResult<(String, String)> {
+    let now = Utc::now();
+    // Abandoned flows are never consumed, clear them out as new ones start
+    db.query("DELETE oauth_state WHERE expires_at < $now")
+        .bind(("now", Datetime::from(now)))
+        .await?;
+    let state = auth_service.generate_oauth_state();
+    let nonce = auth_service.generate_oauth_state();
+    let record = OAuthState {
+        id: None,
+        state_hash: auth_service.hash_oauth_state(&state),
+        provider,
+        nonce: nonce.clone(),
+        link_user_id,
+        expires_at: (now + Duration::seconds(ttl_seconds.max(60))).into(),
+    };
+    let created: Option<OAuthState> = db.create("oauth_state").content(record).await?;
+    created.ok_or_else(|| ApiError::Internal("Failed to create OAuth state".to_string()))?;
+    Ok((state, nonce))
+}
+
+/// Take the flow a callback belongs to; each state works once
+pub async fn consume_oauth_state(
+    db: &Database,
+    auth_service: &AuthService,
+    provider: OAuthProvider,
+    state: &str,
+) -> ApiResult<OAuthState> {
+    let mut result = db
+        .query("DELETE oauth_state WHERE state_hash = $state_hash RETURN BEFORE")
+        .bind(("state_hash", auth_service.hash_oauth_state(state)))
+        .await?;
+    let states: Vec<OAuthState> = result.take(0)?;
+    let record = states
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::Auth("Invalid OAuth state".to_string()))?;
+    let now: Datetime = Utc::now().into();
+    if record.provider != provider || record.expires_at < now {
+        return Err(ApiError::Auth("Invalid or expired OAuth state".to_string()));
+    }
+    Ok(record)
+}
+
+pub async fn get_identity(
+    db: &Database,
+    provider: OAuthProvider,
+    subject: &str,
+) -> ApiResult<Option<UserIdentity>> {
+    let mut result = db
+        .query("SELECT * FROM user_identity WHERE provider = $provider AND subject = $subject")
+        .bind(("provider", provider))
+        .bind(("subject", subject.to_string()))
+        .await?;
+    let identities: Vec<UserIdentity> = result.take(0)?;
+    Ok(identities.into_iter().next())
+}
+
+pub async fn list_identities(db: &Database, user_id: Thing) -> ApiResult<Vec<UserIdentity>> {
+    let user = services::auth::get_user_by_id(db, user_id.clone()).await?;
+    migrate_legacy_identity(db, user).await?;
+    let mut result = db
+        .query("SELECT * FROM user_identity WHERE user_id = $user_id ORDER BY created_at")
+        .bind(("user_id", user_id))
+        .await?;
+    let identities: Vec<UserIdentity> = result.take(0)?;
+    Ok(identities)
+}
+
+/// Attach a provider account to a user
+pub async fn link_identity(
+    db: &Database,
+    user_id: Thing,
+    identity: &OAuthIdentity,
+) -> ApiResult<UserIdentity> {
+    if let Some(existing) = get_identity(db, identity.provider, &identity.subject).await? {
+        if existing.user_id == user_id {
+            return Ok(existing);
+        }
+        return Err(ApiError::Conflict(format!(
+            "This {} account is already linked to another user",
+            identity.provider.as_str()
+        )));
+    }
+    let mut result = db
+        .query("SELECT * FROM user_identity WHERE user_id = $user_id AND provider = $provider")
+        .bind(("user_id", user_id.clone()))
+        .bind(("provider", identity.provider))
+        .await?;
+    let same_provider: Vec<UserIdentity> = result.take(0)?;
+    if !same_provider.is_empty() {
+        return Err(ApiError::Conflict(format!(
+            "Another {} account is already linked, unlink it first",
+            identity.provider.as_str()
+        )));
+    }
+    let record = UserIdentity {
+        id: None,
+        user_id,
+        provider: identity.provider,
+        subject: identity.subject.clone(),
+        email: identity.email.clone(),
+        created_at: Datetime::default(),
+        last_login_at: None,
+    };
+    let created: Option<UserIdentity> = db.create("user_identity").content(record).await?;
+    created.ok_or_else(|| ApiError::Internal("Failed to link identity".to_string()))
+}
+
+/// Detach a provider account, as long as the user can still sign in some other way
+pub async fn unlink_identity(db: &Database, user_id: Thing, identity_id: Thing) -> ApiResult<()> {
+    if identity_id.tb != "user_identity" {
+        return Err(ApiError::BadRequest("Invalid identity id".to_string()));
+    }
+    let user = services::auth::get_user_by_id(db, user_id.clone()).await?;
+    let has_password = user.password_hash.is_some();
+    let identities = list_identities(db, user_id.clone()).await?;
+    if !identities
+        .iter()
+        .any(|i| i.id.as_ref() == Some(&identity_id))
+    {
+        return Err(ApiError::NotFound("Identity not found".to_string()));
+    }
+    if !has_password && identities.len() <= 1 {
+        return Err(ApiError::BadRequest(
+            "Cannot unlink the only way to sign in; set a password first".to_string(),
+        ));
+    }
+    db.query("DELETE $identity_id WHERE user_id = $user_id")
+        .bind(("identity_id", identity_id))
+        .bind(("user_id", user_id))
+        .await?;
+    Ok(())
+}
+
+/// Find or create the user a provider account signs in as
+pub async fn sign_in_with_identity(
+    db: &Database,
+    client: &OAuthClient,
+    identity: &OAuthIdentity,
+    default_location: String,
+) -> ApiResult<User> {
+    if let Some(existing) = get_identity(db, identity.provider, &identity.subject).await? {
+        db.query("UPDATE $identity_id SET last_login_at = $now")
+            .bind(("identity_id", existing.id))
+            .bind(("now", Datetime::default()))
+            .await?;
+        return services::auth::get_user_by_id(db, existing.user_id).await;
+    }
+    // Accounts linked before identities had their own table
+    if let Some(user) =
+        services::auth::get_user_by_oauth(db, identity.provider.as_str(), &identity.subject).await?
+    {
+        return migrate_legacy_identity(db, user).await;
+    }
+    let email = match identity.email.as_deref() {
+        Some(email) if identity.email_verified && !email.trim().is_empty() => email,
+        _ => {
+            return Err(ApiError::Forbidden(format!(
+                "Your {} account has no verified email",
+                identity.provider.as_str()
+            )));
+        }
+    };
+    let mut user = match services::auth::get_user_by_email(db, email).await? {
+        Some(_) if !client.trusts_email() => {
+            return Err(ApiError::Conflict(format!(
+                "Email already registered; sign in and link {} from your profile",
+                identity.provider.as_str()
+            )));
+        }
+        Some(user) => user,
+        None => {
+            let username = allocate_username(db, identity.name.as_deref(), email).await?;
+            services::user::create_user(
+                db,
+                email.to_string(),
+                username,
+                None,
+                default_location,
+                None,
+                None,
+            )
+            .await?
+        }
+    };
+    let user_id = user
+        .id
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("User ID is missing".to_string()))?
+        .clone();
+    link_identity(db, user_id.clone(), identity).await?;
+    // The provider vouches for the address. Whoever registered it without
+    // verifying may not own it, so their password and sessions stop working.
+    if !user.email_verified {
+        if user.password_hash.is_some() {
+            user.password_hash = None;
+            services::user::update_user(db, user_id.clone(), user).await?;
+            services::session::revoke_user_sessions(db, user_id.clone()).await?;
+        }
+        user = services::email_verification::mark_email_verified(db, user_id).await?;
+    }
+    Ok(user)
+}
+
+/// Move a link stored on the user itself into the identity table
+async fn migrate_legacy_identity(db: &Database, mut user: User) -> ApiResult<User> {
+    let (Some(provider), Some(subject)) = (user.oauth_provider, user.oauth_id.clone()) else {
+        return Ok(user);
+    };
+    let user_id = user
+        .id
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("User ID is missing".to_string()))?
+        .clone();
+    let identity = OAuthIdentity {
+        provider,
+        subject,
+        email: Some(user.email.clone()),
+        email_verified: true,
+        name: None,
+    };
+    link_identity(db, user_id.clone(), &identity).await?;
+    user.oauth_provider = None;
+    user.oauth_id = None;
+    services::user::update_user(db, user_id, user).await
+}
+
+async fn allocate_username(db: &Database, name: Option<&str>, email: &str) -> ApiResult<String> {
+    let base_username = normalize_username(name.unwrap_or(""), email);
+    let mut username = base_username.clone();
+    let mut attempts = 0;
+    while services::auth::get_user_by_username(db, &username)
+        .await?
+        .is_some()
+    {
+        if attempts >= 5 {
+            return Err(ApiError::Conflict(
+                "Unable to allocate unique username".to_string(),
+            ));
+        }
+        let suffix = rng().random_range(1000..=9999);
+        username = format!("{base_username}-{suffix}");
+        username.truncate(50);
+        attempts += 1;
+    }
+    Ok(username)
+}
+
+fn normalize_username(name: &str, email: &str) -> String {
+    let base = if name.trim().is_empty() {
+        email.split('@').next().unwrap_or("player")
+    } else {
+        name
+    };
+    let mut normalized: String = base
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .collect();
+    if normalized.is_empty() {
+        normalized = "player".to_string();
+    }
+    if normalized.len() < 3 {
+        normalized.push_str("player");
+    }
+    normalized.truncate(50);
+    normalized
+}
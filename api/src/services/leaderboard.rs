@@ -1,4 +1,8 @@
-use crate::{db::Database, error::ApiResult, models::LeaderboardEntry};
+use crate::{
+    db::Database,
+    error::{ApiError, ApiResult},
+    models::{LeaderboardEntry, RatingLeaderboardEntry, game::find_game_by_id},
+};
 use surrealdb::sql::Thing;
 
 pub async fn get_leaderboard(
@@ -70,3 +74,52 @@ pub async fn get_leaderboard(
         .collect();
     Ok(entries)
 }
+
+/// Players of a game ordered by their Glicko-2 rating
+pub async fn get_rating_leaderboard(
+    db: &Database,
+    limit: u32,
+    game_id: String,
+) -> ApiResult<Vec<RatingLeaderboardEntry>> {
+    find_game_by_id(&game_id).ok_or_else(|| ApiError::NotFound("Game not found".to_string()))?;
+    let limit = limit.min(1000); // Cap at 1000
+    let mut response = db
+        .query(
+            "SELECT user_id, game_id, rating, deviation, matches_played,
+                    user_id.username AS username,
+                    user_id.location AS location
+             FROM rating
+             WHERE game_id = $game_id
+             ORDER BY rating DESC
+             LIMIT $limit",
+        )
+        .bind(("game_id", game_id))
+        .bind(("limit", limit))
+        .await?;
+    #[derive(serde::Deserialize)]
+    struct RawEntry {
+        user_id: Thing,
+        game_id: String,
+        rating: f64,
+        deviation: f64,
+        matches_played: u32,
+        username: Option<String>,
+        location: Option<String>,
+    }
+    let raw_entries: Vec<RawEntry> = response.take(0)?;
+    let entries = raw_entries
+        .into_iter()
+        .enumerate()
+        .map(|(idx, entry)| RatingLeaderboardEntry {
+            rank: (idx + 1) as u32,
+            user_id: entry.user_id.to_string(),
+            username: entry.username.unwrap_or_default(),
+            location: entry.location.unwrap_or_default(),
+            game_id: entry.game_id,
+            rating: entry.rating,
+            deviation: entry.deviation,
+            matches_played: entry.matches_played,
+        })
+        .collect();
+    Ok(entries)
+}
@@ -2,8 +2,8 @@ use crate::{
     db::Database,
     error::{ApiError, ApiResult},
     models::{
-        game::find_game_by_id,
-        matches::{Match, MatchParticipant, MatchStatus},
+        game::{GameEvent, Replay, TimedEvent, find_game_by_id, render_replay},
+        matches::{Match, MatchLog, MatchParticipant, MatchStatus},
         submission::Submission,
     },
 };
@@ -41,6 +41,8 @@ pub async fn create_match(
             user_id: submission.user_id.clone(),
             submission_id: submission.id.clone(),
             score: None,
+            verdict: None,
+            signal: None,
         });
     }
 
@@ -53,7 +55,6 @@ pub async fn create_match(
         participants,
         metadata: None,
         room_id: None,
-        game_event_source: None,
         judge_server_name: None,
         created_at: Datetime::default(),
         updated_at: Datetime::default(),
@@ -71,6 +72,69 @@ pub async fn get_match(db: &Database, match_id: Thing) -> ApiResult<Match> {
     match_data.ok_or_else(|| ApiError::NotFound("Match not found".to_string()))
 }
 
+/// Stderr logs of a match. With a viewer only that user's own bots are
+/// returned; without one (admins) every participant's log is.
+pub async fn get_match_logs(
+    db: &Database,
+    match_id: Thing,
+    viewer: Option<Thing>,
+) -> ApiResult<Vec<MatchLog>> {
+    get_match(db, match_id.clone()).await?;
+    let mut result = if let Some(user_id) = viewer {
+        db.query("SELECT * FROM match_log WHERE match_id = $match_id AND user_id = $user_id")
+            .bind(("match_id", match_id))
+            .bind(("user_id", user_id))
+            .await?
+    } else {
+        db.query("SELECT * FROM match_log WHERE match_id = $match_id")
+            .bind(("match_id", match_id))
+            .await?
+    };
+    let logs: Vec<MatchLog> = result.take(0)?;
+    Ok(logs)
+}
+
+/// Turn-by-turn replay of a completed match, rendered from its event log
+pub async fn get_match_replay(db: &Database, match_id: Thing) -> ApiResult<Replay> {
+    let match_data = get_match(db, match_id.clone()).await?;
+    if match_data.status != MatchStatus::Completed {
+        return Err(ApiError::BadRequest(
+            "Replays are available once the match completes".to_string(),
+        ));
+    }
+    let game = find_game_by_id(&match_data.game_id)
+        .ok_or_else(|| ApiError::NotFound("Game not found".to_string()))?;
+
+    #[derive(Deserialize)]
+    struct EventRow {
+        event: GameEvent,
+        created_at: Datetime,
+    }
+    let mut result = db
+        .query(
+            "SELECT event, created_at FROM game_event
+             WHERE match_id = $match_id
+             ORDER BY seq ASC",
+        )
+        .bind(("match_id", match_id))
+        .await?;
+    let rows: Vec<EventRow> = result.take(0)?;
+    let Some(first) = rows.first() else {
+        return Err(ApiError::NotFound(
+            "No event log was recorded for this match".to_string(),
+        ));
+    };
+    let started = first.created_at.0;
+    let events: Vec<TimedEvent> = rows
+        .into_iter()
+        .map(|row| TimedEvent {
+            elapsed_ms: (row.created_at.0 - started).num_milliseconds().max(0) as u64,
+            event: row.event,
+        })
+        .collect();
+    Ok(render_replay(game, &events))
+}
+
 pub async fn list_matches(
     db: &Database,
     tournament_id: Option<Thing>,
@@ -1,17 +1,43 @@
+pub mod api_token;
 pub mod auth;
+pub mod bracket;
+pub mod completion;
 pub mod email;
+pub mod email_verification;
 pub mod healer;
+pub mod identity;
 pub mod leaderboard;
 pub mod matches;
+pub mod oauth;
+pub mod progression;
+pub mod rating;
+pub mod scheduler;
+pub mod session;
+pub mod standings;
 pub mod submission;
+pub mod swiss;
 pub mod tournament;
+pub mod two_factor;
 pub mod user;
 
+pub use api_token::*;
 pub use auth::*;
+pub use bracket::*;
+pub use completion::*;
 pub use email::*;
+pub use email_verification::*;
 pub use healer::*;
+pub use identity::*;
 pub use leaderboard::*;
 pub use matches::*;
+pub use oauth::*;
+pub use progression::*;
+pub use rating::*;
+pub use scheduler::*;
+pub use session::*;
+pub use standings::*;
 pub use submission::*;
+pub use swiss::*;
 pub use tournament::*;
+pub use two_factor::*;
 pub use user::*;
@@ -0,0 +1,427 @@
+use crate::{
+    config::{OAuthConfig, OidcConfig},
+    error::{ApiError, ApiResult},
+    models::{OAuthProvider, OAuthProviderInfo},
+};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, Validation, decode, decode_header,
+    jwk::{Jwk, JwkSet},
+};
+use reqwest::{Url, header};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+const GOOGLE_AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const GOOGLE_USERINFO_URL: &str = "https://www.googleapis.com/oauth2/v2/userinfo";
+const GITHUB_AUTH_URL: &str = "https://github.com/login/oauth/authorize";
+const GITHUB_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
+const GITHUB_API_URL: &str = "https://api.github.com";
+const GITHUB_USER_AGENT: &str = "axel-tournament-api"; // GitHub rejects requests without one
+
+/// Who a provider says signed in
+#[derive(Debug, Clone)]
+pub struct OAuthIdentity {
+    pub provider: OAuthProvider,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    id_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GoogleUserInfo {
+    id: String,
+    email: Option<String>,
+    name: Option<String>,
+    #[serde(default)]
+    verified_email: bool,
+}
+
+#[derive(Deserialize)]
+struct GithubUser {
+    id: i64,
+    login: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GithubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+#[derive(Deserialize)]
+struct OidcDiscovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+    preferred_username: Option<String>,
+}
+
+/// Checks id_tokens against the issuer's published keys
+struct OidcVerifier {
+    issuer: String,
+    jwks_uri: String,
+    jwks: RwLock<Option<JwkSet>>,
+}
+
+impl OidcVerifier {
+    async fn key(&self, http: &reqwest::Client, kid: Option<&str>) -> ApiResult<Jwk> {
+        let cached = self
+            .jwks
+            .read()
+            .await
+            .as_ref()
+            .and_then(|jwks| find_key(jwks, kid));
+        if let Some(jwk) = cached {
+            return Ok(jwk);
+        }
+        // Unknown key, the issuer may have rotated its keys since the last fetch
+        let jwks: JwkSet = http
+            .get(&self.jwks_uri)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| ApiError::Internal(format!("Failed to fetch issuer keys: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to parse issuer keys: {}", e)))?;
+        let jwk = find_key(&jwks, kid);
+        *self.jwks.write().await = Some(jwks);
+        jwk.ok_or_else(|| ApiError::Auth("id_token is signed with an unknown key".to_string()))
+    }
+}
+
+fn find_key(jwks: &JwkSet, kid: Option<&str>) -> Option<Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid).cloned(),
+        // Without a key id only an issuer with a single key is unambiguous
+        None if jwks.keys.len() == 1 => jwks.keys.first().cloned(),
+        None => None,
+    }
+}
+
+/// One configured sign-in provider and the authorization code flow against it
+pub struct OAuthClient {
+    provider: OAuthProvider,
+    name: String,
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+    auth_url: String,
+    token_url: String,
+    scopes: Vec<String>,
+    trust_email: bool,
+    oidc: Option<OidcVerifier>,
+    http: reqwest::Client,
+}
+
+impl OAuthClient {
+    pub fn google(config: &OAuthConfig) -> Self {
+        Self {
+            provider: OAuthProvider::Google,
+            name: "Google".to_string(),
+            client_id: config.google_client_id.clone(),
+            client_secret: config.google_client_secret.clone(),
+            redirect_uri: config.google_redirect_uri.clone(),
+            auth_url: GOOGLE_AUTH_URL.to_string(),
+            token_url: GOOGLE_TOKEN_URL.to_string(),
+            scopes: vec!["openid".into(), "email".into(), "profile".into()],
+            trust_email: true,
+            oidc: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn github(config: &OAuthConfig) -> Self {
+        Self {
+            provider: OAuthProvider::Github,
+            name: "GitHub".to_string(),
+            client_id: config.github_client_id.clone(),
+            client_secret: config.github_client_secret.clone(),
+            redirect_uri: config.github_redirect_uri.clone(),
+            auth_url: GITHUB_AUTH_URL.to_string(),
+            token_url: GITHUB_TOKEN_URL.to_string(),
+            scopes: vec!["read:user".into(), "user:email".into()],
+            // Only the verified primary address is ever used
+            trust_email: true,
+            oidc: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Configure an OpenID Connect issuer from its discovery document
+    pub async fn discover_oidc(config: &OidcConfig) -> ApiResult<Self> {
+        let http = reqwest::Client::new();
+        let issuer = config.issuer.trim_end_matches('/');
+        let discovery: OidcDiscovery = http
+            .get(format!("{}/.well-known/openid-configuration", issuer))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| ApiError::Internal(format!("OIDC discovery failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Invalid OIDC discovery document: {}", e)))?;
+        if discovery.issuer.trim_end_matches('/') != issuer {
+            return Err(ApiError::Internal(format!(
+                "OIDC discovery returned issuer {}, expected {}",
+                discovery.issuer, issuer
+            )));
+        }
+        Ok(Self {
+            provider: OAuthProvider::Oidc,
+            name: config.name.clone(),
+            client_id: config.client_id.clone(),
+            client_secret: config.client_secret.clone(),
+            redirect_uri: config.redirect_uri.clone(),
+            auth_url: discovery.authorization_endpoint,
+            token_url: discovery.token_endpoint,
+            scopes: config.scopes.clone(),
+            trust_email: config.trust_email,
+            oidc: Some(OidcVerifier {
+                // id_tokens carry the issuer exactly as discovery spelled it
+                issuer: discovery.issuer,
+                jwks_uri: discovery.jwks_uri,
+                jwks: RwLock::new(None),
+            }),
+            http,
+        })
+    }
+
+    pub fn provider(&self) -> OAuthProvider {
+        self.provider
+    }
+
+    pub fn info(&self) -> OAuthProviderInfo {
+        OAuthProviderInfo {
+            id: self.provider,
+            name: self.name.clone(),
+        }
+    }
+
+    /// Whether a verified email from this provider may sign in to the
+    /// existing account with that email
+    pub fn trusts_email(&self) -> bool {
+        self.trust_email
+    }
+
+    /// Where to send the browser to sign in
+    pub fn authorize_url(&self, state: &str, nonce: &str) -> ApiResult<String> {
+        let mut url = Url::parse(&self.auth_url)
+            .map_err(|e| ApiError::Internal(format!("Invalid auth url: {}", e)))?;
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("response_type", "code")
+                .append_pair("client_id", &self.client_id)
+                .append_pair("redirect_uri", &self.redirect_uri)
+                .append_pair("scope", &self.scopes.join(" "))
+                .append_pair("state", state);
+            if self.oidc.is_some() {
+                query.append_pair("nonce", nonce);
+            }
+        }
+        Ok(url.to_string())
+    }
+
+    /// Redeem the code the provider redirected back with and find out who signed in
+    pub async fn identify(&self, code: &str, nonce: &str) -> ApiResult<OAuthIdentity> {
+        let tokens = self.exchange_code(code).await?;
+        match (&self.oidc, self.provider) {
+            (Some(verifier), _) => {
+                let id_token = tokens
+                    .id_token
+                    .ok_or_else(|| ApiError::Auth("Issuer returned no id_token".to_string()))?;
+                self.verify_id_token(verifier, &id_token, nonce).await
+            }
+            (None, OAuthProvider::Github) => self.github_identity(&tokens).await,
+            (None, _) => self.google_identity(&tokens).await,
+        }
+    }
+
+    async fn exchange_code(&self, code: &str) -> ApiResult<TokenResponse> {
+        let tokens: TokenResponse = self
+            .http
+            .post(&self.token_url)
+            .header(header::ACCEPT, "application/json")
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| ApiError::Auth(format!("Failed to exchange code: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| ApiError::Auth(format!("Failed to parse token response: {}", e)))?;
+        if let Some(error) = tokens.error.as_deref() {
+            return Err(ApiError::Auth(format!(
+                "Failed to exchange code: {} ({})",
+                error,
+                tokens
+                    .error_description
+                    .as_deref()
+                    .unwrap_or("no description")
+            )));
+        }
+        Ok(tokens)
+    }
+
+    async fn get_json<T: for<'de> Deserialize<'de>>(&self, url: &str, token: &str) -> ApiResult<T> {
+        self.http
+            .get(url)
+            .bearer_auth(token)
+            .header(header::USER_AGENT, GITHUB_USER_AGENT)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| ApiError::Auth(format!("Failed to get user info: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| ApiError::Auth(format!("Failed to parse user info: {}", e)))
+    }
+
+    async fn google_identity(&self, tokens: &TokenResponse) -> ApiResult<OAuthIdentity> {
+        let access_token = access_token(tokens)?;
+        let info: GoogleUserInfo = self.get_json(GOOGLE_USERINFO_URL, access_token).await?;
+        Ok(OAuthIdentity {
+            provider: self.provider,
+            subject: info.id,
+            email: info.email,
+            email_verified: info.verified_email,
+            name: info.name,
+        })
+    }
+
+    async fn github_identity(&self, tokens: &TokenResponse) -> ApiResult<OAuthIdentity> {
+        let access_token = access_token(tokens)?;
+        let user: GithubUser = self
+            .get_json(&format!("{}/user", GITHUB_API_URL), access_token)
+            .await?;
+        // The profile email is whatever the user chose to show; ask for the primary one
+        let emails: Vec<GithubEmail> = self
+            .get_json(&format!("{}/user/emails", GITHUB_API_URL), access_token)
+            .await?;
+        let primary = emails.into_iter().find(|e| e.primary);
+        Ok(OAuthIdentity {
+            provider: self.provider,
+            subject: user.id.to_string(),
+            email_verified: primary.as_ref().is_some_and(|e| e.verified),
+            email: primary.map(|e| e.email),
+            name: user.name.or(Some(user.login)),
+        })
+    }
+
+    async fn verify_id_token(
+        &self,
+        verifier: &OidcVerifier,
+        id_token: &str,
+        nonce: &str,
+    ) -> ApiResult<OAuthIdentity> {
+        let header = decode_header(id_token)
+            .map_err(|e| ApiError::Auth(format!("Invalid id_token: {}", e)))?;
+        // An HMAC id_token would be signed with our own client secret
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(ApiError::Auth(
+                "id_token must be signed with the issuer's key".to_string(),
+            ));
+        }
+        let jwk = verifier.key(&self.http, header.kid.as_deref()).await?;
+        let key = DecodingKey::from_jwk(&jwk)
+            .map_err(|e| ApiError::Auth(format!("Unusable issuer key: {}", e)))?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&verifier.issuer]);
+        validation.set_audience(&[&self.client_id]);
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|e| ApiError::Auth(format!("Invalid id_token: {}", e)))?
+            .claims;
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(ApiError::Auth("id_token nonce does not match".to_string()));
+        }
+        Ok(OAuthIdentity {
+            provider: self.provider,
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            name: claims.name.or(claims.preferred_username),
+        })
+    }
+}
+
+fn access_token(tokens: &TokenResponse) -> ApiResult<&str> {
+    tokens
+        .access_token
+        .as_deref()
+        .ok_or_else(|| ApiError::Auth("Provider returned no access token".to_string()))
+}
+
+/// The providers this deployment has credentials for
+pub struct OAuthService {
+    clients: Vec<OAuthClient>,
+}
+
+impl OAuthService {
+    pub fn new(clients: Vec<OAuthClient>) -> Self {
+        Self { clients }
+    }
+
+    /// Enable every provider with a client id. An OIDC issuer that cannot be
+    /// reached is left out rather than keeping the API from starting.
+    pub async fn from_config(config: &OAuthConfig) -> Self {
+        let mut clients = Vec::new();
+        if !config.google_client_id.is_empty() {
+            clients.push(OAuthClient::google(config));
+        }
+        if !config.github_client_id.is_empty() {
+            clients.push(OAuthClient::github(config));
+        }
+        if !config.oidc.issuer.is_empty() && !config.oidc.client_id.is_empty() {
+            match OAuthClient::discover_oidc(&config.oidc).await {
+                Ok(client) => clients.push(client),
+                Err(e) => tracing::warn!("OIDC provider disabled: {}", e),
+            }
+        }
+        Self::new(clients)
+    }
+
+    pub fn get(&self, provider: OAuthProvider) -> ApiResult<&OAuthClient> {
+        self.clients
+            .iter()
+            .find(|c| c.provider == provider)
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("Sign-in with {} is not enabled", provider.as_str()))
+            })
+    }
+
+    pub fn providers(&self) -> Vec<OAuthProviderInfo> {
+        self.clients.iter().map(OAuthClient::info).collect()
+    }
+}
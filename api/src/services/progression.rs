@@ -0,0 +1,71 @@
+use crate::{
+    db::Database,
+    models::tournament::{MatchGenerationType, Tournament},
+    services::{bracket::advance_bracket, standings::update_standings, swiss::advance_swiss},
+};
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Keeps running tournaments moving: folds finished match results into the
+/// standings, then creates the matches of formats that only know their next
+/// pairings once earlier matches finish (elimination brackets and Swiss).
+pub struct ProgressionService {
+    db: Database,
+}
+
+impl ProgressionService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn run(self) {
+        info!("Progression service started");
+        loop {
+            let running_result = self
+                .db
+                .query("SELECT * FROM tournament WHERE status = 'running'")
+                .await;
+            let tournaments: Vec<Tournament> = match running_result {
+                Ok(mut response) => response.take(0).unwrap_or_else(|err| {
+                    error!("Failed to read running tournaments: {}", err);
+                    Vec::new()
+                }),
+                Err(err) => {
+                    error!("Failed to load running tournaments: {}", err);
+                    Vec::new()
+                }
+            };
+
+            for tournament in tournaments {
+                let Some(tournament_id) = tournament.id.clone() else {
+                    continue;
+                };
+                // Standings first so Swiss pairs on up to date scores
+                if let Err(err) = update_standings(&self.db, tournament_id.clone()).await {
+                    error!("Failed to update standings for {}: {}", tournament_id, err);
+                }
+                let result = match tournament.match_generation_type {
+                    MatchGenerationType::SingleElimination
+                    | MatchGenerationType::DoubleElimination => {
+                        advance_bracket(&self.db, tournament_id.clone()).await
+                    }
+                    MatchGenerationType::Swiss => {
+                        advance_swiss(&self.db, tournament_id.clone()).await
+                    }
+                    _ => Ok(0),
+                };
+                match result {
+                    Ok(0) => {}
+                    Ok(count) => {
+                        info!("Scheduled {} matches for {}", count, tournament_id);
+                    }
+                    Err(err) => {
+                        error!("Failed to advance tournament {}: {}", tournament_id, err);
+                    }
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        }
+    }
+}
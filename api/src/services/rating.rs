@@ -0,0 +1,373 @@
+use crate::{
+    db::Database,
+    error::{ApiError, ApiResult},
+    models::{
+        matches::Match,
+        rating::{Rating, RatingHistoryEntry},
+    },
+};
+use std::{cmp::Ordering, collections::HashMap, f64::consts::PI, time::Duration};
+use surrealdb::sql::{Datetime, Thing};
+use tracing::{error, info, warn};
+
+/// Conversion factor between the Glicko and Glicko-2 scales
+const GLICKO2_SCALE: f64 = 173.7178;
+/// System constant limiting how fast volatility changes
+const TAU: f64 = 0.5;
+/// Convergence tolerance of the volatility iteration
+const EPSILON: f64 = 0.000001;
+
+pub const DEFAULT_RATING: f64 = 1500.0;
+pub const DEFAULT_DEVIATION: f64 = 350.0;
+pub const DEFAULT_VOLATILITY: f64 = 0.06;
+
+/// Matches rated per pass of the rating service
+const RATING_BATCH: u32 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glicko2 {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+}
+
+impl Default for Glicko2 {
+    fn default() -> Self {
+        Self {
+            rating: DEFAULT_RATING,
+            deviation: DEFAULT_DEVIATION,
+            volatility: DEFAULT_VOLATILITY,
+        }
+    }
+}
+
+/// Apply one Glicko-2 rating period. `results` holds each opponent's rating
+/// before the period and the outcome for the player (1 win, 0.5 draw, 0 loss).
+/// A period without results only widens the deviation.
+pub fn glicko2_update(player: Glicko2, results: &[(Glicko2, f64)]) -> Glicko2 {
+    let mu = (player.rating - DEFAULT_RATING) / GLICKO2_SCALE;
+    let phi = player.deviation / GLICKO2_SCALE;
+    let sigma = player.volatility;
+    if results.is_empty() {
+        let phi_star = (phi * phi + sigma * sigma).sqrt();
+        return Glicko2 {
+            deviation: (phi_star * GLICKO2_SCALE).min(DEFAULT_DEVIATION),
+            ..player
+        };
+    }
+
+    let g = |phi_j: f64| 1.0 / (1.0 + 3.0 * phi_j * phi_j / (PI * PI)).sqrt();
+    let mut v_inv = 0.0;
+    let mut improvement = 0.0;
+    for (opponent, outcome) in results {
+        let mu_j = (opponent.rating - DEFAULT_RATING) / GLICKO2_SCALE;
+        let g_j = g(opponent.deviation / GLICKO2_SCALE);
+        let expected = 1.0 / (1.0 + (-g_j * (mu - mu_j)).exp());
+        v_inv += g_j * g_j * expected * (1.0 - expected);
+        improvement += g_j * (outcome - expected);
+    }
+    let v = 1.0 / v_inv;
+    let delta = v * improvement;
+
+    // Illinois iteration for the new volatility
+    let a = (sigma * sigma).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        let denom = phi * phi + v + ex;
+        ex * (delta * delta - phi * phi - v - ex) / (2.0 * denom * denom) - (x - a) / (TAU * TAU)
+    };
+    let mut big_a = a;
+    let mut big_b = if delta * delta > phi * phi + v {
+        (delta * delta - phi * phi - v).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * TAU
+    };
+    let mut f_a = f(big_a);
+    let mut f_b = f(big_b);
+    while (big_b - big_a).abs() > EPSILON {
+        let big_c = big_a + (big_a - big_b) * f_a / (f_b - f_a);
+        let f_c = f(big_c);
+        if f_c * f_b <= 0.0 {
+            big_a = big_b;
+            f_a = f_b;
+        } else {
+            f_a /= 2.0;
+        }
+        big_b = big_c;
+        f_b = f_c;
+    }
+    let new_sigma = (big_a / 2.0).exp();
+
+    let phi_star = (phi * phi + new_sigma * new_sigma).sqrt();
+    let new_phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / v).sqrt();
+    let new_mu = mu + new_phi * new_phi * improvement;
+    Glicko2 {
+        rating: new_mu * GLICKO2_SCALE + DEFAULT_RATING,
+        deviation: (new_phi * GLICKO2_SCALE).min(DEFAULT_DEVIATION),
+        volatility: new_sigma,
+    }
+}
+
+/// Attempts at rating a match whose players' ratings keep changing underneath
+const RATE_ATTEMPTS: u32 = 3;
+
+/// Update the ratings of everyone in a completed match. Each match is its own
+/// rating period in which every player meets every other player once, winning
+/// against lower scores and drawing against equal ones. The match is marked
+/// rated in the same transaction that stores the new ratings, so a failed
+/// write leaves it to be rated again. Returns false when the match was
+/// already rated, or when its players kept being rated by other matches at
+/// the same time; it is then picked up again on the next pass.
+pub async fn rate_match(db: &Database, match_data: &Match) -> ApiResult<bool> {
+    for _ in 0..RATE_ATTEMPTS {
+        match try_rate_match(db, match_data).await? {
+            RateOutcome::Rated => return Ok(true),
+            RateOutcome::AlreadyRated => return Ok(false),
+            RateOutcome::Stale => continue,
+        }
+    }
+    warn!(
+        "Ratings of the players of match {:?} kept changing, rating it later",
+        match_data.id
+    );
+    Ok(false)
+}
+
+enum RateOutcome {
+    Rated,
+    AlreadyRated,
+    /// A rating changed between reading it and writing the new one
+    Stale,
+}
+
+async fn try_rate_match(db: &Database, match_data: &Match) -> ApiResult<RateOutcome> {
+    let match_id = match_data
+        .id
+        .clone()
+        .ok_or_else(|| ApiError::Internal("Match missing id".to_string()))?;
+
+    // A player's best score stands for them; self-play has no opponents
+    let mut scores: HashMap<String, (Thing, f64)> = HashMap::new();
+    for participant in &match_data.participants {
+        let score = participant.score.unwrap_or(0.0);
+        scores
+            .entry(participant.user_id.to_string())
+            .and_modify(|(_, s)| *s = s.max(score))
+            .or_insert((participant.user_id.clone(), score));
+    }
+
+    let mut current: HashMap<String, Rating> = HashMap::new();
+    if scores.len() >= 2 {
+        for (key, (user_id, _)) in &scores {
+            let rating = get_or_default_rating(db, user_id.clone(), &match_data.game_id).await?;
+            current.insert(key.clone(), rating);
+        }
+    }
+    let glicko = |rating: &Rating| Glicko2 {
+        rating: rating.rating,
+        deviation: rating.deviation,
+        volatility: rating.volatility,
+    };
+
+    let mut updated = Vec::new();
+    let mut created = Vec::new();
+    let mut history = Vec::new();
+    for (key, before) in &current {
+        let score = scores[key].1;
+        let results: Vec<(Glicko2, f64)> = scores
+            .iter()
+            .filter(|(other_key, _)| *other_key != key)
+            .map(|(other_key, (_, other_score))| {
+                let outcome = match score.partial_cmp(other_score) {
+                    Some(Ordering::Greater) => 1.0,
+                    Some(Ordering::Less) => 0.0,
+                    _ => 0.5,
+                };
+                (glicko(&current[other_key]), outcome)
+            })
+            .collect();
+        let after = glicko2_update(glicko(before), &results);
+        let rating = Rating {
+            rating: after.rating,
+            deviation: after.deviation,
+            volatility: after.volatility,
+            matches_played: before.matches_played + 1,
+            updated_at: Datetime::default(),
+            ..before.clone()
+        };
+        history.push(RatingHistoryEntry {
+            id: None,
+            user_id: before.user_id.clone(),
+            game_id: before.game_id.clone(),
+            match_id: match_id.clone(),
+            rating: after.rating,
+            deviation: after.deviation,
+            volatility: after.volatility,
+            created_at: Datetime::default(),
+        });
+        if rating.id.is_some() {
+            updated.push(rating);
+        } else {
+            created.push(rating);
+        }
+    }
+    // The ratings the new ones were computed from, as (id, matches played)
+    let versions: Vec<(Thing, u32)> = current
+        .values()
+        .filter_map(|r| r.id.clone().map(|id| (id, r.matches_played)))
+        .collect();
+    let user_ids: Vec<Thing> = current.values().map(|r| r.user_id.clone()).collect();
+
+    // Claim the match so its result is only counted once. Nothing is written
+    // when a rating changed, or appeared, since it was read.
+    let mut claimed = db
+        .query(
+            "BEGIN TRANSACTION;
+             LET $stale = (SELECT VALUE id FROM rating
+                 WHERE game_id = $game_id AND user_id IN $user_ids
+                   AND [id, matches_played] NOT IN $versions);
+             LET $fresh = array::len($stale) = 0;
+             LET $claimed = IF $fresh {
+                 (UPDATE $match_id SET rated = true
+                     WHERE status = 'completed' AND rated != true
+                     RETURN AFTER)
+             } ELSE {
+                 []
+             };
+             IF array::len($claimed) > 0 {
+                 FOR $rating IN $updated {
+                     UPDATE $rating.id CONTENT $rating;
+                 };
+                 FOR $rating IN $created {
+                     CREATE rating CONTENT $rating;
+                 };
+                 FOR $entry IN $history {
+                     CREATE rating_history CONTENT $entry;
+                 };
+             };
+             RETURN IF !$fresh { 'stale' } ELSE IF array::len($claimed) > 0 { 'rated' } ELSE { 'skipped' };
+             COMMIT TRANSACTION;",
+        )
+        .bind(("match_id", match_id))
+        .bind(("game_id", match_data.game_id.clone()))
+        .bind(("user_ids", user_ids))
+        .bind(("versions", versions))
+        .bind(("updated", updated))
+        .bind(("created", created))
+        .bind(("history", history))
+        .await?;
+    let outcome: Option<String> = claimed.take(0)?;
+    Ok(match outcome.as_deref() {
+        Some("rated") => RateOutcome::Rated,
+        Some("stale") => RateOutcome::Stale,
+        _ => RateOutcome::AlreadyRated,
+    })
+}
+
+/// Rate completed matches that have not been rated yet, oldest first
+pub async fn rate_completed_matches(db: &Database) -> ApiResult<usize> {
+    let mut result = db
+        .query(
+            "SELECT * FROM match
+             WHERE status = 'completed' AND rated != true
+             ORDER BY completed_at ASC
+             LIMIT $limit",
+        )
+        .bind(("limit", RATING_BATCH))
+        .await?;
+    let unrated: Vec<Match> = result.take(0)?;
+    let mut rated = 0;
+    for match_data in &unrated {
+        if rate_match(db, match_data).await? {
+            rated += 1;
+        }
+    }
+    Ok(rated)
+}
+
+pub async fn get_rating(db: &Database, user_id: Thing, game_id: &str) -> ApiResult<Option<Rating>> {
+    let mut result = db
+        .query("SELECT * FROM rating WHERE user_id = $user_id AND game_id = $game_id LIMIT 1")
+        .bind(("user_id", user_id))
+        .bind(("game_id", game_id.to_string()))
+        .await?;
+    let ratings: Vec<Rating> = result.take(0)?;
+    Ok(ratings.into_iter().next())
+}
+
+/// Rating history of a user, oldest first, optionally limited to one game
+pub async fn get_rating_history(
+    db: &Database,
+    user_id: Thing,
+    game_id: Option<String>,
+) -> ApiResult<Vec<RatingHistoryEntry>> {
+    let mut result = if let Some(game_id) = game_id {
+        db.query(
+            "SELECT * FROM rating_history
+             WHERE user_id = $user_id AND game_id = $game_id
+             ORDER BY created_at ASC",
+        )
+        .bind(("user_id", user_id))
+        .bind(("game_id", game_id))
+        .await?
+    } else {
+        db.query(
+            "SELECT * FROM rating_history
+             WHERE user_id = $user_id
+             ORDER BY created_at ASC",
+        )
+        .bind(("user_id", user_id))
+        .await?
+    };
+    let history: Vec<RatingHistoryEntry> = result.take(0)?;
+    Ok(history)
+}
+
+async fn get_or_default_rating(db: &Database, user_id: Thing, game_id: &str) -> ApiResult<Rating> {
+    if let Some(rating) = get_rating(db, user_id.clone(), game_id).await? {
+        return Ok(rating);
+    }
+    let initial = Glicko2::default();
+    Ok(Rating {
+        id: None,
+        user_id,
+        game_id: game_id.to_string(),
+        rating: initial.rating,
+        deviation: initial.deviation,
+        volatility: initial.volatility,
+        matches_played: 0,
+        updated_at: Datetime::default(),
+    })
+}
+
+/// Folds every completed match, tournament or not, into the per-game ratings
+pub struct RatingService {
+    db: Database,
+}
+
+impl RatingService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn run(self) {
+        info!("Rating service started");
+        loop {
+            match rate_completed_matches(&self.db).await {
+                Ok(0) => {}
+                Ok(count) => {
+                    info!("Rated {} matches", count);
+                }
+                Err(err) => {
+                    error!("Failed to rate completed matches: {}", err);
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        }
+    }
+}
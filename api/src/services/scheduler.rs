@@ -0,0 +1,181 @@
+use crate::{
+    db::Database,
+    error::ApiResult,
+    models::tournament::Tournament,
+    services::{completion::close_tournament, tournament::start_tournament},
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::time::Duration;
+use surrealdb::sql::{Datetime, Thing};
+use tracing::{error, info, warn};
+
+/// First delay before a failed scheduled start is tried again
+const START_RETRY_BASE_SECS: u64 = 60;
+/// Longest delay between scheduled start attempts
+const START_RETRY_MAX_SECS: u64 = 3600;
+
+/// Open registration for scheduled tournaments whose registration time has
+/// passed. Without a registration time they open once the start time is
+/// reached, so the next pass can start them. Returns the number opened.
+pub async fn open_due_registrations(db: &Database) -> ApiResult<usize> {
+    let mut result = db
+        .query(
+            "UPDATE tournament
+             SET status = 'registration', updated_at = time::now()
+             WHERE status = 'scheduled'
+               AND ((registration_time != NONE AND registration_time <= time::now())
+                 OR (registration_time = NONE AND start_time != NONE AND start_time <= time::now()))
+             RETURN AFTER",
+        )
+        .await?;
+    let opened: Vec<Tournament> = result.take(0)?;
+    for tournament in &opened {
+        if let Some(id) = &tournament.id {
+            info!("Tournament {} moved from scheduled to registration", id);
+        }
+    }
+    Ok(opened.len())
+}
+
+/// Start tournaments in registration whose start time has passed. A start that
+/// fails, for example because too few players joined, is retried with a
+/// growing delay until the end time cancels the tournament. Returns the number
+/// started.
+pub async fn start_due_tournaments(db: &Database) -> ApiResult<usize> {
+    #[derive(Deserialize)]
+    struct DueTournament {
+        id: Thing,
+        #[serde(default)] // Unset on tournaments created before retries were counted
+        start_attempts: u32,
+    }
+    let mut result = db
+        .query(
+            "SELECT id, start_attempts FROM tournament
+             WHERE status = 'registration' AND start_time != NONE AND start_time <= time::now()
+               AND (end_time = NONE OR end_time > time::now())
+               AND (next_start_attempt = NONE OR next_start_attempt <= time::now())",
+        )
+        .await?;
+    let due: Vec<DueTournament> = result.take(0)?;
+    let mut started = 0;
+    for tournament in due {
+        let tournament_id = tournament.id;
+        match start_tournament(db, tournament_id.clone()).await {
+            Ok(_) => {
+                info!(
+                    "Tournament {} moved from registration to running",
+                    tournament_id
+                );
+                started += 1;
+            }
+            Err(err) => {
+                let delay = start_retry_delay(tournament.start_attempts);
+                warn!(
+                    "Scheduled start of tournament {} failed, retrying in {}s: {}",
+                    tournament_id,
+                    delay.as_secs(),
+                    err
+                );
+                let next_attempt = Utc::now() + delay;
+                db.query(
+                    "UPDATE $tournament_id
+                     SET start_attempts += 1, next_start_attempt = $next_attempt",
+                )
+                .bind(("tournament_id", tournament_id))
+                .bind(("next_attempt", Datetime::from(next_attempt)))
+                .await?;
+            }
+        }
+    }
+    Ok(started)
+}
+
+/// Delay before the next start attempt, doubling from a minute up to an hour
+fn start_retry_delay(attempts: u32) -> Duration {
+    let seconds = START_RETRY_BASE_SECS.saturating_mul(1 << attempts.min(10));
+    Duration::from_secs(seconds.min(START_RETRY_MAX_SECS))
+}
+
+/// Wind down tournaments whose end time has passed. Tournaments that never
+/// started are cancelled; running ones have their unplayed matches cancelled
+/// and are completed with the standings they have. Returns the number closed.
+pub async fn close_expired_tournaments(db: &Database) -> ApiResult<usize> {
+    let mut result = db
+        .query(
+            "UPDATE tournament
+             SET status = 'cancelled', updated_at = time::now()
+             WHERE status IN ['scheduled', 'registration']
+               AND end_time != NONE AND end_time <= time::now()
+             RETURN BEFORE;
+             SELECT * FROM tournament
+             WHERE status = 'running' AND end_time != NONE AND end_time <= time::now();",
+        )
+        .await?;
+    let cancelled: Vec<Tournament> = result.take(0)?;
+    let expired: Vec<Tournament> = result.take(1)?;
+    for tournament in &cancelled {
+        if let Some(id) = &tournament.id {
+            info!(
+                "Tournament {} moved from {:?} to cancelled",
+                id, tournament.status
+            );
+        }
+    }
+
+    let mut closed = cancelled.len();
+    for tournament in expired {
+        let Some(tournament_id) = tournament.id.clone() else {
+            continue;
+        };
+        db.query(
+            "UPDATE match
+             SET status = 'cancelled', updated_at = time::now()
+             WHERE tournament_id = $tournament_id AND status IN ['pending', 'queued', 'running']",
+        )
+        .bind(("tournament_id", tournament_id.clone()))
+        .await?;
+        match close_tournament(db, &tournament).await {
+            Ok(None) => {}
+            Ok(Some(_)) => {
+                info!(
+                    "Tournament {} moved from running to completed at its end time",
+                    tournament_id
+                );
+                closed += 1;
+            }
+            Err(err) => {
+                error!("Failed to close tournament {}: {}", tournament_id, err);
+            }
+        }
+    }
+    Ok(closed)
+}
+
+/// Moves tournaments through their lifecycle on the times set by admins
+pub struct SchedulerService {
+    db: Database,
+}
+
+impl SchedulerService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn run(self) {
+        info!("Scheduler service started");
+        loop {
+            if let Err(err) = open_due_registrations(&self.db).await {
+                error!("Failed to open scheduled registrations: {}", err);
+            }
+            if let Err(err) = start_due_tournaments(&self.db).await {
+                error!("Failed to start scheduled tournaments: {}", err);
+            }
+            if let Err(err) = close_expired_tournaments(&self.db).await {
+                error!("Failed to close expired tournaments: {}", err);
+            }
+
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        }
+    }
+}
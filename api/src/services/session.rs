@@ -0,0 +1,143 @@
+use crate::{
+    db::Database,
+    error::{ApiError, ApiResult},
+    models::Session,
+    services::AuthService,
+};
+use chrono::{Duration, Utc};
+use surrealdb::sql::{Datetime, Thing};
+
+/// Start a session for a user, returning it with its first refresh token.
+/// `two_factor` records whether the login included a second factor.
+pub async fn create_session(
+    db: &Database,
+    auth_service: &AuthService,
+    user_id: Thing,
+    ttl_seconds: i64,
+    two_factor: bool,
+) -> ApiResult<(Session, String)> {
+    let refresh_token = auth_service.generate_refresh_token();
+    let now = Utc::now();
+    let session = Session {
+        id: None,
+        user_id,
+        refresh_token_hash: auth_service.hash_refresh_token(&refresh_token),
+        previous_token_hash: None,
+        expires_at: (now + Duration::seconds(ttl_seconds)).into(),
+        revoked_at: None,
+        created_at: now.into(),
+        last_used_at: now.into(),
+        two_factor,
+    };
+    let created: Option<Session> = db.create("session").content(session).await?;
+    let session =
+        created.ok_or_else(|| ApiError::Internal("Failed to create session".to_string()))?;
+    Ok((session, refresh_token))
+}
+
+/// Exchange a refresh token for the next one. Every refresh token works once;
+/// a replaced token coming back means it was copied, so its session is revoked.
+pub async fn rotate_refresh_token(
+    db: &Database,
+    auth_service: &AuthService,
+    refresh_token: &str,
+    ttl_seconds: i64,
+) -> ApiResult<(Session, String)> {
+    let token_hash = auth_service.hash_refresh_token(refresh_token);
+    let mut result = db
+        .query(
+            "SELECT * FROM session WHERE refresh_token_hash = $token_hash;
+             SELECT * FROM session WHERE previous_token_hash = $token_hash;",
+        )
+        .bind(("token_hash", token_hash.clone()))
+        .await?;
+    let current: Vec<Session> = result.take(0)?;
+    let replaced: Vec<Session> = result.take(1)?;
+
+    let Some(session) = current.into_iter().next() else {
+        if let Some(session_id) = replaced.into_iter().next().and_then(|s| s.id) {
+            tracing::warn!(
+                "Refresh token of session {} was reused, revoking it",
+                session_id
+            );
+            revoke_session(db, session_id).await?;
+        }
+        return Err(ApiError::Auth("Invalid refresh token".to_string()));
+    };
+    let now: Datetime = Utc::now().into();
+    if session.revoked_at.is_some() || session.expires_at < now {
+        return Err(ApiError::Auth("Session has expired".to_string()));
+    }
+    let session_id = session
+        .id
+        .ok_or_else(|| ApiError::Internal("Session ID is missing".to_string()))?;
+
+    // Only rotate from the token we were given, so of two concurrent refreshes
+    // one wins and the other fails instead of both getting a token
+    let next_token = auth_service.generate_refresh_token();
+    let expires_at: Datetime = (Utc::now() + Duration::seconds(ttl_seconds)).into();
+    let mut result = db
+        .query(
+            "UPDATE $session_id SET previous_token_hash = refresh_token_hash,
+                refresh_token_hash = $next_hash, last_used_at = $now, expires_at = $expires_at
+             WHERE refresh_token_hash = $token_hash AND revoked_at = NONE RETURN AFTER",
+        )
+        .bind(("session_id", session_id))
+        .bind(("next_hash", auth_service.hash_refresh_token(&next_token)))
+        .bind(("token_hash", token_hash))
+        .bind(("now", now))
+        .bind(("expires_at", expires_at))
+        .await?;
+    let rotated: Vec<Session> = result.take(0)?;
+    let session = rotated
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::Auth("Invalid refresh token".to_string()))?;
+    Ok((session, next_token))
+}
+
+/// Whether access tokens issued for a session are still honoured
+pub async fn is_session_active(db: &Database, session_id: Thing) -> ApiResult<bool> {
+    let key = (session_id.tb.as_str(), session_id.id.to_string());
+    let session: Option<Session> = db.select(key).await?;
+    let now: Datetime = Utc::now().into();
+    Ok(session.is_some_and(|s| s.revoked_at.is_none() && s.expires_at > now))
+}
+
+/// Whether a session was started with a second factor
+pub async fn is_two_factor_session(db: &Database, session_id: Thing) -> ApiResult<bool> {
+    let key = (session_id.tb.as_str(), session_id.id.to_string());
+    let session: Option<Session> = db.select(key).await?;
+    Ok(session.is_some_and(|s| s.two_factor))
+}
+
+/// Record that the user of a session has just shown their second factor
+pub async fn mark_session_two_factor(db: &Database, session_id: Thing) -> ApiResult<()> {
+    db.query("UPDATE $session_id SET two_factor = true WHERE revoked_at = NONE")
+        .bind(("session_id", session_id))
+        .await?;
+    Ok(())
+}
+
+/// End one session; its refresh token and access tokens stop working
+pub async fn revoke_session(db: &Database, session_id: Thing) -> ApiResult<()> {
+    db.query("UPDATE $session_id SET revoked_at = $now WHERE revoked_at = NONE")
+        .bind(("session_id", session_id))
+        .bind(("now", Datetime::default()))
+        .await?;
+    Ok(())
+}
+
+/// End every session of a user, returning how many were still active
+pub async fn revoke_user_sessions(db: &Database, user_id: Thing) -> ApiResult<usize> {
+    let mut result = db
+        .query(
+            "UPDATE session SET revoked_at = $now
+             WHERE user_id = $user_id AND revoked_at = NONE RETURN AFTER",
+        )
+        .bind(("user_id", user_id))
+        .bind(("now", Datetime::default()))
+        .await?;
+    let revoked: Vec<Session> = result.take(0)?;
+    Ok(revoked.len())
+}
@@ -0,0 +1,259 @@
+use crate::{
+    db::Database,
+    error::{ApiError, ApiResult},
+    models::{
+        bracket::{BracketNode, BracketSide},
+        matches::MatchParticipant,
+        standings::StandingEntry,
+        tournament::{MatchGenerationType, Tournament, TournamentParticipant},
+    },
+    services::{bracket::get_bracket, tournament::get_tournament_participants},
+};
+use serde::Deserialize;
+use std::{cmp::Ordering, collections::HashMap};
+use surrealdb::sql::{Datetime, Thing};
+
+#[derive(Deserialize)]
+struct CompletedMatch {
+    participants: Vec<MatchParticipant>,
+}
+
+#[derive(Default)]
+struct PlayerRecord {
+    score: f64,
+    wins: u32,
+    draws: u32,
+    losses: u32,
+    opponents: Vec<String>,
+}
+
+async fn completed_matches(db: &Database, tournament_id: Thing) -> ApiResult<Vec<CompletedMatch>> {
+    let mut result = db
+        .query(
+            "SELECT participants FROM match
+             WHERE tournament_id = $tournament_id AND status = 'completed'",
+        )
+        .bind(("tournament_id", tournament_id))
+        .await?;
+    let completed: Vec<CompletedMatch> = result.take(0)?;
+    Ok(completed)
+}
+
+/// Recompute every participant's score and rank from the tournament's
+/// completed matches. Scores are rebuilt from scratch on each call, so a match
+/// that is re-queued and played again is never counted twice. Players with
+/// equal scores share a rank. Returns the number of participants updated.
+pub async fn update_standings(db: &Database, tournament_id: Thing) -> ApiResult<usize> {
+    let completed = completed_matches(db, tournament_id.clone()).await?;
+    let mut totals: HashMap<String, f64> = HashMap::new();
+    for completed_match in completed {
+        for participant in completed_match.participants {
+            *totals.entry(participant.user_id.to_string()).or_insert(0.0) +=
+                participant.score.unwrap_or(0.0);
+        }
+    }
+
+    let participants = get_tournament_participants(db, tournament_id).await?;
+    let mut standings: Vec<(TournamentParticipant, f64)> = participants
+        .into_iter()
+        .map(|p| {
+            let score = totals.get(&p.user_id.to_string()).copied().unwrap_or(0.0);
+            (p, score)
+        })
+        .collect();
+    standings.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+
+    let mut updated = 0;
+    let mut rank = 0;
+    let mut previous_score = None;
+    for (idx, (participant, score)) in standings.iter().enumerate() {
+        if previous_score != Some(*score) {
+            rank = idx as u32 + 1;
+            previous_score = Some(*score);
+        }
+        if participant.score == *score && participant.rank == Some(rank) {
+            continue;
+        }
+        let Some(participant_id) = participant.id.clone() else {
+            continue;
+        };
+        db.query("UPDATE $participant_id SET score = $score, rank = $rank")
+            .bind(("participant_id", participant_id))
+            .bind(("score", *score))
+            .bind(("rank", rank))
+            .await?;
+        updated += 1;
+    }
+    Ok(updated)
+}
+
+/// How far each entrant of an elimination bracket got, higher is better. The
+/// champion is the only entrant never eliminated and gets `u32::MAX`; everyone
+/// else gets the stage of the match that knocked them out, with the grand
+/// final ranked above every losers bracket round.
+fn bracket_placements(nodes: &[BracketNode]) -> HashMap<String, u32> {
+    let last_round = nodes
+        .iter()
+        .filter(|n| n.bracket != BracketSide::GrandFinal)
+        .map(|n| n.round)
+        .max()
+        .unwrap_or(0);
+    let mut placements = HashMap::new();
+    for node in nodes {
+        for entrant in [&node.player1, &node.player2].into_iter().flatten() {
+            placements.insert(entrant.user_id.to_string(), u32::MAX);
+        }
+    }
+    for node in nodes {
+        let (Some(player1), Some(player2), Some(winner)) =
+            (&node.player1, &node.player2, &node.winner)
+        else {
+            continue;
+        };
+        let loser = if winner == player1 { player2 } else { player1 };
+        // The grand final only sends its loser on to the reset when the
+        // losers bracket champion won it
+        let eliminated = node.loser_to.is_none()
+            || (node.bracket == BracketSide::GrandFinal && node.round == 1 && winner == player1);
+        if !eliminated {
+            continue;
+        }
+        let stage = match node.bracket {
+            BracketSide::GrandFinal => last_round + node.round,
+            BracketSide::Winners | BracketSide::Losers => node.round,
+        };
+        placements.insert(loser.user_id.to_string(), stage);
+    }
+    placements
+}
+
+/// Compute the final order of a tournament and write it to the participants.
+/// Unlike `update_standings` every player gets a distinct rank. Ties on score
+/// are broken by wins, then by the total score of the opponents faced, then by
+/// who joined first. Elimination formats rank by bracket position first: the
+/// champion, the grand final loser, then by the round each player went out
+/// in, with wins ahead of score among players who went out in the same round.
+pub async fn freeze_standings(
+    db: &Database,
+    tournament: &Tournament,
+) -> ApiResult<Vec<StandingEntry>> {
+    let tournament_id = tournament
+        .id
+        .clone()
+        .ok_or_else(|| ApiError::Internal("Tournament missing id".to_string()))?;
+    let completed = completed_matches(db, tournament_id.clone()).await?;
+    let mut records: HashMap<String, PlayerRecord> = HashMap::new();
+    for completed_match in &completed {
+        for participant in &completed_match.participants {
+            let score = participant.score.unwrap_or(0.0);
+            let opponents: Vec<&MatchParticipant> = completed_match
+                .participants
+                .iter()
+                .filter(|o| o.user_id != participant.user_id)
+                .collect();
+            let record = records.entry(participant.user_id.to_string()).or_default();
+            record.score += score;
+            // Self-play only counts toward the score
+            if opponents.is_empty() {
+                continue;
+            }
+            let best_opponent = opponents
+                .iter()
+                .map(|o| o.score.unwrap_or(0.0))
+                .fold(f64::MIN, f64::max);
+            match score.partial_cmp(&best_opponent) {
+                Some(Ordering::Greater) => record.wins += 1,
+                Some(Ordering::Less) => record.losses += 1,
+                _ => record.draws += 1,
+            }
+            record
+                .opponents
+                .extend(opponents.iter().map(|o| o.user_id.to_string()));
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct RawParticipant {
+        id: Thing,
+        user_id: Thing,
+        username: Option<String>,
+        joined_at: Datetime,
+    }
+    let mut result = db
+        .query(
+            "SELECT id, user_id, user_id.username AS username, joined_at
+             FROM tournament_participant
+             WHERE tournament_id = $tournament_id",
+        )
+        .bind(("tournament_id", tournament_id.clone()))
+        .await?;
+    let participants: Vec<RawParticipant> = result.take(0)?;
+
+    let score_of = |user_id: &String| records.get(user_id).map_or(0.0, |r| r.score);
+    let mut rows: Vec<(RawParticipant, StandingEntry)> = participants
+        .into_iter()
+        .map(|p| {
+            let user_key = p.user_id.to_string();
+            let record = records.get(&user_key);
+            let entry = StandingEntry {
+                rank: 0,
+                user_id: p.user_id.clone(),
+                username: p.username.clone().unwrap_or_default(),
+                score: score_of(&user_key),
+                wins: record.map_or(0, |r| r.wins),
+                draws: record.map_or(0, |r| r.draws),
+                losses: record.map_or(0, |r| r.losses),
+                opponent_score: record.map_or(0.0, |r| r.opponents.iter().map(score_of).sum()),
+            };
+            (p, entry)
+        })
+        .collect();
+
+    let elimination = matches!(
+        tournament.match_generation_type,
+        MatchGenerationType::SingleElimination | MatchGenerationType::DoubleElimination
+    );
+    let placements = if elimination {
+        bracket_placements(&get_bracket(db, tournament_id).await?)
+    } else {
+        HashMap::new()
+    };
+    // Players outside the bracket rank below every entrant
+    let placement_of = |entry: &StandingEntry| {
+        placements
+            .get(&entry.user_id.to_string())
+            .copied()
+            .unwrap_or(0)
+    };
+    rows.sort_by(|(pa, a), (pb, b)| {
+        let by_score = b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal);
+        let by_wins = b.wins.cmp(&a.wins);
+        let primary = if elimination {
+            placement_of(b)
+                .cmp(&placement_of(a))
+                .then(by_wins)
+                .then(by_score)
+        } else {
+            by_score.then(by_wins)
+        };
+        primary
+            .then_with(|| {
+                b.opponent_score
+                    .partial_cmp(&a.opponent_score)
+                    .unwrap_or(Ordering::Equal)
+            })
+            .then_with(|| pa.joined_at.cmp(&pb.joined_at))
+    });
+
+    let mut entries = Vec::with_capacity(rows.len());
+    for (idx, (participant, mut entry)) in rows.into_iter().enumerate() {
+        entry.rank = idx as u32 + 1;
+        db.query("UPDATE $participant_id SET score = $score, rank = $rank")
+            .bind(("participant_id", participant.id))
+            .bind(("score", entry.score))
+            .bind(("rank", entry.rank))
+            .await?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
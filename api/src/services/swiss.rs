@@ -0,0 +1,241 @@
+use crate::{
+    db::Database,
+    error::{ApiError, ApiResult},
+    models::{
+        matches::{Match, MatchParticipant},
+        tournament::{Tournament, TournamentParticipant},
+    },
+    services::tournament::{get_match_ready_participants, get_tournament, new_match_for_seats},
+};
+use serde::Deserialize;
+use std::{cmp::Ordering, collections::HashSet};
+use surrealdb::sql::Thing;
+
+/// Upper bound on pairing attempts before rematches are allowed
+const PAIRING_BUDGET: usize = 100_000;
+
+/// Generate the first Swiss round. When the tournament has no configured
+/// round count, ceil(log2(players)) rounds are played.
+pub async fn generate_swiss(
+    db: &Database,
+    tournament: &Tournament,
+    participants: &[TournamentParticipant],
+) -> ApiResult<usize> {
+    if participants.len() < 2 {
+        return Err(ApiError::BadRequest(
+            "Swiss needs at least 2 participants with accepted submissions".to_string(),
+        ));
+    }
+    let tournament_id = tournament
+        .id
+        .clone()
+        .ok_or_else(|| ApiError::Internal("Tournament missing id".to_string()))?;
+    let rounds = tournament
+        .swiss_rounds
+        .unwrap_or_else(|| participants.len().next_power_of_two().trailing_zeros());
+    db.query(
+        "UPDATE $tournament_id
+         SET swiss_rounds = $rounds, current_round = 0, updated_at = time::now()",
+    )
+    .bind(("tournament_id", tournament_id))
+    .bind(("rounds", rounds))
+    .await?;
+    create_round(db, tournament, participants, 1).await
+}
+
+/// Generate the next Swiss round once every match of the current one has
+/// finished. Returns the number of matches created.
+pub async fn advance_swiss(db: &Database, tournament_id: Thing) -> ApiResult<usize> {
+    let tournament = get_tournament(db, tournament_id.clone()).await?;
+    let total_rounds = tournament.swiss_rounds.unwrap_or(0);
+    if tournament.current_round >= total_rounds {
+        return Ok(0);
+    }
+
+    let mut unfinished = db
+        .query(
+            "SELECT VALUE id FROM match
+             WHERE tournament_id = $tournament_id AND status IN ['pending', 'queued', 'running']
+             LIMIT 1",
+        )
+        .bind(("tournament_id", tournament_id.clone()))
+        .await?;
+    let unfinished: Vec<Thing> = unfinished.take(0)?;
+    if !unfinished.is_empty() {
+        return Ok(0);
+    }
+
+    let participants = get_match_ready_participants(db, tournament_id).await?;
+    create_round(db, &tournament, &participants, tournament.current_round + 1).await
+}
+
+/// Pair the field by current standings. With an odd field the lowest ranked
+/// player who has not had a bye sits the round out; byes carry no points since
+/// match scores are game specific. The round number is claimed in the same
+/// transaction that stores the matches and the bye, so a round is generated
+/// once and a failure leaves the tournament on the previous round.
+async fn create_round(
+    db: &Database,
+    tournament: &Tournament,
+    participants: &[TournamentParticipant],
+    round: u32,
+) -> ApiResult<usize> {
+    #[derive(Deserialize)]
+    struct PastMatch {
+        participants: Vec<MatchParticipant>,
+    }
+    let tournament_id = tournament
+        .id
+        .clone()
+        .ok_or_else(|| ApiError::Internal("Tournament missing id".to_string()))?;
+    let mut result = db
+        .query("SELECT participants FROM match WHERE tournament_id = $tournament_id")
+        .bind(("tournament_id", tournament_id.clone()))
+        .await?;
+    let past: Vec<PastMatch> = result.take(0)?;
+    let mut played: HashSet<(String, String)> = HashSet::new();
+    for past_match in &past {
+        for a in &past_match.participants {
+            for b in &past_match.participants {
+                if a.user_id != b.user_id {
+                    played.insert((a.user_id.to_string(), b.user_id.to_string()));
+                }
+            }
+        }
+    }
+
+    let mut standings: Vec<&TournamentParticipant> = participants.iter().collect();
+    standings.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.joined_at.cmp(&b.joined_at))
+    });
+
+    let mut bye = None;
+    if standings.len() % 2 == 1 {
+        let idx = standings
+            .iter()
+            .rposition(|p| p.byes == 0)
+            .unwrap_or(standings.len() - 1);
+        bye = standings.remove(idx).id.clone();
+    }
+
+    let user_ids: Vec<String> = standings.iter().map(|p| p.user_id.to_string()).collect();
+    let mut round_matches: Vec<Match> = Vec::new();
+    for (a, b) in pair_players(&user_ids, &played) {
+        round_matches.push(new_match_for_seats(
+            tournament,
+            &[standings[a], standings[b]],
+        )?);
+    }
+    let matches_created = round_matches.len();
+
+    let mut claimed = db
+        .query(
+            "BEGIN TRANSACTION;
+             LET $claimed = (UPDATE $tournament_id
+                 SET current_round = $round, updated_at = time::now()
+                 WHERE current_round = $previous
+                 RETURN AFTER);
+             IF array::len($claimed) > 0 {
+                 FOR $round_match IN $matches {
+                     CREATE match CONTENT $round_match;
+                 };
+                 IF $bye != NONE {
+                     UPDATE $bye SET byes += 1;
+                 };
+             };
+             RETURN array::len($claimed) > 0;
+             COMMIT TRANSACTION;",
+        )
+        .bind(("tournament_id", tournament_id))
+        .bind(("round", round))
+        .bind(("previous", round - 1))
+        .bind(("matches", round_matches))
+        .bind(("bye", bye))
+        .await?;
+    let claimed: Option<bool> = claimed.take(0)?;
+    if claimed != Some(true) {
+        return Ok(0);
+    }
+    Ok(matches_created)
+}
+
+/// Pair players in standings order, each with the closest ranked player they
+/// have not met yet. When every rematch cannot be avoided, or the search runs
+/// out of budget, the longest rematch-free pairing found is kept and the rest
+/// are paired in standings order, still preferring opponents they have not met.
+fn pair_players(players: &[String], played: &HashSet<(String, String)>) -> Vec<(usize, usize)> {
+    let mut paired = vec![false; players.len()];
+    let mut pairs = Vec::new();
+    let mut best = Vec::new();
+    let mut budget = PAIRING_BUDGET;
+    if pair_from(
+        players,
+        played,
+        &mut paired,
+        &mut pairs,
+        &mut best,
+        &mut budget,
+    ) {
+        return pairs;
+    }
+
+    let mut paired = vec![false; players.len()];
+    for &(a, b) in &best {
+        paired[a] = true;
+        paired[b] = true;
+    }
+    let mut pairs = best;
+    while let Some(first) = paired.iter().position(|p| !p) {
+        paired[first] = true;
+        let open: Vec<usize> = ((first + 1)..players.len())
+            .filter(|&other| !paired[other])
+            .collect();
+        let fresh = open
+            .iter()
+            .find(|&&other| !played.contains(&(players[first].clone(), players[other].clone())));
+        let Some(&other) = fresh.or(open.first()) else {
+            break;
+        };
+        paired[other] = true;
+        pairs.push((first, other));
+    }
+    pairs
+}
+
+fn pair_from(
+    players: &[String],
+    played: &HashSet<(String, String)>,
+    paired: &mut [bool],
+    pairs: &mut Vec<(usize, usize)>,
+    best: &mut Vec<(usize, usize)>,
+    budget: &mut usize,
+) -> bool {
+    let Some(first) = paired.iter().position(|p| !p) else {
+        return true;
+    };
+    paired[first] = true;
+    for other in (first + 1)..players.len() {
+        if paired[other] || played.contains(&(players[first].clone(), players[other].clone())) {
+            continue;
+        }
+        if *budget == 0 {
+            break;
+        }
+        *budget -= 1;
+        paired[other] = true;
+        pairs.push((first, other));
+        if pairs.len() > best.len() {
+            *best = pairs.clone();
+        }
+        if pair_from(players, played, paired, pairs, best, budget) {
+            return true;
+        }
+        pairs.pop();
+        paired[other] = false;
+    }
+    paired[first] = false;
+    false
+}
@@ -2,40 +2,58 @@ use crate::{
     db::Database,
     error::{ApiError, ApiResult},
     models::{
-        tournament::{MatchGenerationType, Tournament, TournamentParticipant, TournamentStatus},
+        tournament::{
+            CreateTournamentRequest, MatchGenerationType, ResourceLimitOverrides,
+            SCHEDULE_ORDER_MESSAGE, Tournament, TournamentParticipant, TournamentStatus,
+            UpdateTournamentRequest, schedule_in_order,
+        },
         matches::{Match, MatchParticipant, MatchStatus},
         game::find_game_by_id,
     },
+    services::{bracket, swiss},
 };
-use chrono::{DateTime, Utc};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
 use surrealdb::sql::{Datetime, Thing};
 
+/// Create a tournament from a request the caller has already validated
 pub async fn create_tournament(
     db: &Database,
-    game_id: String,
-    name: String,
-    description: String,
-    min_players: u32,
-    max_players: u32,
-    start_time: Option<DateTime<Utc>>,
-    end_time: Option<DateTime<Utc>>,
-    match_generation_type: Option<MatchGenerationType>,
+    request: CreateTournamentRequest,
 ) -> ApiResult<Tournament> {
     // Verify game exists in hardcoded registry
-    find_game_by_id(&game_id)
+    let game = find_game_by_id(&request.game_id)
         .ok_or_else(|| ApiError::NotFound("Game not found".to_string()))?;
+    let match_generation_type = request.match_generation_type.unwrap_or_default();
+    // Only free-for-all tables fill more than two seats
+    if game.players_per_match != 2 && match_generation_type != MatchGenerationType::FreeForAll {
+        return Err(ApiError::BadRequest(format!(
+            "{} seats {} players per match, use free-for-all match generation",
+            game.name, game.players_per_match
+        )));
+    }
 
+    // Registration opening later is left to the scheduler
+    let status = match request.registration_time {
+        Some(opens) if opens > Utc::now() => TournamentStatus::Scheduled,
+        _ => TournamentStatus::Registration,
+    };
     let tournament = Tournament {
         id: None,
-        game_id,
-        name,
-        description,
-        status: TournamentStatus::Registration,
-        min_players,
-        max_players,
-        start_time: start_time.map(|dt| dt.into()),
-        end_time: end_time.map(|dt| dt.into()),
-        match_generation_type: match_generation_type.unwrap_or_default(),
+        game_id: request.game_id,
+        name: request.name,
+        description: request.description,
+        status,
+        min_players: request.min_players,
+        max_players: request.max_players,
+        registration_time: request.registration_time.map(|dt| dt.into()),
+        start_time: request.start_time.map(|dt| dt.into()),
+        end_time: request.end_time.map(|dt| dt.into()),
+        match_generation_type,
+        swiss_rounds: request.swiss_rounds,
+        current_round: 0,
+        resource_limits: request.resource_limits,
         created_at: Datetime::default(),
         updated_at: Datetime::default(),
     };
@@ -86,37 +104,81 @@ pub async fn list_tournaments(
     Ok(tournaments)
 }
 
+/// Apply the fields set in a validated update request. The schedule is checked
+/// against the stored times the request leaves unchanged.
 pub async fn update_tournament(
     db: &Database,
     tournament_id: Thing,
-    name: Option<String>,
-    description: Option<String>,
-    status: Option<TournamentStatus>,
-    start_time: Option<DateTime<Utc>>,
-    end_time: Option<DateTime<Utc>>,
+    request: UpdateTournamentRequest,
 ) -> ApiResult<Tournament> {
     let mut tournament = get_tournament(db, tournament_id.clone()).await?;
-    if let Some(n) = name {
+    if let Some(n) = request.name {
         tournament.name = n;
     }
-    if let Some(d) = description {
+    if let Some(d) = request.description {
         tournament.description = d;
     }
-    if let Some(s) = status {
-        tournament.status = s;
+    if let Some(rt) = request.registration_time {
+        tournament.registration_time = Some(rt.into());
     }
-    if let Some(st) = start_time {
+    if let Some(st) = request.start_time {
         tournament.start_time = Some(st.into());
     }
-    if let Some(et) = end_time {
+    if let Some(et) = request.end_time {
         tournament.end_time = Some(et.into());
     }
+    let in_order = schedule_in_order(
+        tournament.registration_time.clone().map(|t| t.0),
+        tournament.start_time.clone().map(|t| t.0),
+        tournament.end_time.clone().map(|t| t.0),
+    );
+    if !in_order {
+        return Err(ApiError::Validation(SCHEDULE_ORDER_MESSAGE.to_string()));
+    }
+    // Limits first, so a status change in the same request cannot lock them out
+    if let Some(limits) = request.resource_limits {
+        let limited = set_resource_limits(db, tournament_id.clone(), limits).await?;
+        tournament.resource_limits = limited.resource_limits;
+    }
+    if let Some(s) = request.status {
+        tournament.status = s;
+    }
     tournament.updated_at = Datetime::default();
     let key = (tournament_id.tb.as_str(), tournament_id.id.to_string());
     let updated: Option<Tournament> = db.update(key).content(tournament).await?;
     updated.ok_or_else(|| ApiError::NotFound("Tournament not found".to_string()))
 }
 
+/// Replace the bot limits a tournament overrides. Limits are fixed once the
+/// tournament starts so every match of it runs under the same budget.
+pub async fn set_resource_limits(
+    db: &Database,
+    tournament_id: Thing,
+    resource_limits: ResourceLimitOverrides,
+) -> ApiResult<Tournament> {
+    let mut result = db
+        .query(
+            "UPDATE $tournament_id
+             SET resource_limits = $resource_limits, updated_at = time::now()
+             WHERE status IN ['scheduled', 'registration']
+             RETURN AFTER",
+        )
+        .bind(("tournament_id", tournament_id.clone()))
+        .bind(("resource_limits", resource_limits))
+        .await?;
+    let updated: Vec<Tournament> = result.take(0)?;
+    match updated.into_iter().next() {
+        Some(tournament) => Ok(tournament),
+        None => {
+            // Tell a missing tournament apart from one that already started
+            get_tournament(db, tournament_id).await?;
+            Err(ApiError::BadRequest(
+                "Resource limits can only change before the tournament starts".to_string(),
+            ))
+        }
+    }
+}
+
 pub async fn join_tournament(
     db: &Database,
     tournament_id: Thing,
@@ -156,6 +218,7 @@ pub async fn join_tournament(
         submission_id: None,
         score: 0.0,
         rank: None,
+        byes: 0,
         joined_at: Datetime::default(),
     };
     let created: Option<TournamentParticipant> = db
@@ -177,6 +240,44 @@ pub async fn get_tournament_participants(
     Ok(participants)
 }
 
+/// Participants that can be scheduled: those with an accepted submission,
+/// each pointing at their latest accepted one. A newer submission that is
+/// still compiling or failed to compile does not take an entrant out.
+pub async fn get_match_ready_participants(
+    db: &Database,
+    tournament_id: Thing,
+) -> ApiResult<Vec<TournamentParticipant>> {
+    #[derive(Deserialize)]
+    struct AcceptedSubmission {
+        id: Thing,
+        user_id: Thing,
+    }
+    let mut result = db
+        .query(
+            "SELECT id, user_id, created_at FROM submission
+             WHERE tournament_id = $tournament_id AND status = 'accepted'
+             ORDER BY created_at DESC",
+        )
+        .bind(("tournament_id", tournament_id.clone()))
+        .await?;
+    let accepted: Vec<AcceptedSubmission> = result.take(0)?;
+    let mut latest: HashMap<String, Thing> = HashMap::new();
+    for submission in accepted {
+        latest
+            .entry(submission.user_id.to_string())
+            .or_insert(submission.id);
+    }
+
+    let participants = get_tournament_participants(db, tournament_id).await?;
+    Ok(participants
+        .into_iter()
+        .filter_map(|mut p| {
+            p.submission_id = Some(latest.get(&p.user_id.to_string())?.clone());
+            Some(p)
+        })
+        .collect())
+}
+
 pub async fn leave_tournament(
     db: &Database,
     tournament_id: Thing,
@@ -231,15 +332,13 @@ pub async fn start_tournament(db: &Database, tournament_id: Thing) -> ApiResult<
         )));
     }
 
-    // Filter participants who have submitted code
-    let participants_with_submissions: Vec<TournamentParticipant> = participants
-        .into_iter()
-        .filter(|p| p.submission_id.is_some())
-        .collect();
+    // Only participants whose code compiled take part
+    let participants_with_submissions =
+        get_match_ready_participants(db, tournament_id_thing.clone()).await?;
 
     if participants_with_submissions.is_empty() {
         return Err(ApiError::BadRequest(
-            "No participants have submitted code yet".to_string(),
+            "No participants have an accepted submission yet".to_string(),
         ));
     }
 
@@ -276,12 +375,21 @@ pub async fn start_tournament(db: &Database, tournament_id: Thing) -> ApiResult<
             generate_double_elimination_matches(db, &tournament, &participants_with_submissions)
                 .await
         }
+        MatchGenerationType::Swiss => {
+            swiss::generate_swiss(db, &tournament, &participants_with_submissions).await
+        }
+        MatchGenerationType::FreeForAll => {
+            generate_free_for_all_matches(db, &tournament, &participants_with_submissions).await
+        }
     };
     let matches_created = match matches_created {
         Ok(count) => count,
         Err(err) => {
             let _ = db
-                .query("DELETE match WHERE tournament_id = $tournament_id")
+                .query(
+                    "DELETE match WHERE tournament_id = $tournament_id;
+                     DELETE bracket_node WHERE tournament_id = $tournament_id;",
+                )
                 .bind(("tournament_id", tournament_id_thing.clone()))
                 .await;
             let _ = db
@@ -298,7 +406,10 @@ pub async fn start_tournament(db: &Database, tournament_id: Thing) -> ApiResult<
     if matches_created == 0 {
         // Roll back claim if no matches were generated
         let _ = db
-            .query("DELETE match WHERE tournament_id = $tournament_id")
+            .query(
+                "DELETE match WHERE tournament_id = $tournament_id;
+                 DELETE bracket_node WHERE tournament_id = $tournament_id;",
+            )
             .bind(("tournament_id", tournament_id_thing.clone()))
             .await;
         let _ = db
@@ -330,54 +441,64 @@ pub async fn start_tournament(db: &Database, tournament_id: Thing) -> ApiResult<
     Ok(updated_tournament)
 }
 
-async fn create_match_for_participants(
+pub async fn create_match_for_participants(
     db: &Database,
     tournament: &Tournament,
     p1: &TournamentParticipant,
     p2: &TournamentParticipant,
 ) -> ApiResult<()> {
-    let submission_id_1 = p1
-        .submission_id
-        .clone()
-        .ok_or_else(|| ApiError::Internal("Participant missing submission".to_string()))?;
-    let submission_id_2 = p2
-        .submission_id
-        .clone()
-        .ok_or_else(|| ApiError::Internal("Participant missing submission".to_string()))?;
+    create_match_for_seats(db, tournament, &[p1, p2]).await
+}
+
+/// Create a pending match with one seat per participant, in seat order
+pub async fn create_match_for_seats(
+    db: &Database,
+    tournament: &Tournament,
+    seats: &[&TournamentParticipant],
+) -> ApiResult<()> {
+    let match_data = new_match_for_seats(tournament, seats)?;
+    let _: Option<Match> = db.create("match").content(match_data).await?;
+    Ok(())
+}
+
+/// Pending match with one seat per participant, not stored yet
+pub fn new_match_for_seats(
+    tournament: &Tournament,
+    seats: &[&TournamentParticipant],
+) -> ApiResult<Match> {
+    let mut participants = Vec::with_capacity(seats.len());
+    for seat in seats {
+        let submission_id = seat
+            .submission_id
+            .clone()
+            .ok_or_else(|| ApiError::Internal("Participant missing submission".to_string()))?;
+        participants.push(MatchParticipant {
+            user_id: seat.user_id.clone(),
+            submission_id: Some(submission_id),
+            score: None,
+            verdict: None,
+            signal: None,
+        });
+    }
 
     let tournament_id = tournament
         .id
         .clone()
         .ok_or_else(|| ApiError::Internal("Tournament missing id".to_string()))?;
-    let match_data = Match {
+    Ok(Match {
         id: None,
         tournament_id: Some(tournament_id),
         game_id: tournament.game_id.clone(),
         status: MatchStatus::Pending,
-        participants: vec![
-            MatchParticipant {
-                user_id: p1.user_id.clone(),
-                submission_id: Some(submission_id_1),
-                score: None,
-            },
-            MatchParticipant {
-                user_id: p2.user_id.clone(),
-                submission_id: Some(submission_id_2),
-                score: None,
-            },
-        ],
+        participants,
         metadata: None,
         room_id: None,
-        game_event_source: None,
         judge_server_name: None,
         created_at: Datetime::default(),
         updated_at: Datetime::default(),
         started_at: None,
         completed_at: None,
-    };
-
-    let _: Option<Match> = db.create("match").content(match_data).await?;
-    Ok(())
+    })
 }
 
 /// Generate all vs all matches (each player plays against every player including themselves)
@@ -423,26 +544,55 @@ async fn generate_round_robin_matches(
 }
 
 /// Generate single elimination matches (bracket tournament)
+/// Only the first round is created here, later rounds follow as results come in
 async fn generate_single_elimination_matches(
-    _db: &Database,
-    _tournament: &Tournament,
-    _participants: &[TournamentParticipant],
+    db: &Database,
+    tournament: &Tournament,
+    participants: &[TournamentParticipant],
 ) -> ApiResult<usize> {
-    // TODO: Implement single elimination bracket generation
-    // This requires more complex logic for bracket seeding
-    Err(ApiError::BadRequest(
-        "Single elimination not yet implemented".to_string(),
-    ))
+    bracket::generate_single_elimination(db, tournament, participants).await
 }
 
 /// Generate double elimination matches (double bracket tournament)
+/// Losers bracket and grand final matches are created once their sources finish
 async fn generate_double_elimination_matches(
-    _db: &Database,
-    _tournament: &Tournament,
-    _participants: &[TournamentParticipant],
+    db: &Database,
+    tournament: &Tournament,
+    participants: &[TournamentParticipant],
+) -> ApiResult<usize> {
+    bracket::generate_double_elimination(db, tournament, participants).await
+}
+
+/// Generate free-for-all matches (players split into tables of the game's seat count)
+/// Table sizes differ by at most one; a player left alone at a table sits the round out
+async fn generate_free_for_all_matches(
+    db: &Database,
+    tournament: &Tournament,
+    participants: &[TournamentParticipant],
 ) -> ApiResult<usize> {
-    // TODO: Implement double elimination bracket generation
-    Err(ApiError::BadRequest(
-        "Double elimination not yet implemented".to_string(),
-    ))
+    let game = find_game_by_id(&tournament.game_id)
+        .ok_or_else(|| ApiError::NotFound("Game not found".to_string()))?;
+    let seats = (game.players_per_match as usize).max(2);
+    if participants.len() < 2 {
+        return Err(ApiError::BadRequest(
+            "Free-for-all needs at least 2 participants with accepted submissions".to_string(),
+        ));
+    }
+
+    let tables = participants.len().div_ceil(seats);
+    let base = participants.len() / tables;
+    let larger = participants.len() % tables;
+    let mut remaining = participants.iter();
+    let mut matches_created = 0;
+    for table in 0..tables {
+        let size = if table < larger { base + 1 } else { base };
+        let seated: Vec<&TournamentParticipant> = remaining.by_ref().take(size).collect();
+        if seated.len() < 2 {
+            continue;
+        }
+        create_match_for_seats(db, tournament, &seated).await?;
+        matches_created += 1;
+    }
+
+    Ok(matches_created)
 }
@@ -0,0 +1,410 @@
+use crate::{
+    db::Database,
+    error::{ApiError, ApiResult},
+    models::{
+        TwoFactorChallenge, TwoFactorSetupResponse, TwoFactorStatusResponse, User, UserRole,
+        UserTotp,
+    },
+    services::AuthService,
+};
+use chrono::{Duration, Utc};
+use qrcode::{QrCode, render::svg};
+use rand::Rng;
+use surrealdb::sql::{Datetime, Thing};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP_SECONDS: u64 = 30;
+const TOTP_SECRET_BYTES: usize = 20;
+const RECOVERY_CODE_COUNT: usize = 10;
+const MAX_CHALLENGE_ATTEMPTS: i64 = 5;
+const MAX_FAILED_LOGIN_CODES: i64 = 10;
+const LOGIN_LOCKOUT_MINUTES: i64 = 15;
+
+fn build_totp(secret: Vec<u8>, issuer: Option<String>, account_name: String) -> ApiResult<TOTP> {
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        1,
+        TOTP_STEP_SECONDS,
+        secret,
+        issuer,
+        account_name,
+    )
+    .map_err(|e| ApiError::Internal(format!("Invalid TOTP settings: {}", e)))
+}
+
+fn stored_totp(record: &UserTotp) -> ApiResult<TOTP> {
+    let secret = Secret::Encoded(record.secret.clone())
+        .to_bytes()
+        .map_err(|_| ApiError::Internal("Stored TOTP secret is invalid".to_string()))?;
+    build_totp(secret, None, String::new())
+}
+
+/// Time step a code was generated for, allowing a step of clock drift either way
+fn matching_step(totp: &TOTP, code: &str) -> Option<i64> {
+    let current = Utc::now().timestamp() as u64 / TOTP_STEP_SECONDS;
+    [current - 1, current, current + 1]
+        .into_iter()
+        .find(|step| totp.generate(step * TOTP_STEP_SECONDS) == code)
+        .map(|step| step as i64)
+}
+
+fn is_totp_code(code: &str) -> bool {
+    code.len() == TOTP_DIGITS && code.chars().all(|c| c.is_ascii_digit())
+}
+
+fn new_recovery_codes(auth_service: &AuthService) -> (Vec<String>, Vec<String>) {
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| auth_service.generate_recovery_code())
+        .collect();
+    let hashes = codes
+        .iter()
+        .map(|code| auth_service.hash_recovery_code(code))
+        .collect();
+    (codes, hashes)
+}
+
+/// Whether an instance setting forbids this user from going without a second factor
+pub fn is_two_factor_required(user: &User, require_for_admins: bool) -> bool {
+    require_for_admins && user.role == UserRole::Admin
+}
+
+pub async fn get_user_totp(db: &Database, user_id: Thing) -> ApiResult<Option<UserTotp>> {
+    let mut result = db
+        .query("SELECT * FROM user_totp WHERE user_id = $user_id")
+        .bind(("user_id", user_id))
+        .await?;
+    let records: Vec<UserTotp> = result.take(0)?;
+    Ok(records.into_iter().next())
+}
+
+pub async fn is_two_factor_enabled(db: &Database, user_id: Thing) -> ApiResult<bool> {
+    Ok(get_user_totp(db, user_id)
+        .await?
+        .is_some_and(|t| t.enabled_at.is_some()))
+}
+
+pub async fn get_two_factor_status(
+    db: &Database,
+    user: &User,
+    require_for_admins: bool,
+) -> ApiResult<TwoFactorStatusResponse> {
+    let user_id = user
+        .id
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("User ID is missing".to_string()))?
+        .clone();
+    let enabled = get_user_totp(db, user_id)
+        .await?
+        .filter(|t| t.enabled_at.is_some());
+    Ok(TwoFactorStatusResponse {
+        enabled: enabled.is_some(),
+        required: is_two_factor_required(user, require_for_admins),
+        recovery_codes_remaining: enabled.map_or(0, |t| t.recovery_code_hashes.len()),
+    })
+}
+
+/// Generate a new authenticator secret. It stays pending until a code from it
+/// is confirmed, so a half-finished setup never locks anyone out.
+pub async fn begin_enrollment(
+    db: &Database,
+    user: &User,
+    issuer: &str,
+) -> ApiResult<TwoFactorSetupResponse> {
+    let user_id = user
+        .id
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("User ID is missing".to_string()))?
+        .clone();
+    if is_two_factor_enabled(db, user_id.clone()).await? {
+        return Err(ApiError::Conflict(
+            "Two-factor authentication is already enabled".to_string(),
+        ));
+    }
+    let mut secret = [0u8; TOTP_SECRET_BYTES];
+    rand::rng().fill(&mut secret);
+    let totp = build_totp(
+        secret.to_vec(),
+        Some(issuer.to_string()),
+        user.email.clone(),
+    )?;
+    let otpauth_uri = totp.get_url();
+    let qr_code_svg = QrCode::new(otpauth_uri.as_bytes())
+        .map_err(|e| ApiError::Internal(format!("Failed to render QR code: {}", e)))?
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .build();
+
+    // Starting over replaces a setup that was never confirmed
+    db.query("DELETE user_totp WHERE user_id = $user_id AND enabled_at = NONE")
+        .bind(("user_id", user_id.clone()))
+        .await?;
+    let record = UserTotp {
+        id: None,
+        user_id,
+        secret: totp.get_secret_base32(),
+        enabled_at: None,
+        last_used_step: None,
+        recovery_code_hashes: Vec::new(),
+        failed_attempts: 0,
+        locked_until: None,
+        created_at: Datetime::default(),
+    };
+    let created: Option<UserTotp> = db.create("user_totp").content(record).await?;
+    let record = created
+        .ok_or_else(|| ApiError::Internal("Failed to start two-factor setup".to_string()))?;
+    Ok(TwoFactorSetupResponse {
+        secret: record.secret,
+        otpauth_uri,
+        qr_code_svg,
+    })
+}
+
+/// Turn on a pending authenticator with a code from it, returning the recovery codes
+pub async fn enable_two_factor(
+    db: &Database,
+    auth_service: &AuthService,
+    user_id: Thing,
+    code: &str,
+) -> ApiResult<Vec<String>> {
+    let record = get_user_totp(db, user_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Start two-factor setup first".to_string()))?;
+    if record.enabled_at.is_some() {
+        return Err(ApiError::Conflict(
+            "Two-factor authentication is already enabled".to_string(),
+        ));
+    }
+    let step = matching_step(&stored_totp(&record)?, code.trim())
+        .ok_or_else(|| ApiError::BadRequest("Invalid authentication code".to_string()))?;
+    let (codes, hashes) = new_recovery_codes(auth_service);
+    let mut result = db
+        .query(
+            "UPDATE $totp_id SET enabled_at = $now, last_used_step = $step,
+                recovery_code_hashes = $hashes
+             WHERE enabled_at = NONE RETURN AFTER",
+        )
+        .bind(("totp_id", record.id))
+        .bind(("now", Datetime::default()))
+        .bind(("step", step))
+        .bind(("hashes", hashes))
+        .await?;
+    let enabled: Vec<UserTotp> = result.take(0)?;
+    if enabled.is_empty() {
+        return Err(ApiError::Conflict(
+            "Two-factor authentication is already enabled".to_string(),
+        ));
+    }
+    Ok(codes)
+}
+
+/// Check an authenticator or recovery code. Each authenticator code works once,
+/// and a recovery code is used up.
+pub async fn verify_second_factor(
+    db: &Database,
+    auth_service: &AuthService,
+    user_id: Thing,
+    code: &str,
+) -> ApiResult<bool> {
+    let Some(record) = get_user_totp(db, user_id).await? else {
+        return Ok(false);
+    };
+    if record.enabled_at.is_none() {
+        return Ok(false);
+    }
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    if is_totp_code(&code) {
+        let Some(step) = matching_step(&stored_totp(&record)?, &code) else {
+            return Ok(false);
+        };
+        // Claim the step, so a code read over someone's shoulder cannot be replayed
+        let mut result = db
+            .query(
+                "UPDATE $totp_id SET last_used_step = $step
+                 WHERE last_used_step = NONE OR last_used_step < $step RETURN AFTER",
+            )
+            .bind(("totp_id", record.id))
+            .bind(("step", step))
+            .await?;
+        let claimed: Vec<UserTotp> = result.take(0)?;
+        return Ok(!claimed.is_empty());
+    }
+    let mut result = db
+        .query(
+            "UPDATE $totp_id SET recovery_code_hashes -= $code_hash
+             WHERE recovery_code_hashes CONTAINS $code_hash RETURN AFTER",
+        )
+        .bind(("totp_id", record.id))
+        .bind(("code_hash", auth_service.hash_recovery_code(&code)))
+        .await?;
+    let used: Vec<UserTotp> = result.take(0)?;
+    Ok(!used.is_empty())
+}
+
+/// Turn two-factor authentication off, which takes a valid code
+pub async fn disable_two_factor(
+    db: &Database,
+    auth_service: &AuthService,
+    user: &User,
+    code: &str,
+    require_for_admins: bool,
+) -> ApiResult<()> {
+    if is_two_factor_required(user, require_for_admins) {
+        return Err(ApiError::Forbidden(
+            "Two-factor authentication is required for admin accounts".to_string(),
+        ));
+    }
+    let user_id = user
+        .id
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("User ID is missing".to_string()))?
+        .clone();
+    if !is_two_factor_enabled(db, user_id.clone()).await? {
+        return Err(ApiError::BadRequest(
+            "Two-factor authentication is not enabled".to_string(),
+        ));
+    }
+    if !verify_second_factor(db, auth_service, user_id.clone(), code).await? {
+        return Err(ApiError::BadRequest(
+            "Invalid authentication code".to_string(),
+        ));
+    }
+    db.query("DELETE user_totp WHERE user_id = $user_id")
+        .bind(("user_id", user_id))
+        .await?;
+    Ok(())
+}
+
+/// Replace the recovery codes, for example once most are used up
+pub async fn regenerate_recovery_codes(
+    db: &Database,
+    auth_service: &AuthService,
+    user_id: Thing,
+    code: &str,
+) -> ApiResult<Vec<String>> {
+    if !is_two_factor_enabled(db, user_id.clone()).await? {
+        return Err(ApiError::BadRequest(
+            "Two-factor authentication is not enabled".to_string(),
+        ));
+    }
+    if !verify_second_factor(db, auth_service, user_id.clone(), code).await? {
+        return Err(ApiError::BadRequest(
+            "Invalid authentication code".to_string(),
+        ));
+    }
+    let (codes, hashes) = new_recovery_codes(auth_service);
+    db.query("UPDATE user_totp SET recovery_code_hashes = $hashes WHERE user_id = $user_id")
+        .bind(("user_id", user_id))
+        .bind(("hashes", hashes))
+        .await?;
+    Ok(codes)
+}
+
+/// Hold back a login until its second factor is shown, returning the token naming it
+pub async fn create_challenge(
+    db: &Database,
+    auth_service: &AuthService,
+    user_id: Thing,
+    ttl_seconds: i64,
+) -> ApiResult<String> {
+    let now = Utc::now();
+    // Logins nobody finished are never consumed, clear them out as new ones start
+    db.query("DELETE two_factor_challenge WHERE expires_at < $now")
+        .bind(("now", Datetime::from(now)))
+        .await?;
+    let token = auth_service.generate_two_factor_token();
+    let challenge = TwoFactorChallenge {
+        id: None,
+        token_hash: auth_service.hash_two_factor_token(&token),
+        user_id,
+        attempts: 0,
+        expires_at: (now + Duration::seconds(ttl_seconds)).into(),
+    };
+    let created: Option<TwoFactorChallenge> =
+        db.create("two_factor_challenge").content(challenge).await?;
+    created.ok_or_else(|| ApiError::Internal("Failed to start two-factor login".to_string()))?;
+    Ok(token)
+}
+
+/// Hold back a user's logins once too many wrong codes were entered, whatever
+/// challenges they were spread over
+async fn lock_logins(db: &Database, user_id: Thing) -> ApiResult<()> {
+    db.query("UPDATE user_totp SET failed_attempts = 0, locked_until = $until WHERE user_id = $user_id")
+        .bind(("user_id", user_id))
+        .bind((
+            "until",
+            Datetime::from(Utc::now() + Duration::minutes(LOGIN_LOCKOUT_MINUTES)),
+        ))
+        .await?;
+    Ok(())
+}
+
+/// Answer a login's challenge, returning the user it was for. A challenge
+/// allows a few attempts only, and a user a few more over all their
+/// challenges, so codes cannot be guessed by signing in again and again.
+pub async fn complete_challenge(
+    db: &Database,
+    auth_service: &AuthService,
+    token: &str,
+    code: &str,
+) -> ApiResult<Thing> {
+    let token_hash = auth_service.hash_two_factor_token(token);
+    // Count the attempt before checking it, so parallel guesses share the limit
+    let mut result = db
+        .query(
+            "UPDATE two_factor_challenge SET attempts += 1
+             WHERE token_hash = $token_hash AND attempts < $max_attempts AND expires_at > $now
+             RETURN AFTER",
+        )
+        .bind(("token_hash", token_hash.clone()))
+        .bind(("max_attempts", MAX_CHALLENGE_ATTEMPTS))
+        .bind(("now", Datetime::default()))
+        .await?;
+    let challenges: Vec<TwoFactorChallenge> = result.take(0)?;
+    let challenge = challenges
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::Auth("Login has expired, sign in again".to_string()))?;
+
+    let too_many = || {
+        ApiError::TooManyRequests(
+            "Too many invalid authentication codes, try again later".to_string(),
+        )
+    };
+    let mut result = db
+        .query(
+            "UPDATE user_totp SET failed_attempts += 1
+             WHERE user_id = $user_id AND (locked_until = NONE OR locked_until <= $now)
+             RETURN AFTER",
+        )
+        .bind(("user_id", challenge.user_id.clone()))
+        .bind(("now", Datetime::default()))
+        .await?;
+    let counted: Vec<UserTotp> = result.take(0)?;
+    let failed_attempts = counted.first().ok_or_else(too_many)?.failed_attempts;
+    if failed_attempts > MAX_FAILED_LOGIN_CODES {
+        lock_logins(db, challenge.user_id).await?;
+        return Err(too_many());
+    }
+    if !verify_second_factor(db, auth_service, challenge.user_id.clone(), code).await? {
+        if failed_attempts == MAX_FAILED_LOGIN_CODES {
+            lock_logins(db, challenge.user_id).await?;
+        }
+        return Err(ApiError::Auth("Invalid authentication code".to_string()));
+    }
+
+    // Only the request that removes the challenge starts a session
+    let mut result = db
+        .query("DELETE two_factor_challenge WHERE token_hash = $token_hash RETURN BEFORE")
+        .bind(("token_hash", token_hash))
+        .await?;
+    let deleted: Vec<TwoFactorChallenge> = result.take(0)?;
+    if deleted.is_empty() {
+        return Err(ApiError::Auth("Login has expired, sign in again".to_string()));
+    }
+    db.query("UPDATE user_totp SET failed_attempts = 0 WHERE user_id = $user_id")
+        .bind(("user_id", challenge.user_id.clone()))
+        .await?;
+    Ok(challenge.user_id)
+}
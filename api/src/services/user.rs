@@ -29,6 +29,8 @@ pub async fn create_user(
         updated_at: Datetime::default(),
         password_reset_token: None,
         password_reset_expires: None,
+        email_verified: false,
+        email_verification_sent_at: None,
     };
     let created: Option<User> = db.create("user").content(user).await?;
     created.ok_or_else(|| ApiError::Internal("Failed to create user".to_string()))
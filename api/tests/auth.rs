@@ -1,10 +1,16 @@
 mod db;
+mod smtp;
 
 use api::{
     config::Config,
-    models::{LoginRequest, RegisterRequest, User, UserRole},
-    services::{AuthService, auth, user},
+    error::ApiError,
+    models::{ApiTokenScope, LoginRequest, RegisterRequest, User, UserRole},
+    services::{
+        API_TOKEN_PREFIX, AuthService, EmailService, api_token, auth, email_verification, session,
+        two_factor, user,
+    },
 };
+use totp_rs::{Algorithm, Secret, TOTP};
 
 async fn get_bob_user(db: &api::db::Database) -> api::models::User {
     let config = Config::from_env();
@@ -45,12 +51,16 @@ async fn test_auth_service_jwt_generation() {
         updated_at: surrealdb::sql::Datetime::default(),
         password_reset_token: None,
         password_reset_expires: None,
+        email_verified: true,
+        email_verification_sent_at: None,
     };
-    let token = auth_service.generate_token(&user).unwrap();
+    let session_id = surrealdb::sql::Thing::from(("session", "test123"));
+    let token = auth_service.generate_token(&user, &session_id).unwrap();
     assert!(!token.is_empty());
     let claims = auth_service.validate_token(&token).unwrap();
     assert_eq!(claims.email, "test@example.com");
     assert_eq!(claims.role, UserRole::Player);
+    assert_eq!(claims.sid, session_id.to_string());
 }
 
 #[tokio::test]
@@ -136,8 +146,13 @@ async fn test_registration_and_login_flow() {
             .unwrap()
     );
 
-    // Generate token for user
-    let token = auth_service.generate_token(&bob_user).unwrap();
+    // Start a session and generate its token
+    let (bob_session, _) = session::create_session(&db, &auth_service, bob_user.id.clone().unwrap(), 3600, false)
+        .await
+        .unwrap();
+    let token = auth_service
+        .generate_token(&bob_user, bob_session.id.as_ref().unwrap())
+        .unwrap();
     assert!(!token.is_empty());
 
     // Validate token
@@ -188,3 +203,346 @@ async fn test_password_reset_flow() {
     restore_user.password_hash = Some(original_password_hash);
     user::update_user(&db, user_id, restore_user).await.unwrap();
 }
+
+#[tokio::test]
+async fn test_refresh_token_rotation_and_revocation() {
+    let db = db::setup_test_db().await;
+    let auth_service = AuthService::new("test-secret".to_string(), 3600);
+    let bob_id = get_bob_user(&db).await.id.unwrap();
+
+    let (first, refresh_token) = session::create_session(&db, &auth_service, bob_id.clone(), 3600, false)
+        .await
+        .unwrap();
+    let session_id = first.id.clone().unwrap();
+    assert_ne!(first.refresh_token_hash, refresh_token, "Refresh tokens are stored hashed");
+    assert!(session::is_session_active(&db, session_id.clone()).await.unwrap());
+
+    // Each refresh hands out a new token for the same session
+    let (rotated, next_token) = session::rotate_refresh_token(&db, &auth_service, &refresh_token, 3600)
+        .await
+        .unwrap();
+    assert_eq!(rotated.id, Some(session_id.clone()));
+    assert_ne!(next_token, refresh_token);
+
+    // Replaying the old token is treated as theft and ends the session
+    assert!(session::rotate_refresh_token(&db, &auth_service, &refresh_token, 3600).await.is_err());
+    assert!(!session::is_session_active(&db, session_id.clone()).await.unwrap());
+    assert!(session::rotate_refresh_token(&db, &auth_service, &next_token, 3600).await.is_err());
+
+    // Logging out everywhere ends every other session too
+    let (other, other_token) = session::create_session(&db, &auth_service, bob_id.clone(), 3600, false)
+        .await
+        .unwrap();
+    assert!(session::revoke_user_sessions(&db, bob_id).await.unwrap() >= 1);
+    assert!(!session::is_session_active(&db, other.id.unwrap()).await.unwrap());
+    assert!(session::rotate_refresh_token(&db, &auth_service, &other_token, 3600).await.is_err());
+    assert!(session::rotate_refresh_token(&db, &auth_service, "not-a-token", 3600).await.is_err());
+}
+
+#[tokio::test]
+async fn test_api_token_lifecycle() {
+    let db = db::setup_test_db().await;
+    let auth_service = AuthService::new("test-secret".to_string(), 3600);
+    let config = Config::from_env();
+    let bob_id = get_bob_user(&db).await.id.unwrap();
+    let alice_id = auth::get_user_by_email(&db, &config.alice.email)
+        .await
+        .unwrap()
+        .unwrap()
+        .id
+        .unwrap();
+
+    let (token, secret) = api_token::create_api_token(
+        &db,
+        &auth_service,
+        bob_id.clone(),
+        "ci".to_string(),
+        vec![ApiTokenScope::SubmissionsWrite, ApiTokenScope::SubmissionsWrite],
+        Some(30),
+    )
+    .await
+    .unwrap();
+    assert!(secret.starts_with(API_TOKEN_PREFIX));
+    assert_eq!(token.token_hash, auth_service.hash_api_token(&secret));
+    assert_eq!(token.scopes, vec![ApiTokenScope::SubmissionsWrite]);
+    assert!(token.expires_at.is_some());
+    assert!(token.last_used_at.is_none());
+
+    // Using the token records when it was last used
+    let used = api_token::authenticate_api_token(&db, &auth_service, &secret).await.unwrap();
+    assert_eq!(used.id, token.id);
+    assert!(used.last_used_at.is_some());
+    assert!(api_token::authenticate_api_token(&db, &auth_service, "axt_wrong").await.is_err());
+
+    // Only its owner can revoke it, after which it stops working
+    let token_id = token.id.unwrap();
+    assert!(api_token::delete_api_token(&db, alice_id, token_id.clone()).await.is_err());
+    api_token::delete_api_token(&db, bob_id.clone(), token_id).await.unwrap();
+    assert!(api_token::authenticate_api_token(&db, &auth_service, &secret).await.is_err());
+
+    // Expired tokens are refused
+    let (expired, expired_secret) = api_token::create_api_token(
+        &db,
+        &auth_service,
+        bob_id.clone(),
+        "expired".to_string(),
+        vec![ApiTokenScope::MatchesRead],
+        None,
+    )
+    .await
+    .unwrap();
+    let expired_id = expired.id.unwrap();
+    db.query("UPDATE $token_id SET expires_at = time::now() - 1h")
+        .bind(("token_id", expired_id.clone()))
+        .await
+        .unwrap();
+    assert!(api_token::authenticate_api_token(&db, &auth_service, &expired_secret).await.is_err());
+    api_token::delete_api_token(&db, bob_id, expired_id).await.unwrap();
+}
+
+#[test]
+fn test_api_token_scopes_serialize_as_strings() {
+    let scopes: Vec<ApiTokenScope> =
+        serde_json::from_str(r#"["submissions:write", "matches:read"]"#).unwrap();
+    assert_eq!(scopes, vec![ApiTokenScope::SubmissionsWrite, ApiTokenScope::MatchesRead]);
+    assert!(serde_json::from_str::<ApiTokenScope>(r#""admin""#).is_err());
+}
+
+#[tokio::test]
+async fn test_email_verification_flow() {
+    let db = db::setup_test_db().await;
+    let auth_service = AuthService::new("test-secret".to_string(), 3600);
+    let mail_server = smtp::SmtpStandIn::start();
+    let mut email_config = Config::from_env().email;
+    email_config.smtp_host = "127.0.0.1".to_string();
+    email_config.smtp_port = mail_server.port;
+    email_config.smtp_use_tls = false;
+    let email_service = EmailService::new(email_config);
+
+    let name = format!("verify{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+    let email = format!("{}@example.com", name);
+    let new_user = user::create_user(
+        &db,
+        email.clone(),
+        name,
+        None,
+        "US".to_string(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(!new_user.email_verified);
+    let user_id = new_user.id.clone().unwrap();
+    assert!(matches!(
+        email_verification::require_verified_email(&db, user_id.clone()).await,
+        Err(ApiError::Forbidden(_))
+    ));
+
+    email_verification::send_verification_email(
+        &db,
+        &auth_service,
+        &email_service,
+        &new_user,
+        3600,
+        60,
+    )
+    .await
+    .unwrap();
+    let messages = mail_server.messages();
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].contains(&email));
+    let token = messages[0]
+        .split("/verify-email?token=")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .expect("Message should contain a verification link")
+        .to_string();
+
+    // Asking again right away is refused and sends nothing
+    assert!(matches!(
+        email_verification::send_verification_email(
+            &db,
+            &auth_service,
+            &email_service,
+            &new_user,
+            3600,
+            60,
+        )
+        .await,
+        Err(ApiError::TooManyRequests(_))
+    ));
+    assert_eq!(mail_server.messages().len(), 1);
+
+    // Only a link signed by us for email verification counts
+    let other_service = AuthService::new("other-secret".to_string(), 3600);
+    assert!(
+        email_verification::verify_email(&db, &other_service, &token)
+            .await
+            .is_err()
+    );
+    let session_id = surrealdb::sql::Thing::from(("session", "verify"));
+    let access_token = auth_service.generate_token(&new_user, &session_id).unwrap();
+    assert!(
+        email_verification::verify_email(&db, &auth_service, &access_token)
+            .await
+            .is_err()
+    );
+
+    let verified = email_verification::verify_email(&db, &auth_service, &token)
+        .await
+        .unwrap();
+    assert!(verified.email_verified);
+    email_verification::require_verified_email(&db, user_id.clone())
+        .await
+        .unwrap();
+    assert!(matches!(
+        email_verification::send_verification_email(
+            &db,
+            &auth_service,
+            &email_service,
+            &verified,
+            3600,
+            0,
+        )
+        .await,
+        Err(ApiError::BadRequest(_))
+    ));
+
+    db.query("DELETE $user_id")
+        .bind(("user_id", user_id))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_two_factor_enrollment_and_login() {
+    let db = db::setup_test_db().await;
+    let auth_service = AuthService::new("test-secret".to_string(), 3600);
+    let name = format!("totp{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+    let new_user = user::create_user(
+        &db,
+        format!("{}@example.com", name),
+        name,
+        None,
+        "US".to_string(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let user_id = new_user.id.clone().unwrap();
+
+    // Enrollment stays pending until a code from the authenticator is confirmed
+    let setup = two_factor::begin_enrollment(&db, &new_user, "Axel Tournament")
+        .await
+        .unwrap();
+    assert!(setup.otpauth_uri.starts_with("otpauth://totp/"));
+    assert!(setup.otpauth_uri.contains(&setup.secret));
+    assert!(setup.qr_code_svg.contains("<svg"));
+    assert!(!two_factor::is_two_factor_enabled(&db, user_id.clone()).await.unwrap());
+    let secret = Secret::Encoded(setup.secret.clone()).to_bytes().unwrap();
+    let authenticator = TOTP::new(Algorithm::SHA1, 6, 1, 30, secret, None, String::new()).unwrap();
+    assert!(matches!(
+        two_factor::enable_two_factor(&db, &auth_service, user_id.clone(), "12345").await,
+        Err(ApiError::BadRequest(_))
+    ));
+    let code = authenticator.generate_current().unwrap();
+    let recovery_codes = two_factor::enable_two_factor(&db, &auth_service, user_id.clone(), &code)
+        .await
+        .unwrap();
+    assert_eq!(recovery_codes.len(), 10);
+    assert!(two_factor::is_two_factor_enabled(&db, user_id.clone()).await.unwrap());
+    assert!(matches!(
+        two_factor::begin_enrollment(&db, &new_user, "Axel Tournament").await,
+        Err(ApiError::Conflict(_))
+    ));
+
+    // A code is only good once
+    assert!(!two_factor::verify_second_factor(&db, &auth_service, user_id.clone(), &code).await.unwrap());
+
+    // A password login is held back until the second factor is shown
+    let token = two_factor::create_challenge(&db, &auth_service, user_id.clone(), 300)
+        .await
+        .unwrap();
+    assert!(matches!(
+        two_factor::complete_challenge(&db, &auth_service, &token, "wrong-code").await,
+        Err(ApiError::Auth(_))
+    ));
+    let recovered = two_factor::complete_challenge(&db, &auth_service, &token, &recovery_codes[0].to_uppercase())
+        .await
+        .unwrap();
+    assert_eq!(recovered, user_id);
+    assert!(two_factor::complete_challenge(&db, &auth_service, &token, &recovery_codes[1]).await.is_err());
+
+    // Recovery codes are used up, and a challenge only takes a few guesses
+    let token = two_factor::create_challenge(&db, &auth_service, user_id.clone(), 300)
+        .await
+        .unwrap();
+    assert!(two_factor::complete_challenge(&db, &auth_service, &token, &recovery_codes[0]).await.is_err());
+    for _ in 0..4 {
+        assert!(two_factor::complete_challenge(&db, &auth_service, &token, "wrong-code").await.is_err());
+    }
+    assert!(two_factor::complete_challenge(&db, &auth_service, &token, &recovery_codes[1]).await.is_err());
+
+    // Wrong codes add up over challenges until the user's logins are held back
+    let token = two_factor::create_challenge(&db, &auth_service, user_id.clone(), 300)
+        .await
+        .unwrap();
+    for _ in 0..5 {
+        assert!(matches!(
+            two_factor::complete_challenge(&db, &auth_service, &token, "wrong-code").await,
+            Err(ApiError::Auth(_))
+        ));
+    }
+    let token = two_factor::create_challenge(&db, &auth_service, user_id.clone(), 300)
+        .await
+        .unwrap();
+    assert!(matches!(
+        two_factor::complete_challenge(&db, &auth_service, &token, &recovery_codes[1]).await,
+        Err(ApiError::TooManyRequests(_))
+    ));
+    let status = two_factor::get_two_factor_status(&db, &new_user, true).await.unwrap();
+    assert!(status.enabled);
+    assert!(!status.required, "Only admins can be required to use two-factor");
+    assert_eq!(status.recovery_codes_remaining, 9);
+
+    // New recovery codes replace the old ones
+    let next_code = authenticator.generate(chrono::Utc::now().timestamp() as u64 + 30);
+    let new_codes = two_factor::regenerate_recovery_codes(&db, &auth_service, user_id.clone(), &next_code)
+        .await
+        .unwrap();
+    assert!(!two_factor::verify_second_factor(&db, &auth_service, user_id.clone(), &recovery_codes[1]).await.unwrap());
+
+    // Admins cannot turn it off when the instance requires it, anyone else can with a code
+    let admin = User {
+        role: UserRole::Admin,
+        ..new_user.clone()
+    };
+    assert!(matches!(
+        two_factor::disable_two_factor(&db, &auth_service, &admin, &new_codes[0], true).await,
+        Err(ApiError::Forbidden(_))
+    ));
+    assert!(matches!(
+        two_factor::disable_two_factor(&db, &auth_service, &new_user, "wrong-code", true).await,
+        Err(ApiError::BadRequest(_))
+    ));
+    two_factor::disable_two_factor(&db, &auth_service, &new_user, &new_codes[0], true)
+        .await
+        .unwrap();
+    assert!(!two_factor::is_two_factor_enabled(&db, user_id.clone()).await.unwrap());
+
+    // Sessions remember whether they were started with a second factor
+    let (plain, _) = session::create_session(&db, &auth_service, user_id.clone(), 3600, false)
+        .await
+        .unwrap();
+    let plain_id = plain.id.unwrap();
+    assert!(!session::is_two_factor_session(&db, plain_id.clone()).await.unwrap());
+    session::mark_session_two_factor(&db, plain_id.clone()).await.unwrap();
+    assert!(session::is_two_factor_session(&db, plain_id).await.unwrap());
+
+    db.query("DELETE session WHERE user_id = $user_id; DELETE $user_id")
+        .bind(("user_id", user_id))
+        .await
+        .unwrap();
+}
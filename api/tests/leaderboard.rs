@@ -1,8 +1,11 @@
 mod db;
 
 use api::{
-    config::Config, services::{leaderboard, tournament, auth}
+    config::Config,
+    models::{CreateTournamentRequest, Match, MatchParticipant, MatchStatus},
+    services::{auth, leaderboard, rating, tournament, user},
 };
+use surrealdb::sql::{Datetime, Thing};
 
 fn unique_name(prefix: &str) -> String {
     let timestamp = std::time::SystemTime::now()
@@ -37,14 +40,19 @@ async fn test_leaderboard_ordering_and_limit() {
     // Create tournament
     let tournament = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("Leaderboard Tournament "),
-        "Test tournament".to_string(),
-        2,
-        32,
-        None,
-        None,
-        None,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Leaderboard Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 32,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
@@ -87,14 +95,19 @@ async fn test_tournament_specific_leaderboard() {
     // Create tournament
     let tournament = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("Specific Leaderboard Tournament "),
-        "Test tournament".to_string(),
-        2,
-        8,
-        None,
-        None,
-        None,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Specific Leaderboard Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 8,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
@@ -137,3 +150,116 @@ async fn test_tournament_specific_leaderboard() {
     assert!(leaderboard_entries[0].score >= leaderboard_entries[1].score);
     assert_eq!(leaderboard_entries[0].rank, 1);
 }
+
+#[test]
+fn test_glicko2_update_matches_reference_example() {
+    // Worked example from Glickman's description of Glicko-2
+    let player = rating::Glicko2 {
+        rating: 1500.0,
+        deviation: 200.0,
+        volatility: 0.06,
+    };
+    let opponent = |rating, deviation| rating::Glicko2 {
+        rating,
+        deviation,
+        volatility: 0.06,
+    };
+    let results = [
+        (opponent(1400.0, 30.0), 1.0),
+        (opponent(1550.0, 100.0), 0.0),
+        (opponent(1700.0, 300.0), 0.0),
+    ];
+    let updated = rating::glicko2_update(player, &results);
+    assert!((updated.rating - 1464.06).abs() < 0.01);
+    assert!((updated.deviation - 151.52).abs() < 0.01);
+    assert!((updated.volatility - 0.05999).abs() < 0.0001);
+}
+
+#[tokio::test]
+async fn test_rating_leaderboard_and_history() {
+    let db = db::setup_test_db().await;
+
+    let mut user_ids: Vec<Thing> = Vec::new();
+    for prefix in ["rated_winner", "rated_loser"] {
+        let name = unique_name(prefix);
+        let created = user::create_user(
+            &db,
+            format!("{}@example.com", name),
+            name,
+            None,
+            "US".to_string(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        user_ids.push(created.id.unwrap());
+    }
+
+    let completed = Match {
+        id: None,
+        tournament_id: None,
+        game_id: TEST_GAME_ID.to_string(),
+        status: MatchStatus::Completed,
+        participants: vec![
+            MatchParticipant {
+                user_id: user_ids[0].clone(),
+                submission_id: None,
+                score: Some(60.0),
+                verdict: None,
+                signal: None,
+            },
+            MatchParticipant {
+                user_id: user_ids[1].clone(),
+                submission_id: None,
+                score: Some(40.0),
+                verdict: None,
+                signal: None,
+            },
+        ],
+        metadata: None,
+        room_id: None,
+        judge_server_name: None,
+        created_at: Datetime::default(),
+        updated_at: Datetime::default(),
+        started_at: None,
+        completed_at: Some(Datetime::default()),
+    };
+    let created: Option<Match> = db.create("match").content(completed).await.unwrap();
+    let created = created.unwrap();
+
+    assert!(rating::rate_match(&db, &created).await.unwrap());
+    // A match is only ever rated once
+    assert!(!rating::rate_match(&db, &created).await.unwrap());
+
+    let winner = rating::get_rating(&db, user_ids[0].clone(), TEST_GAME_ID)
+        .await
+        .unwrap()
+        .unwrap();
+    let loser = rating::get_rating(&db, user_ids[1].clone(), TEST_GAME_ID)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(winner.rating > rating::DEFAULT_RATING);
+    assert!(loser.rating < rating::DEFAULT_RATING);
+    assert!(winner.deviation < rating::DEFAULT_DEVIATION);
+    assert_eq!(winner.matches_played, 1);
+
+    let entries = leaderboard::get_rating_leaderboard(&db, 1000, TEST_GAME_ID.to_string())
+        .await
+        .unwrap();
+    let position = |user_id: &Thing| {
+        entries
+            .iter()
+            .position(|e| e.user_id == user_id.to_string())
+            .unwrap()
+    };
+    assert!(position(&user_ids[0]) < position(&user_ids[1]));
+
+    let history = rating::get_rating_history(&db, user_ids[0].clone(), Some(TEST_GAME_ID.to_string()))
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].match_id, created.id.unwrap());
+    assert_eq!(history[0].rating, winner.rating);
+}
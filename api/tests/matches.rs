@@ -0,0 +1,158 @@
+mod db;
+
+use api::{
+    config::Config,
+    models::{Match, MatchLog, MatchParticipant, MatchStatus},
+    services::{auth, matches},
+};
+use surrealdb::sql::{Datetime, Thing};
+
+// Use hardcoded game IDs (games are now maintained by developers)
+const TEST_GAME_ID: &str = "rock-paper-scissors";
+
+async fn get_user_id(db: &api::db::Database, email: &str) -> Thing {
+    auth::get_user_by_email(db, email)
+        .await
+        .unwrap()
+        .expect("Seed user should exist")
+        .id
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_match_logs_only_visible_to_their_author() {
+    let db = db::setup_test_db().await;
+    let config = Config::from_env();
+    let bob_id = get_user_id(&db, &config.bob.email).await;
+    let alice_id = get_user_id(&db, &config.alice.email).await;
+
+    let participant = |user_id: &Thing| MatchParticipant {
+        user_id: user_id.clone(),
+        submission_id: None,
+        score: Some(1.0),
+        verdict: None,
+        signal: None,
+    };
+    let completed = Match {
+        id: None,
+        tournament_id: None,
+        game_id: TEST_GAME_ID.to_string(),
+        status: MatchStatus::Completed,
+        participants: vec![participant(&bob_id), participant(&alice_id)],
+        metadata: None,
+        room_id: None,
+        judge_server_name: None,
+        created_at: Datetime::default(),
+        updated_at: Datetime::default(),
+        started_at: None,
+        completed_at: Some(Datetime::default()),
+    };
+    let created: Option<Match> = db.create("match").content(completed).await.unwrap();
+    let match_id = created.unwrap().id.unwrap();
+
+    for (user_id, stderr) in [(&bob_id, "bob thinks"), (&alice_id, "alice thinks")] {
+        let log = MatchLog {
+            id: None,
+            match_id: match_id.clone(),
+            submission_id: Thing::from(("submission", user_id.id.to_string().as_str())),
+            user_id: user_id.clone(),
+            stderr: stderr.to_string(),
+            truncated: false,
+            created_at: Datetime::default(),
+        };
+        let _: Option<MatchLog> = db.create("match_log").content(log).await.unwrap();
+    }
+
+    let bob_logs = matches::get_match_logs(&db, match_id.clone(), Some(bob_id.clone()))
+        .await
+        .unwrap();
+    assert_eq!(bob_logs.len(), 1);
+    assert_eq!(bob_logs[0].user_id, bob_id);
+    assert_eq!(bob_logs[0].stderr, "bob thinks");
+
+    // Admins see every participant's log
+    let all_logs = matches::get_match_logs(&db, match_id, None).await.unwrap();
+    assert_eq!(all_logs.len(), 2);
+
+    let missing: Thing = "match:does_not_exist".parse().unwrap();
+    assert!(matches::get_match_logs(&db, missing, None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_match_replay_from_event_log() {
+    let db = db::setup_test_db().await;
+    let config = Config::from_env();
+    let bob_id = get_user_id(&db, &config.bob.email).await;
+    let alice_id = get_user_id(&db, &config.alice.email).await;
+
+    let participant = |user_id: &Thing| MatchParticipant {
+        user_id: user_id.clone(),
+        submission_id: None,
+        score: None,
+        verdict: None,
+        signal: None,
+    };
+    let running = Match {
+        id: None,
+        tournament_id: None,
+        game_id: TEST_GAME_ID.to_string(),
+        status: MatchStatus::Running,
+        participants: vec![participant(&bob_id), participant(&alice_id)],
+        metadata: None,
+        room_id: None,
+        judge_server_name: None,
+        created_at: Datetime::default(),
+        updated_at: Datetime::default(),
+        started_at: Some(Datetime::default()),
+        completed_at: None,
+    };
+    let created: Option<Match> = db.create("match").content(running).await.unwrap();
+    let match_id = created.unwrap().id.unwrap();
+
+    let events = [
+        serde_json::json!({"type": "game_init", "player_ids": [bob_id.to_string(), alice_id.to_string()], "rounds": 2}),
+        serde_json::json!({"type": "round_result", "round": 1, "moves": [0, 2]}),
+        serde_json::json!({"type": "round_result", "round": 2, "moves": [1, 2]}),
+        serde_json::json!({"type": "game_end", "scores": [1, 1]}),
+    ];
+    for (seq, event) in events.into_iter().enumerate() {
+        db.query(
+            "CREATE game_event CONTENT {
+                match_id: $match_id, seq: $seq, version: 1, event: $event, created_at: time::now()
+            }",
+        )
+        .bind(("match_id", match_id.clone()))
+        .bind(("seq", seq as u64))
+        .bind(("event", event))
+        .await
+        .unwrap()
+        .check()
+        .unwrap();
+    }
+
+    // Only finished matches can be replayed
+    assert!(
+        matches::get_match_replay(&db, match_id.clone())
+            .await
+            .is_err()
+    );
+
+    db.query("UPDATE $match_id SET status = 'completed', completed_at = time::now()")
+        .bind(("match_id", match_id.clone()))
+        .await
+        .unwrap();
+    let replay = matches::get_match_replay(&db, match_id).await.unwrap();
+    assert_eq!(replay.game_id, TEST_GAME_ID);
+    assert_eq!(
+        replay.players,
+        vec![bob_id.to_string(), alice_id.to_string()]
+    );
+    assert_eq!(replay.steps.len(), 3);
+    assert_eq!(replay.steps[1].moves[0].action, "rock");
+    assert_eq!(replay.steps[1].moves[1].action, "scissors");
+    let json = serde_json::to_value(&replay.steps[2].state).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"kind": "scores", "scores": [1, 1]})
+    );
+}
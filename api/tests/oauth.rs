@@ -0,0 +1,249 @@
+mod db;
+
+use api::{
+    config::{Config, OidcConfig},
+    error::ApiError,
+    models::OAuthProvider,
+    services::{AuthService, OAuthClient, OAuthIdentity, auth, identity},
+};
+use axum::{
+    Form, Json, Router,
+    extract::State,
+    routing::{get, post},
+};
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+const CLIENT_ID: &str = "axel-test-client";
+const NONCE: &str = "mock-nonce";
+
+#[derive(Deserialize)]
+struct TokenForm {
+    code: String,
+    client_id: String,
+}
+
+/// Start an OpenID Connect issuer on a free port, returning its URL. The code
+/// redeemed picks the id_token: `wrong-audience` and `unknown-key` issue ones
+/// a client must refuse, anything else a valid token for subject `mock-<code>`.
+async fn start_mock_idp() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let issuer = format!("http://{}", listener.local_addr().unwrap());
+    let app = Router::new()
+        .route("/.well-known/openid-configuration", get(discovery))
+        .route("/jwks", get(jwks))
+        .route("/token", post(token))
+        .with_state(issuer.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    issuer
+}
+
+async fn discovery(State(issuer): State<String>) -> Json<Value> {
+    Json(json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{}/authorize", issuer),
+        "token_endpoint": format!("{}/token", issuer),
+        "jwks_uri": format!("{}/jwks", issuer),
+    }))
+}
+
+async fn jwks() -> Json<Value> {
+    Json(serde_json::from_str(include_str!("fixtures/mock_idp_jwks.json")).unwrap())
+}
+
+async fn token(State(issuer): State<String>, Form(form): Form<TokenForm>) -> Json<Value> {
+    let now = chrono::Utc::now().timestamp();
+    let audience = if form.code == "wrong-audience" {
+        "someone-else".to_string()
+    } else {
+        form.client_id
+    };
+    let claims = json!({
+        "iss": issuer,
+        "sub": format!("mock-{}", form.code),
+        "aud": audience,
+        "iat": now,
+        "exp": now + 300,
+        "nonce": NONCE,
+        "email": format!("{}@mock-idp.test", form.code),
+        "email_verified": true,
+        "name": "Mock User",
+    });
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(
+        if form.code == "unknown-key" {
+            "rotated-away"
+        } else {
+            "mock-idp-1"
+        }
+        .to_string(),
+    );
+    let key = EncodingKey::from_rsa_pem(include_bytes!("fixtures/mock_idp_key.pem")).unwrap();
+    Json(json!({
+        "access_token": "mock-access-token",
+        "token_type": "Bearer",
+        "id_token": encode(&header, &claims, &key).unwrap(),
+    }))
+}
+
+fn oidc_config(issuer: &str, trust_email: bool) -> OidcConfig {
+    OidcConfig {
+        issuer: issuer.to_string(),
+        name: "Mock IdP".to_string(),
+        client_id: CLIENT_ID.to_string(),
+        client_secret: "mock-secret".to_string(),
+        redirect_uri: "http://localhost:8080/api/auth/oidc/callback".to_string(),
+        scopes: vec!["openid".to_string(), "email".to_string()],
+        trust_email,
+    }
+}
+
+#[tokio::test]
+async fn test_oidc_discovery_and_id_token_validation() {
+    let issuer = start_mock_idp().await;
+    let client = OAuthClient::discover_oidc(&oidc_config(&issuer, false))
+        .await
+        .unwrap();
+    assert_eq!(client.provider(), OAuthProvider::Oidc);
+    assert_eq!(client.info().name, "Mock IdP");
+
+    let url = client.authorize_url("state-1", "nonce-1").unwrap();
+    assert!(url.starts_with(&format!("{}/authorize?", issuer)));
+    assert!(url.contains(&format!("client_id={}", CLIENT_ID)));
+    assert!(url.contains("state=state-1"));
+    assert!(url.contains("nonce=nonce-1"));
+
+    let identity = client.identify("alice", NONCE).await.unwrap();
+    assert_eq!(identity.subject, "mock-alice");
+    assert_eq!(identity.email.as_deref(), Some("alice@mock-idp.test"));
+    assert!(identity.email_verified);
+    assert_eq!(identity.name.as_deref(), Some("Mock User"));
+
+    // Tokens minted for another flow, another client or with a foreign key are refused
+    assert!(client.identify("alice", "another-nonce").await.is_err());
+    assert!(client.identify("wrong-audience", NONCE).await.is_err());
+    assert!(client.identify("unknown-key", NONCE).await.is_err());
+
+    // The discovery document must be for the configured issuer
+    let misconfigured = oidc_config(&format!("{}/other", issuer), false);
+    assert!(OAuthClient::discover_oidc(&misconfigured).await.is_err());
+}
+
+#[tokio::test]
+async fn test_oauth_state_is_single_use() {
+    let db = db::setup_test_db().await;
+    let auth_service = AuthService::new("test-secret".to_string(), 3600);
+    let (state, nonce) =
+        identity::create_oauth_state(&db, &auth_service, OAuthProvider::Github, None, 600)
+            .await
+            .unwrap();
+    assert_ne!(state, nonce);
+    // A state only works on the callback of the provider it was made for
+    assert!(
+        identity::consume_oauth_state(&db, &auth_service, OAuthProvider::Google, &state)
+            .await
+            .is_err()
+    );
+
+    let (state, nonce) =
+        identity::create_oauth_state(&db, &auth_service, OAuthProvider::Github, None, 600)
+            .await
+            .unwrap();
+    let flow = identity::consume_oauth_state(&db, &auth_service, OAuthProvider::Github, &state)
+        .await
+        .unwrap();
+    assert_eq!(flow.nonce, nonce);
+    assert!(flow.link_user_id.is_none());
+    assert!(
+        identity::consume_oauth_state(&db, &auth_service, OAuthProvider::Github, &state)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_link_sign_in_and_unlink_identity() {
+    let db = db::setup_test_db().await;
+    let config = Config::from_env();
+    let issuer = start_mock_idp().await;
+    let client = OAuthClient::discover_oidc(&oidc_config(&issuer, false))
+        .await
+        .unwrap();
+    let bob = auth::get_user_by_email(&db, &config.bob.email)
+        .await
+        .unwrap()
+        .expect("Bob user should exist");
+    let bob_id = bob.id.clone().unwrap();
+    let alice_id = auth::get_user_by_email(&db, &config.alice.email)
+        .await
+        .unwrap()
+        .unwrap()
+        .id
+        .unwrap();
+    let account = OAuthIdentity {
+        provider: OAuthProvider::Oidc,
+        subject: format!("mock-{}", uuid::Uuid::new_v4()),
+        email: Some("bob@mock-idp.test".to_string()),
+        email_verified: true,
+        name: None,
+    };
+
+    // Bob links the account and can then sign in with it
+    let linked = identity::link_identity(&db, bob_id.clone(), &account)
+        .await
+        .unwrap();
+    let user = identity::sign_in_with_identity(&db, &client, &account, "US".to_string())
+        .await
+        .unwrap();
+    assert_eq!(user.id, bob.id);
+    let identities = identity::list_identities(&db, bob_id.clone())
+        .await
+        .unwrap();
+    assert!(identities.iter().any(|i| i.id == linked.id));
+
+    // The account cannot also be linked to Alice, nor a second one of the same provider to Bob
+    assert!(matches!(
+        identity::link_identity(&db, alice_id, &account).await,
+        Err(ApiError::Conflict(_))
+    ));
+    let second = OAuthIdentity {
+        subject: format!("mock-{}", uuid::Uuid::new_v4()),
+        ..account.clone()
+    };
+    assert!(matches!(
+        identity::link_identity(&db, bob_id.clone(), &second).await,
+        Err(ApiError::Conflict(_))
+    ));
+
+    // An issuer that is not trusted with emails cannot take over Bob's account by email
+    let stranger = OAuthIdentity {
+        email: Some(bob.email.clone()),
+        ..second.clone()
+    };
+    assert!(matches!(
+        identity::sign_in_with_identity(&db, &client, &stranger, "US".to_string()).await,
+        Err(ApiError::Conflict(_))
+    ));
+    let unverified = OAuthIdentity {
+        email_verified: false,
+        ..second
+    };
+    assert!(matches!(
+        identity::sign_in_with_identity(&db, &client, &unverified, "US".to_string()).await,
+        Err(ApiError::Forbidden(_))
+    ));
+
+    // Bob still has his password, so the account can be unlinked
+    identity::unlink_identity(&db, bob_id.clone(), linked.id.unwrap())
+        .await
+        .unwrap();
+    assert!(
+        identity::get_identity(&db, OAuthProvider::Oidc, &account.subject)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
@@ -0,0 +1,72 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// Mail server stand-in that accepts every message and keeps it in memory.
+/// It runs on its own threads, since the mailer blocks the test runtime.
+pub struct SmtpStandIn {
+    pub port: u16,
+    messages: Arc<Mutex<Vec<String>>>,
+}
+
+impl SmtpStandIn {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind SMTP stand-in");
+        let port = listener.local_addr().unwrap().port();
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let inbox = messages.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let inbox = inbox.clone();
+                thread::spawn(move || {
+                    let _ = serve(stream, inbox);
+                });
+            }
+        });
+        Self { port, messages }
+    }
+
+    /// Messages received so far, with quoted-printable line breaks undone
+    pub fn messages(&self) -> Vec<String> {
+        self.messages.lock().unwrap().clone()
+    }
+}
+
+fn serve(stream: TcpStream, inbox: Arc<Mutex<Vec<String>>>) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    writer.write_all(b"220 localhost SMTP stand-in\r\n")?;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let command = line.trim_end().to_ascii_uppercase();
+        if command.starts_with("DATA") {
+            writer.write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n")?;
+            let mut message = String::new();
+            loop {
+                line.clear();
+                if reader.read_line(&mut line)? == 0 {
+                    return Ok(());
+                }
+                if line == ".\r\n" {
+                    break;
+                }
+                message.push_str(&line);
+            }
+            let message = message.replace("=\r\n", "").replace("=3D", "=");
+            inbox.lock().unwrap().push(message);
+            writer.write_all(b"250 OK\r\n")?;
+        } else if command.starts_with("QUIT") {
+            writer.write_all(b"221 Bye\r\n")?;
+            return Ok(());
+        } else {
+            writer.write_all(b"250 OK\r\n")?;
+        }
+    }
+}
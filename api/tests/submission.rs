@@ -1,7 +1,7 @@
 mod db;
 use api::{
     config::Config,
-    models::{CreateSubmissionRequest, ProgrammingLanguage},
+    models::{CreateSubmissionRequest, CreateTournamentRequest, ProgrammingLanguage},
     services::{submission, tournament, auth},
 };
 use validator::Validate;
@@ -44,14 +44,19 @@ async fn test_submission_create() {
     // Create a tournament
     let tournament_data = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("Tournament "),
-        "Test tournament".to_string(),
-        2,
-        100,
-        None,
-        None,
-        None,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 100,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
@@ -87,14 +92,19 @@ async fn test_submission_get() {
     // Create tournament
     let tournament_data = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("Tournament "),
-        "Test tournament".to_string(),
-        2,
-        100,
-        None,
-        None,
-        None,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 100,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
@@ -134,14 +144,19 @@ async fn test_submission_list_by_user() {
     // Create tournament
     let tournament_data = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("Tournament "),
-        "Test tournament".to_string(),
-        2,
-        100,
-        None,
-        None,
-        None,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 100,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
@@ -213,14 +228,19 @@ async fn test_submission_workflow() {
     // Create tournament
     let tournament_data = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("Workflow Tournament "),
-        "Test tournament".to_string(),
-        2,
-        100,
-        None,
-        None,
-        None,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Workflow Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 100,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
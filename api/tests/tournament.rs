@@ -1,9 +1,16 @@
 mod db;
 use api::{
     config::Config,
-    models::{CreateTournamentRequest, MatchGenerationType, TournamentStatus},
-    services::{matches, submission, tournament, auth},
+    models::{
+        BracketSide, CreateTournamentRequest, MatchGenerationType, ResourceLimitOverrides,
+        SubmissionStatus, TournamentParticipant, TournamentStatus, UpdateTournamentRequest,
+    },
+    services::{
+        auth, bracket, completion, matches, scheduler, standings, submission, swiss, tournament,
+        user,
+    },
 };
+use chrono::{Duration, Utc};
 use surrealdb::sql::Thing;
 use validator::Validate;
 
@@ -34,19 +41,48 @@ async fn get_alice_user(db: &api::db::Database) -> api::models::User {
         .expect("Alice user should exist")
 }
 
+/// Submit code and mark it accepted, as the judge does once it compiles
+async fn submit_accepted(db: &api::db::Database, user_id: Thing, tournament_id: Thing) -> Thing {
+    let created = submission::create_submission(
+        db,
+        user_id,
+        tournament_id,
+        TEST_GAME_ID.to_string(),
+        api::models::ProgrammingLanguage::Rust,
+        "fn main() {}".to_string(),
+    )
+    .await
+    .unwrap();
+    let submission_id = created.id.unwrap();
+    submission::update_submission_status(
+        db,
+        submission_id.clone(),
+        SubmissionStatus::Accepted,
+        None,
+    )
+    .await
+    .unwrap();
+    submission_id
+}
+
 #[tokio::test]
 async fn test_create_and_get_tournament() {
     let db = db::setup_test_db().await;
     let tournament = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("Tournament "),
-        "Test tournament".to_string(),
-        2,
-        16,
-        None,
-        None,
-        None,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 16,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
@@ -63,14 +99,19 @@ async fn test_update_tournament_status() {
     let db = db::setup_test_db().await;
     let tournament = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("Status Tournament "),
-        "Test tournament".to_string(),
-        2,
-        8,
-        None,
-        None,
-        None,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Status Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 8,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
@@ -78,11 +119,15 @@ async fn test_update_tournament_status() {
     let updated = tournament::update_tournament(
         &db,
         tournament_id,
-        Some("Updated".to_string()),
-        None,
-        Some(TournamentStatus::Running),
-        None,
-        None,
+        UpdateTournamentRequest {
+            name: Some("Updated".to_string()),
+            description: None,
+            status: Some(TournamentStatus::Running),
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
@@ -95,14 +140,19 @@ async fn test_join_and_leave_tournament() {
     let db = db::setup_test_db().await;
     let tournament = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("Join Tournament "),
-        "Test tournament".to_string(),
-        2,
-        8,
-        None,
-        None,
-        None,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Join Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 8,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
@@ -137,9 +187,12 @@ fn test_create_tournament_request_validation() {
         description: "A test tournament".to_string(),
         min_players: 2,
         max_players: 100,
+        registration_time: None,
         start_time: None,
         end_time: None,
         match_generation_type: None,
+        swiss_rounds: None,
+        resource_limits: None,
     };
     assert!(valid_request.validate().is_ok());
     let low_min = CreateTournamentRequest {
@@ -148,9 +201,12 @@ fn test_create_tournament_request_validation() {
         description: "A test tournament".to_string(),
         min_players: 1,
         max_players: 100,
+        registration_time: None,
         start_time: None,
         end_time: None,
         match_generation_type: None,
+        swiss_rounds: None,
+        resource_limits: None,
     };
     assert!(low_min.validate().is_err());
     let high_max = CreateTournamentRequest {
@@ -159,11 +215,69 @@ fn test_create_tournament_request_validation() {
         description: "A test tournament".to_string(),
         min_players: 2,
         max_players: 1000,
+        registration_time: None,
         start_time: None,
         end_time: None,
         match_generation_type: None,
+        swiss_rounds: None,
+        resource_limits: None,
     };
     assert!(high_max.validate().is_err());
+    let huge_memory = CreateTournamentRequest {
+        game_id: TEST_GAME_ID.to_string(),
+        name: "Test Tournament".to_string(),
+        description: "A test tournament".to_string(),
+        min_players: 2,
+        max_players: 100,
+        registration_time: None,
+        start_time: None,
+        end_time: None,
+        match_generation_type: None,
+        swiss_rounds: None,
+        resource_limits: Some(ResourceLimitOverrides {
+            memory_limit_mb: Some(100_000),
+            ..Default::default()
+        }),
+    };
+    assert!(huge_memory.validate().is_err());
+    let stray_rounds = CreateTournamentRequest {
+        game_id: TEST_GAME_ID.to_string(),
+        name: "Test Tournament".to_string(),
+        description: "A test tournament".to_string(),
+        min_players: 2,
+        max_players: 100,
+        registration_time: None,
+        start_time: None,
+        end_time: None,
+        match_generation_type: Some(MatchGenerationType::RoundRobin),
+        swiss_rounds: Some(3),
+        resource_limits: None,
+    };
+    assert!(stray_rounds.validate().is_err());
+    let swiss = CreateTournamentRequest {
+        match_generation_type: Some(MatchGenerationType::Swiss),
+        ..stray_rounds
+    };
+    assert!(swiss.validate().is_ok());
+    let now = Utc::now();
+    let ends_before_start = CreateTournamentRequest {
+        start_time: Some(now + Duration::hours(2)),
+        end_time: Some(now + Duration::hours(1)),
+        ..swiss
+    };
+    assert!(ends_before_start.validate().is_err());
+    let opens_after_start = CreateTournamentRequest {
+        registration_time: Some(now + Duration::hours(3)),
+        start_time: Some(now + Duration::hours(2)),
+        end_time: None,
+        ..ends_before_start
+    };
+    assert!(opens_after_start.validate().is_err());
+    let in_order = CreateTournamentRequest {
+        registration_time: Some(now + Duration::hours(1)),
+        ..opens_after_start
+    };
+    assert!(in_order.validate().is_ok());
 }
 
 #[tokio::test]
@@ -190,14 +304,19 @@ async fn test_start_tournament_all_vs_all() {
     // Create tournament with AllVsAll match generation (default)
     let tournament = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("AllVsAll Tournament "),
-        "Test tournament".to_string(),
-        2,
-        10,
-        None,
-        None,
-        Some(MatchGenerationType::AllVsAll),
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("AllVsAll Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::AllVsAll),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
@@ -214,16 +333,7 @@ async fn test_start_tournament_all_vs_all() {
             .await
             .unwrap();
 
-        submission::create_submission(
-            &db,
-            user_id.clone(),
-            tournament_id.clone(),
-            TEST_GAME_ID.to_string(),
-            api::models::ProgrammingLanguage::Rust,
-            "fn main() {}".to_string(),
-        )
-        .await
-        .unwrap();
+        submit_accepted(&db, user_id.clone(), tournament_id.clone()).await;
     }
 
     // Start tournament and generate matches
@@ -249,14 +359,19 @@ async fn test_start_tournament_round_robin() {
     // Create tournament with RoundRobin match generation
     let tournament = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("RoundRobin Tournament "),
-        "Test tournament".to_string(),
-        2,
-        10,
-        None,
-        None,
-        Some(MatchGenerationType::RoundRobin),
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("RoundRobin Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::RoundRobin),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
@@ -273,16 +388,7 @@ async fn test_start_tournament_round_robin() {
             .await
             .unwrap();
 
-        submission::create_submission(
-            &db,
-            user_id.clone(),
-            tournament_id.clone(),
-            TEST_GAME_ID.to_string(),
-            api::models::ProgrammingLanguage::Rust,
-            "fn main() {}".to_string(),
-        )
-        .await
-        .unwrap();
+        submit_accepted(&db, user_id.clone(), tournament_id.clone()).await;
     }
 
     // Start tournament and generate matches
@@ -302,85 +408,1298 @@ async fn test_start_tournament_round_robin() {
 }
 
 #[tokio::test]
-async fn test_start_tournament_without_submissions_fails() {
+async fn test_start_tournament_single_elimination() {
     let db = db::setup_test_db().await;
 
-    // Create tournament
     let tournament = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("NoSub Tournament "),
-        "Test tournament".to_string(),
-        2,
-        10,
-        None,
-        None,
-        None,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("SingleElim Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::SingleElimination),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
     )
     .await
     .unwrap();
     let tournament_id = tournament.id.clone().unwrap();
 
-    // Use Bob and Alice but don't submit code
+    // Three players so the top seed gets a bye
     let bob_user = get_bob_user(&db).await;
     let alice_user = get_alice_user(&db).await;
-    let users = vec![bob_user, alice_user];
+    let carol_name = unique_name("carol");
+    let carol_user = user::create_user(
+        &db,
+        format!("{}@example.com", carol_name),
+        carol_name,
+        None,
+        "US".to_string(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let user_ids: Vec<Thing> = vec![
+        bob_user.id.unwrap(),
+        alice_user.id.unwrap(),
+        carol_user.id.unwrap(),
+    ];
 
-    for user in users {
-        let user_id = user.id.unwrap();
+    for user_id in &user_ids {
         tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
             .await
             .unwrap();
+
+        submit_accepted(&db, user_id.clone(), tournament_id.clone()).await;
     }
 
-    // Try to start tournament - should fail because no submissions
-    let result = tournament::start_tournament(&db, tournament_id.clone()).await;
-    assert!(result.is_err());
+    let started_tournament = tournament::start_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(started_tournament.status, TournamentStatus::Running);
+
+    // 3 players fill a 4 slot bracket: one bye and one first round match
+    let created_matches =
+        matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+            .await
+            .unwrap();
+    assert_eq!(created_matches.len(), 1);
+
+    let nodes = bracket::get_bracket(&db, tournament_id.clone()).await.unwrap();
+    assert_eq!(nodes.len(), 3);
+    let bye = nodes
+        .iter()
+        .find(|n| n.round == 1 && n.player2.is_none())
+        .expect("Top seed should have a bye");
+    assert_eq!(bye.player1.as_ref().unwrap().seed, 1);
+    let final_node = nodes.iter().find(|n| n.round == 2).unwrap();
+    assert_eq!(final_node.player1.as_ref().unwrap().seed, 1);
+    assert!(final_node.match_id.is_none());
+
+    // Finish the first round match and let the bracket advance
+    let first_match_id = created_matches[0].id.clone().unwrap();
+    db.query(
+        "UPDATE $match_id SET status = 'completed',
+             participants[0].score = 0, participants[1].score = 1",
+    )
+    .bind(("match_id", first_match_id))
+    .await
+    .unwrap();
+    let scheduled = bracket::advance_bracket(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(scheduled, 1);
+    // Advancing again must not schedule the final twice
+    let scheduled_again = bracket::advance_bracket(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(scheduled_again, 0);
+
+    let nodes = bracket::get_bracket(&db, tournament_id.clone()).await.unwrap();
+    let final_node = nodes.iter().find(|n| n.round == 2).unwrap();
+    assert!(final_node.player2.is_some());
+    assert!(final_node.match_id.is_some());
+    let all_matches =
+        matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+            .await
+            .unwrap();
+    assert_eq!(all_matches.len(), 2);
 }
 
 #[tokio::test]
-async fn test_start_tournament_not_enough_players_fails() {
+async fn test_start_tournament_double_elimination_with_reset() {
     let db = db::setup_test_db().await;
 
-    // Create tournament requiring 5 minimum players
     let tournament = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("MinPlayers Tournament "),
-        "Test tournament".to_string(),
-        5,
-        10,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("DoubleElim Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::DoubleElimination),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+
+    let bob_user = get_bob_user(&db).await;
+    let alice_user = get_alice_user(&db).await;
+    let user_ids: Vec<Thing> = vec![bob_user.id.unwrap(), alice_user.id.unwrap()];
+
+    for user_id in &user_ids {
+        tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
+            .await
+            .unwrap();
+
+        submit_accepted(&db, user_id.clone(), tournament_id.clone()).await;
+    }
+
+    let started_tournament = tournament::start_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(started_tournament.status, TournamentStatus::Running);
+
+    // Winners final, grand final and reset
+    let nodes = bracket::get_bracket(&db, tournament_id.clone()).await.unwrap();
+    assert_eq!(nodes.len(), 3);
+    let created_matches =
+        matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+            .await
+            .unwrap();
+    assert_eq!(created_matches.len(), 1);
+
+    // Seed 2 wins the winners final, seed 1 drops into the grand final
+    let winners_final = nodes
+        .iter()
+        .find(|n| n.bracket == BracketSide::Winners)
+        .unwrap();
+    let seed_two = winners_final.player2.clone().unwrap();
+    db.query(
+        "UPDATE $match_id SET status = 'completed',
+             participants[0].score = 0, participants[1].score = 1",
+    )
+    .bind(("match_id", winners_final.match_id.clone().unwrap()))
+    .await
+    .unwrap();
+    let scheduled = bracket::advance_bracket(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(scheduled, 1);
+
+    // The losers bracket champion (seed 1, slot 2) wins, forcing a reset
+    let nodes = bracket::get_bracket(&db, tournament_id.clone()).await.unwrap();
+    let grand_final = nodes
+        .iter()
+        .find(|n| n.bracket == BracketSide::GrandFinal && n.round == 1)
+        .unwrap();
+    assert_eq!(grand_final.player1.as_ref(), Some(&seed_two));
+    db.query(
+        "UPDATE $match_id SET status = 'completed',
+             participants[0].score = 0, participants[1].score = 1",
+    )
+    .bind(("match_id", grand_final.match_id.clone().unwrap()))
+    .await
+    .unwrap();
+    let scheduled = bracket::advance_bracket(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(scheduled, 1);
+
+    let nodes = bracket::get_bracket(&db, tournament_id.clone()).await.unwrap();
+    let reset = nodes
+        .iter()
+        .find(|n| n.bracket == BracketSide::GrandFinal && n.round == 2)
+        .unwrap();
+    assert!(reset.match_id.is_some());
+    let all_matches =
+        matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+            .await
+            .unwrap();
+    assert_eq!(all_matches.len(), 3);
+}
+
+async fn create_test_user(db: &api::db::Database, prefix: &str) -> Thing {
+    let name = unique_name(prefix);
+    user::create_user(
+        db,
+        format!("{}@example.com", name),
+        name,
         None,
+        "US".to_string(),
         None,
         None,
     )
     .await
+    .unwrap()
+    .id
+    .unwrap()
+}
+
+/// Complete the match of a bracket node in favour of `winner` and advance the bracket
+async fn play_bracket_node(
+    db: &api::db::Database,
+    tournament_id: &Thing,
+    side: BracketSide,
+    round: u32,
+    position: u32,
+    winner: &Thing,
+    scores: (f64, f64),
+) {
+    let nodes = bracket::get_bracket(db, tournament_id.clone()).await.unwrap();
+    let node = nodes
+        .iter()
+        .find(|n| n.bracket == side && n.round == round && n.position == position)
+        .unwrap();
+    let (winner_score, loser_score) = scores;
+    let (score1, score2) = if &node.player1.as_ref().unwrap().user_id == winner {
+        (winner_score, loser_score)
+    } else {
+        (loser_score, winner_score)
+    };
+    db.query(
+        "UPDATE $match_id SET status = 'completed',
+             participants[0].score = $score1, participants[1].score = $score2",
+    )
+    .bind(("match_id", node.match_id.clone().unwrap()))
+    .bind(("score1", score1))
+    .bind(("score2", score2))
+    .await
+    .unwrap();
+    bracket::advance_bracket(db, tournament_id.clone())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_single_elimination_standings_follow_the_bracket() {
+    let db = db::setup_test_db().await;
+
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("SingleElim Standings "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::SingleElimination),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
     .unwrap();
     let tournament_id = tournament.id.clone().unwrap();
 
-    // Try to start with 0 players - should fail
-    let result = tournament::start_tournament(&db, tournament_id.clone()).await;
-    assert!(result.is_err());
+    // Three players so the top seed gets a bye
+    for prefix in ["elim_a", "elim_b", "elim_c"] {
+        let user_id = create_test_user(&db, prefix).await;
+        tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
+            .await
+            .unwrap();
+        submit_accepted(&db, user_id, tournament_id.clone()).await;
+    }
+    let tournament = tournament::start_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+
+    let nodes = bracket::get_bracket(&db, tournament_id.clone()).await.unwrap();
+    let first_round = nodes
+        .iter()
+        .find(|n| n.round == 1 && n.player1.is_some() && n.player2.is_some())
+        .unwrap();
+    let top_seed = nodes
+        .iter()
+        .find(|n| n.round == 1 && n.player2.is_none())
+        .and_then(|n| n.player1.clone())
+        .unwrap()
+        .user_id;
+    let champion = first_round.player1.clone().unwrap().user_id;
+    let first_round_loser = first_round.player2.clone().unwrap().user_id;
+
+    // The first round loser scores more overall than the top seed, who then
+    // loses the final without a single win
+    play_bracket_node(
+        &db,
+        &tournament_id,
+        BracketSide::Winners,
+        1,
+        first_round.position,
+        &champion,
+        (6.0, 5.0),
+    )
+    .await;
+    play_bracket_node(&db, &tournament_id, BracketSide::Winners, 2, 0, &champion, (1.0, 0.0)).await;
+
+    let entries = standings::freeze_standings(&db, &tournament).await.unwrap();
+    let order: Vec<Thing> = entries.iter().map(|e| e.user_id.clone()).collect();
+    assert_eq!(order, vec![champion, top_seed, first_round_loser]);
 }
 
 #[tokio::test]
-async fn test_tournament_participant_management() {
+async fn test_double_elimination_standings_follow_the_bracket() {
     let db = db::setup_test_db().await;
 
-    // Create tournament
     let tournament = tournament::create_tournament(
         &db,
-        TEST_GAME_ID.to_string(),
-        unique_name("Participant Tournament "),
-        "Test tournament".to_string(),
-        2,
-        16,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("DoubleElim Standings "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::DoubleElimination),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+
+    for prefix in ["double_a", "double_b", "double_c", "double_d"] {
+        let user_id = create_test_user(&db, prefix).await;
+        tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
+            .await
+            .unwrap();
+        submit_accepted(&db, user_id, tournament_id.clone()).await;
+    }
+    let tournament = tournament::start_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+
+    let nodes = bracket::get_bracket(&db, tournament_id.clone()).await.unwrap();
+    let entrant = |position: u32, slot: u32| {
+        let node = nodes
+            .iter()
+            .find(|n| n.bracket == BracketSide::Winners && n.round == 1 && n.position == position)
+            .unwrap();
+        let player = if slot == 1 { &node.player1 } else { &node.player2 };
+        player.clone().unwrap().user_id
+    };
+    let (champion, fourth) = (entrant(0, 1), entrant(0, 2));
+    let (third, runner_up) = (entrant(1, 1), entrant(1, 2));
+
+    // The runner-up drops to the losers bracket, wins it with big margins and
+    // takes the grand final, then loses the reset. It ends on as many wins as
+    // the champion and a higher total score.
+    let side = BracketSide::Winners;
+    play_bracket_node(&db, &tournament_id, side, 1, 0, &champion, (1.0, 0.0)).await;
+    play_bracket_node(&db, &tournament_id, side, 1, 1, &third, (1.0, 0.0)).await;
+    play_bracket_node(&db, &tournament_id, side, 2, 0, &champion, (1.0, 0.0)).await;
+    let side = BracketSide::Losers;
+    play_bracket_node(&db, &tournament_id, side, 1, 0, &runner_up, (10.0, 0.0)).await;
+    play_bracket_node(&db, &tournament_id, side, 2, 0, &runner_up, (10.0, 0.0)).await;
+    let side = BracketSide::GrandFinal;
+    play_bracket_node(&db, &tournament_id, side, 1, 0, &runner_up, (10.0, 0.0)).await;
+    play_bracket_node(&db, &tournament_id, side, 2, 0, &champion, (1.0, 0.0)).await;
+
+    let entries = standings::freeze_standings(&db, &tournament).await.unwrap();
+    assert_eq!(entries[0].wins, entries[1].wins);
+    assert!(entries[1].score > entries[0].score);
+    let order: Vec<Thing> = entries.iter().map(|e| e.user_id.clone()).collect();
+    assert_eq!(order, vec![champion, runner_up, third, fourth]);
+}
+
+#[tokio::test]
+async fn test_start_tournament_swiss() {
+    let db = db::setup_test_db().await;
+
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Swiss Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::Swiss),
+            swiss_rounds: Some(2),
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+
+    // Three players so every round has a bye
+    let bob_user = get_bob_user(&db).await;
+    let alice_user = get_alice_user(&db).await;
+    let carol_name = unique_name("carol");
+    let carol_user = user::create_user(
+        &db,
+        format!("{}@example.com", carol_name),
+        carol_name,
         None,
+        "US".to_string(),
         None,
         None,
     )
     .await
     .unwrap();
+    let user_ids: Vec<Thing> = vec![
+        bob_user.id.unwrap(),
+        alice_user.id.unwrap(),
+        carol_user.id.unwrap(),
+    ];
+
+    for user_id in &user_ids {
+        tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
+            .await
+            .unwrap();
+
+        submit_accepted(&db, user_id.clone(), tournament_id.clone()).await;
+    }
+
+    let started_tournament = tournament::start_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(started_tournament.status, TournamentStatus::Running);
+    assert_eq!(started_tournament.current_round, 1);
+
+    let first_round =
+        matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+            .await
+            .unwrap();
+    assert_eq!(first_round.len(), 1);
+
+    // Nothing to do while the round is still being played
+    let scheduled = swiss::advance_swiss(&db, tournament_id.clone()).await.unwrap();
+    assert_eq!(scheduled, 0);
+
+    let first_match_id = first_round[0].id.clone().unwrap();
+    db.query("UPDATE $match_id SET status = 'completed'")
+        .bind(("match_id", first_match_id.clone()))
+        .await
+        .unwrap();
+    let scheduled = swiss::advance_swiss(&db, tournament_id.clone()).await.unwrap();
+    assert_eq!(scheduled, 1);
+
+    // The second round avoids a rematch and gives the bye to someone new
+    let all_matches =
+        matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+            .await
+            .unwrap();
+    assert_eq!(all_matches.len(), 2);
+    let second_match = all_matches
+        .iter()
+        .find(|m| m.id.as_ref() != Some(&first_match_id))
+        .unwrap();
+    let mut first_pair: Vec<String> = first_round[0]
+        .participants
+        .iter()
+        .map(|p| p.user_id.to_string())
+        .collect();
+    let mut second_pair: Vec<String> = second_match
+        .participants
+        .iter()
+        .map(|p| p.user_id.to_string())
+        .collect();
+    first_pair.sort();
+    second_pair.sort();
+    assert_ne!(first_pair, second_pair);
+    let participants = tournament::get_tournament_participants(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert!(participants.iter().all(|p| p.byes <= 1));
+    assert_eq!(participants.iter().filter(|p| p.byes == 1).count(), 2);
+
+    // Both configured rounds have been generated
+    db.query("UPDATE $match_id SET status = 'completed'")
+        .bind(("match_id", second_match.id.clone().unwrap()))
+        .await
+        .unwrap();
+    let scheduled = swiss::advance_swiss(&db, tournament_id.clone()).await.unwrap();
+    assert_eq!(scheduled, 0);
+    let finished = tournament::get_tournament(&db, tournament_id).await.unwrap();
+    assert_eq!(finished.current_round, 2);
+}
+
+#[tokio::test]
+async fn test_start_tournament_free_for_all() {
+    let db = db::setup_test_db().await;
+
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Free For All Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::FreeForAll),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+
+    let bob_user = get_bob_user(&db).await;
+    let alice_user = get_alice_user(&db).await;
+    let carol_name = unique_name("carol");
+    let carol_user = user::create_user(
+        &db,
+        format!("{}@example.com", carol_name),
+        carol_name,
+        None,
+        "US".to_string(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let user_ids: Vec<Thing> = vec![
+        bob_user.id.unwrap(),
+        alice_user.id.unwrap(),
+        carol_user.id.unwrap(),
+    ];
+
+    for user_id in &user_ids {
+        tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
+            .await
+            .unwrap();
+
+        submit_accepted(&db, user_id.clone(), tournament_id.clone()).await;
+    }
+
+    tournament::start_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+
+    // A two seat game splits three players into a full table and one who sits out
+    let created_matches =
+        matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+            .await
+            .unwrap();
+    assert_eq!(created_matches.len(), 1);
+    assert_eq!(created_matches[0].participants.len(), 2);
+}
+
+#[tokio::test]
+async fn test_three_seat_match_scores_and_standings() {
+    let db = db::setup_test_db().await;
+
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Three Seat Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::FreeForAll),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+
+    let bob_user = get_bob_user(&db).await;
+    let alice_user = get_alice_user(&db).await;
+    let carol_name = unique_name("carol");
+    let carol_user = user::create_user(
+        &db,
+        format!("{}@example.com", carol_name),
+        carol_name,
+        None,
+        "US".to_string(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    for user_id in [
+        bob_user.id.unwrap(),
+        alice_user.id.unwrap(),
+        carol_user.id.unwrap(),
+    ] {
+        tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
+            .await
+            .unwrap();
+
+        submit_accepted(&db, user_id, tournament_id.clone()).await;
+    }
+
+    // One table seating all three players, as a three seat game would
+    let participants = tournament::get_match_ready_participants(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(participants.len(), 3);
+    let seats: Vec<&TournamentParticipant> = participants.iter().collect();
+    tournament::create_match_for_seats(&db, &tournament, &seats)
+        .await
+        .unwrap();
+    let created_matches =
+        matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+            .await
+            .unwrap();
+    assert_eq!(created_matches.len(), 1);
+    let seated: Vec<Thing> = created_matches[0]
+        .participants
+        .iter()
+        .map(|p| p.user_id.clone())
+        .collect();
+    assert_eq!(seated.len(), 3);
+
+    // Scored the way the judge stores an N seat result, one field per seat
+    db.query(
+        "UPDATE $match_id SET status = 'completed',
+             participants[0].score = 1, participants[1].score = 3, participants[2].score = 2",
+    )
+    .bind(("match_id", created_matches[0].id.clone().unwrap()))
+    .await
+    .unwrap();
+
+    standings::update_standings(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    let ranked = tournament::get_tournament_participants(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    let rank_of = |user_id: &Thing| {
+        ranked
+            .iter()
+            .find(|p| &p.user_id == user_id)
+            .and_then(|p| p.rank)
+    };
+    assert_eq!(rank_of(&seated[1]), Some(1));
+    assert_eq!(rank_of(&seated[2]), Some(2));
+    assert_eq!(rank_of(&seated[0]), Some(3));
+
+    // Only the best score at the table wins, everyone else loses
+    let entries = standings::freeze_standings(&db, &tournament).await.unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].user_id, seated[1]);
+    assert_eq!(entries[0].wins, 1);
+    assert_eq!(entries[1].user_id, seated[2]);
+    assert_eq!(entries[1].losses, 1);
+    assert_eq!(entries[2].user_id, seated[0]);
+    assert_eq!(entries[2].losses, 1);
+}
+
+#[tokio::test]
+async fn test_update_standings_from_completed_matches() {
+    let db = db::setup_test_db().await;
+
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Standings Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::RoundRobin),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+
+    let bob_user = get_bob_user(&db).await;
+    let alice_user = get_alice_user(&db).await;
+    let user_ids: Vec<Thing> = vec![bob_user.id.unwrap(), alice_user.id.unwrap()];
+
+    for user_id in &user_ids {
+        tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
+            .await
+            .unwrap();
+
+        submit_accepted(&db, user_id.clone(), tournament_id.clone()).await;
+    }
+    tournament::start_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+
+    let created_matches =
+        matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+            .await
+            .unwrap();
+    let match_id = created_matches[0].id.clone().unwrap();
+    let winner_id = created_matches[0].participants[0].user_id.clone();
+    db.query(
+        "UPDATE $match_id SET status = 'completed',
+             participants[0].score = 3, participants[1].score = 1",
+    )
+    .bind(("match_id", match_id.clone()))
+    .await
+    .unwrap();
+
+    let updated = standings::update_standings(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(updated, 2);
+    let participants = tournament::get_tournament_participants(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(participants[0].user_id, winner_id);
+    assert_eq!(participants[0].score, 3.0);
+    assert_eq!(participants[0].rank, Some(1));
+    assert_eq!(participants[1].score, 1.0);
+    assert_eq!(participants[1].rank, Some(2));
+
+    // Running it again changes nothing
+    let updated = standings::update_standings(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(updated, 0);
+
+    // A re-queued match no longer counts until it completes again
+    db.query("UPDATE $match_id SET status = 'pending'")
+        .bind(("match_id", match_id))
+        .await
+        .unwrap();
+    standings::update_standings(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    let participants = tournament::get_tournament_participants(&db, tournament_id)
+        .await
+        .unwrap();
+    assert!(participants.iter().all(|p| p.score == 0.0));
+    assert!(participants.iter().all(|p| p.rank == Some(1)));
+}
+
+#[tokio::test]
+async fn test_complete_tournament_stores_final_standings() {
+    let db = db::setup_test_db().await;
+
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Completion Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::RoundRobin),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+
+    let bob_user = get_bob_user(&db).await;
+    let alice_user = get_alice_user(&db).await;
+    let user_ids: Vec<Thing> = vec![bob_user.id.unwrap(), alice_user.id.unwrap()];
+
+    for user_id in &user_ids {
+        tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
+            .await
+            .unwrap();
+
+        submit_accepted(&db, user_id.clone(), tournament_id.clone()).await;
+    }
+    tournament::start_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+
+    // Not finished while matches are still pending
+    let completed = completion::complete_tournament_if_finished(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert!(completed.is_none());
+    assert!(
+        completion::get_standings_snapshot(&db, tournament_id.clone())
+            .await
+            .is_err()
+    );
+
+    let created_matches =
+        matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+            .await
+            .unwrap();
+    let winner_id = created_matches[0].participants[0].user_id.clone();
+    for created_match in &created_matches {
+        db.query(
+            "UPDATE $match_id SET status = 'completed',
+                 participants[0].score = 3, participants[1].score = 1",
+        )
+        .bind(("match_id", created_match.id.clone().unwrap()))
+        .await
+        .unwrap();
+    }
+
+    let snapshot = completion::complete_tournament_if_finished(&db, tournament_id.clone())
+        .await
+        .unwrap()
+        .expect("Tournament should complete");
+    assert_eq!(snapshot.entries.len(), 2);
+    assert_eq!(snapshot.entries[0].rank, 1);
+    assert_eq!(snapshot.entries[1].rank, 2);
+    if created_matches.len() == 1 {
+        assert_eq!(snapshot.entries[0].user_id, winner_id);
+        assert_eq!(snapshot.entries[0].wins, 1);
+        assert_eq!(snapshot.entries[1].losses, 1);
+    }
+
+    let updated = tournament::get_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(updated.status, TournamentStatus::Completed);
+
+    // Completing twice does nothing and the snapshot stays as written
+    let again = completion::complete_tournament_if_finished(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert!(again.is_none());
+    let stored = completion::get_standings_snapshot(&db, tournament_id)
+        .await
+        .unwrap();
+    assert_eq!(stored.entries.len(), 2);
+    assert_eq!(stored.entries[0].user_id, snapshot.entries[0].user_id);
+
+    // Storing the final standings again is a no-op that returns the snapshot
+    let restored = completion::store_final_standings(&db, &updated)
+        .await
+        .unwrap();
+    assert_eq!(restored.id, stored.id);
+    let closed = completion::close_tournament(&db, &updated).await.unwrap();
+    assert!(closed.is_none());
+}
+
+#[tokio::test]
+async fn test_scheduler_runs_tournament_lifecycle() {
+    let db = db::setup_test_db().await;
+    let now = Utc::now();
+
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Scheduled Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: Some(now + Duration::hours(1)),
+            start_time: Some(now + Duration::hours(2)),
+            end_time: Some(now + Duration::hours(3)),
+            match_generation_type: Some(MatchGenerationType::RoundRobin),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+    assert_eq!(tournament.status, TournamentStatus::Scheduled);
+
+    // Nothing happens before the registration time
+    scheduler::open_due_registrations(&db).await.unwrap();
+    let current = tournament::get_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(current.status, TournamentStatus::Scheduled);
+
+    tournament::update_tournament(
+        &db,
+        tournament_id.clone(),
+        UpdateTournamentRequest {
+            name: None,
+            description: None,
+            status: None,
+            registration_time: Some(now - Duration::minutes(1)),
+            start_time: None,
+            end_time: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    scheduler::open_due_registrations(&db).await.unwrap();
+    let current = tournament::get_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(current.status, TournamentStatus::Registration);
+
+    let bob_user = get_bob_user(&db).await;
+    let alice_user = get_alice_user(&db).await;
+    for user_id in [bob_user.id.unwrap(), alice_user.id.unwrap()] {
+        tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
+            .await
+            .unwrap();
+
+        submit_accepted(&db, user_id, tournament_id.clone()).await;
+    }
+
+    tournament::update_tournament(
+        &db,
+        tournament_id.clone(),
+        UpdateTournamentRequest {
+            name: None,
+            description: None,
+            status: None,
+            registration_time: None,
+            start_time: Some(now - Duration::minutes(1)),
+            end_time: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    scheduler::start_due_tournaments(&db).await.unwrap();
+    let current = tournament::get_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(current.status, TournamentStatus::Running);
+
+    // An end time before the stored start time is rejected
+    let result = tournament::update_tournament(
+        &db,
+        tournament_id.clone(),
+        UpdateTournamentRequest {
+            name: None,
+            description: None,
+            status: None,
+            registration_time: None,
+            start_time: None,
+            end_time: Some(now - Duration::hours(1)),
+            resource_limits: None,
+        },
+    )
+    .await;
+    assert!(result.is_err());
+
+    // At the end time unplayed matches are cancelled and the standings frozen
+    tournament::update_tournament(
+        &db,
+        tournament_id.clone(),
+        UpdateTournamentRequest {
+            name: None,
+            description: None,
+            status: None,
+            registration_time: None,
+            start_time: None,
+            end_time: Some(now - Duration::minutes(1)),
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    scheduler::close_expired_tournaments(&db).await.unwrap();
+    let current = tournament::get_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(current.status, TournamentStatus::Completed);
+    let remaining = matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+        .await
+        .unwrap();
+    assert!(
+        remaining
+            .iter()
+            .all(|m| m.status == api::models::MatchStatus::Cancelled)
+    );
+    let snapshot = completion::get_standings_snapshot(&db, tournament_id)
+        .await
+        .unwrap();
+    assert_eq!(snapshot.entries.len(), 2);
+}
+
+#[tokio::test]
+async fn test_scheduler_cancels_tournament_that_never_started() {
+    let db = db::setup_test_db().await;
+    let now = Utc::now();
+
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Expired Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: Some(now - Duration::hours(2)),
+            end_time: Some(now - Duration::hours(1)),
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+    assert_eq!(tournament.status, TournamentStatus::Registration);
+
+    scheduler::close_expired_tournaments(&db).await.unwrap();
+    let current = tournament::get_tournament(&db, tournament_id)
+        .await
+        .unwrap();
+    assert_eq!(current.status, TournamentStatus::Cancelled);
+}
+
+#[tokio::test]
+async fn test_start_tournament_without_submissions_fails() {
+    let db = db::setup_test_db().await;
+
+    // Create tournament
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("NoSub Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+
+    // Use Bob and Alice but don't submit code
+    let bob_user = get_bob_user(&db).await;
+    let alice_user = get_alice_user(&db).await;
+    let users = vec![bob_user, alice_user];
+
+    for user in users {
+        let user_id = user.id.unwrap();
+        tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
+            .await
+            .unwrap();
+    }
+
+    // Try to start tournament - should fail because no submissions
+    let result = tournament::start_tournament(&db, tournament_id.clone()).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_start_tournament_only_schedules_accepted_submissions() {
+    let db = db::setup_test_db().await;
+
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Accepted Only Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: Some(MatchGenerationType::RoundRobin),
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+
+    let bob_id = get_bob_user(&db).await.id.unwrap();
+    let alice_id = get_alice_user(&db).await.id.unwrap();
+    let carol_name = unique_name("carol");
+    let carol_id = user::create_user(
+        &db,
+        format!("{}@example.com", carol_name),
+        carol_name,
+        None,
+        "US".to_string(),
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+    .id
+    .unwrap();
+    for user_id in [&bob_id, &alice_id, &carol_id] {
+        tournament::join_tournament(&db, tournament_id.clone(), user_id.clone())
+            .await
+            .unwrap();
+    }
+
+    // Bob's newer submission failed to compile, so his accepted one plays
+    let bob_accepted = submit_accepted(&db, bob_id.clone(), tournament_id.clone()).await;
+    let bob_broken = submission::create_submission(
+        &db,
+        bob_id.clone(),
+        tournament_id.clone(),
+        TEST_GAME_ID.to_string(),
+        api::models::ProgrammingLanguage::Rust,
+        "fn main( {".to_string(),
+    )
+    .await
+    .unwrap();
+    submission::update_submission_status(
+        &db,
+        bob_broken.id.unwrap(),
+        SubmissionStatus::Failed,
+        Some("expected type, found `{`".to_string()),
+    )
+    .await
+    .unwrap();
+    // Alice's code is still waiting for the judge
+    submission::create_submission(
+        &db,
+        alice_id.clone(),
+        tournament_id.clone(),
+        TEST_GAME_ID.to_string(),
+        api::models::ProgrammingLanguage::Rust,
+        "fn main() {}".to_string(),
+    )
+    .await
+    .unwrap();
+    let carol_accepted = submit_accepted(&db, carol_id.clone(), tournament_id.clone()).await;
+
+    tournament::start_tournament(&db, tournament_id.clone())
+        .await
+        .unwrap();
+
+    let created_matches =
+        matches::list_matches(&db, Some(tournament_id.clone()), None, None, None, None)
+            .await
+            .unwrap();
+    assert_eq!(created_matches.len(), 1);
+    let mut submissions: Vec<Thing> = created_matches[0]
+        .participants
+        .iter()
+        .filter_map(|p| p.submission_id.clone())
+        .collect();
+    submissions.sort_by_key(|id| id.to_string());
+    let mut expected = vec![bob_accepted, carol_accepted];
+    expected.sort_by_key(|id| id.to_string());
+    assert_eq!(submissions, expected);
+}
+
+#[tokio::test]
+async fn test_start_tournament_not_enough_players_fails() {
+    let db = db::setup_test_db().await;
+
+    // Create tournament requiring 5 minimum players
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("MinPlayers Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 5,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+
+    // Try to start with 0 players - should fail
+    let result = tournament::start_tournament(&db, tournament_id.clone()).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_resource_limits_fixed_once_started() {
+    let db = db::setup_test_db().await;
+
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Limits Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 10,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let tournament_id = tournament.id.clone().unwrap();
+    assert!(tournament.resource_limits.is_none());
+
+    let limits = ResourceLimitOverrides {
+        cpu_limit: Some(0.5),
+        memory_limit_mb: Some(128),
+        max_pids: None,
+        wall_clock_ms: Some(30_000),
+    };
+    let updated = tournament::set_resource_limits(&db, tournament_id.clone(), limits.clone())
+        .await
+        .unwrap();
+    assert_eq!(updated.resource_limits, Some(limits.clone()));
+
+    tournament::update_tournament(
+        &db,
+        tournament_id.clone(),
+        UpdateTournamentRequest {
+            name: None,
+            description: None,
+            status: Some(TournamentStatus::Running),
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
+    let result = tournament::set_resource_limits(&db, tournament_id.clone(), limits).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_tournament_participant_management() {
+    let db = db::setup_test_db().await;
+
+    // Create tournament
+    let tournament = tournament::create_tournament(
+        &db,
+        CreateTournamentRequest {
+            game_id: TEST_GAME_ID.to_string(),
+            name: unique_name("Participant Tournament "),
+            description: "Test tournament".to_string(),
+            min_players: 2,
+            max_players: 16,
+            registration_time: None,
+            start_time: None,
+            end_time: None,
+            match_generation_type: None,
+            swiss_rounds: None,
+            resource_limits: None,
+        },
+    )
+    .await
+    .unwrap();
     let tournament_id = tournament.id.unwrap();
 
     // Use Bob user
@@ -0,0 +1,85 @@
+//! Event log written by the judge while a match runs and read back by the
+//! API to build replays.
+
+use serde::{Deserialize, Serialize};
+
+/// Version stored with every event. Bump it when the fields of an event change
+/// so readers can tell old matches from new ones.
+pub const GAME_EVENT_VERSION: u32 = 1;
+
+/// Something that happened in a match. Games write these as they run and
+/// rebuild their state from them in `Game::restore_from_events`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    /// A room match was started by its host
+    MatchStart {
+        game_id: String,
+        players: usize,
+    },
+    /// The game has its players; rounds is None for games without a fixed count
+    GameInit {
+        player_ids: Vec<String>,
+        rounds: Option<u32>,
+    },
+    /// Simultaneous moves of one round, in seat order
+    RoundResult {
+        round: u32,
+        moves: Vec<u8>,
+    },
+    /// A seat placed a mark on the board
+    Move {
+        seat: usize,
+        row: usize,
+        col: usize,
+    },
+    Winner {
+        seat: usize,
+    },
+    Draw,
+    /// Final scores, in seat order
+    GameEnd {
+        scores: Vec<i32>,
+    },
+    /// Results of a room match as reported to its players
+    MatchEnd {
+        results: Vec<GameResult>,
+    },
+}
+
+/// Result of a player's performance in the game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GameResult {
+    Accepted(i32), // Score
+    TimeLimitExceeded,
+    WrongAnswer,
+    RuntimeError,
+    MemoryLimitExceeded,
+    Killed(i32),      // Signal that ended the bot
+    SeccompViolation, // Bot made a forbidden system call
+    OutputLimitExceeded,
+}
+
+impl GameResult {
+    /// Name of the verdict as stored on the match participant
+    pub fn verdict(&self) -> &'static str {
+        match self {
+            GameResult::Accepted(_) => "accepted",
+            GameResult::TimeLimitExceeded => "time_limit_exceeded",
+            GameResult::WrongAnswer => "wrong_answer",
+            GameResult::RuntimeError => "runtime_error",
+            GameResult::MemoryLimitExceeded => "memory_limit_exceeded",
+            GameResult::Killed(_) => "killed",
+            GameResult::SeccompViolation => "seccomp_violation",
+            GameResult::OutputLimitExceeded => "output_limit_exceeded",
+        }
+    }
+
+    /// Signal number for a bot that was killed
+    pub fn signal(&self) -> Option<i32> {
+        match self {
+            GameResult::Killed(signal) => Some(*signal),
+            _ => None,
+        }
+    }
+}
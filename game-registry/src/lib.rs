@@ -0,0 +1,154 @@
+//! Games known to the platform, shared by the API server and the judge.
+//! Adding a game means adding its metadata and replay renderer here and
+//! registering its implementation in the judge's game registry.
+
+use serde::{Deserialize, Serialize};
+
+pub mod events;
+pub mod payoff;
+pub mod replay;
+
+use replay::ReplayRenderer;
+
+// Game metadata (only serialized, never deserialized)
+// All games support both automated (bot) and interactive (human) modes
+#[derive(Debug, Clone, Serialize)]
+pub struct GameMetadata {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub supported_languages: &'static [ProgrammingLanguage],
+    pub rounds_per_match: u32,
+    pub repetitions: u32,
+    pub bot_timeout_ms: u32,
+    pub human_timeout_ms: u32,
+    pub cpu_limit: f64,
+    pub bot_turn_timeout_ms: u64,
+    pub human_turn_timeout_ms: u64,
+    pub memory_limit_mb: u64,
+    pub max_pids: u32,          // Processes and threads one bot may run
+    pub wall_clock_ms: u64,     // Time one bot may spend on its moves over a whole match
+    pub players_per_match: u32, // Seats in one automated match
+    #[serde(skip)]
+    pub replay: &'static dyn ReplayRenderer, // Builds replays from the match's event log
+}
+
+pub const ROCK_PAPER_SCISSORS: GameMetadata = GameMetadata {
+    id: "rock-paper-scissors",
+    name: "Rock Paper Scissors",
+    description: "Classic rock-paper-scissors game for 2 players",
+    supported_languages: &[
+        ProgrammingLanguage::Rust,
+        ProgrammingLanguage::Go,
+        ProgrammingLanguage::C,
+        ProgrammingLanguage::Python,
+        ProgrammingLanguage::JavaScript,
+    ],
+    rounds_per_match: 100,
+    repetitions: 1,
+    bot_timeout_ms: 5000,
+    human_timeout_ms: 30000,
+    cpu_limit: 1.0,
+    bot_turn_timeout_ms: 2000,
+    human_turn_timeout_ms: 10000,
+    memory_limit_mb: 64,
+    max_pids: 16,
+    wall_clock_ms: 60_000,
+    players_per_match: 2,
+    replay: &replay::ROCK_PAPER_SCISSORS_REPLAY,
+};
+
+pub const PRISONERS_DILEMMA: GameMetadata = GameMetadata {
+    id: "prisoners-dilemma",
+    name: "Prisoner's Dilemma",
+    description: "Classic game theory prisoner's dilemma",
+    supported_languages: &[
+        ProgrammingLanguage::Rust,
+        ProgrammingLanguage::Go,
+        ProgrammingLanguage::C,
+        ProgrammingLanguage::Python,
+        ProgrammingLanguage::JavaScript,
+    ],
+    rounds_per_match: 100,
+    repetitions: 1,
+    bot_timeout_ms: 5000,
+    human_timeout_ms: 30000,
+    cpu_limit: 1.0,
+    bot_turn_timeout_ms: 2000,
+    human_turn_timeout_ms: 10000,
+    memory_limit_mb: 64,
+    max_pids: 16,
+    wall_clock_ms: 60_000,
+    players_per_match: 2,
+    replay: &replay::PRISONERS_DILEMMA_REPLAY,
+};
+
+pub const TIC_TAC_TOE: GameMetadata = GameMetadata {
+    id: "tic-tac-toe",
+    name: "Tic Tac Toe",
+    description: "Classic tic-tac-toe game for 2 players",
+    supported_languages: &[
+        ProgrammingLanguage::Rust,
+        ProgrammingLanguage::Go,
+        ProgrammingLanguage::C,
+        ProgrammingLanguage::Python,
+        ProgrammingLanguage::JavaScript,
+    ],
+    rounds_per_match: 1,
+    repetitions: 1,
+    bot_timeout_ms: 60000,
+    human_timeout_ms: 120000,
+    cpu_limit: 1.0,
+    bot_turn_timeout_ms: 30000,
+    human_turn_timeout_ms: 60000,
+    memory_limit_mb: 64,
+    max_pids: 16,
+    wall_clock_ms: 120_000,
+    players_per_match: 2,
+    replay: &replay::TIC_TAC_TOE_REPLAY,
+};
+
+// Every game, in listing order
+pub static GAMES: &[GameMetadata] = &[ROCK_PAPER_SCISSORS, PRISONERS_DILEMMA, TIC_TAC_TOE];
+
+// Helper function to find a game by ID
+pub fn find_game_by_id(id: &str) -> Option<&'static GameMetadata> {
+    GAMES.iter().find(|g| g.id == id)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgrammingLanguage {
+    Rust,
+    Go,
+    C,
+    Python,
+    JavaScript,
+}
+
+impl std::str::FromStr for ProgrammingLanguage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rust" => Ok(Self::Rust),
+            "go" => Ok(Self::Go),
+            "c" => Ok(Self::C),
+            "python" => Ok(Self::Python),
+            "javascript" => Ok(Self::JavaScript),
+            _ => Err(format!("Unknown programming language: {}", s)),
+        }
+    }
+}
+
+impl ProgrammingLanguage {
+    pub fn to_extension(&self) -> &str {
+        match self {
+            Self::Rust => "rs",
+            Self::Go => "go",
+            Self::C => "c",
+            Self::Python => "py",
+            Self::JavaScript => "js",
+        }
+    }
+}
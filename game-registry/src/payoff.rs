@@ -0,0 +1,23 @@
+//! Points each seat scores in one round of the simultaneous-move games. The
+//! judge scores matches with these and replays are rendered with them, so a
+//! replay always adds up to the score the match was decided on.
+
+/// Rock-paper-scissors with 0 = rock, 1 = paper, 2 = scissors; the winner of
+/// the round gets a point, a draw gives none
+pub fn rock_paper_scissors(a: u8, b: u8) -> (i32, i32) {
+    match (a, b) {
+        (a, b) if a == b => (0, 0),
+        (0, 2) | (1, 0) | (2, 1) => (1, 0),
+        _ => (0, 1),
+    }
+}
+
+/// Prisoner's dilemma with 0 = cooperate, 1 = defect
+pub fn prisoners_dilemma(a: u8, b: u8) -> (i32, i32) {
+    match (a, b) {
+        (0, 0) => (3, 3), // Both cooperate
+        (0, _) => (0, 5), // Only the first defector profits
+        (_, 0) => (5, 0),
+        _ => (1, 1), // Both defect
+    }
+}
@@ -0,0 +1,213 @@
+//! Turn-by-turn replays rendered from a match's event log. Each game's
+//! registry entry carries its renderer, so the API can serve replays without
+//! knowing the rules of every game.
+
+use std::fmt::Debug;
+
+use serde::Serialize;
+
+use crate::events::GameEvent;
+
+/// A logged event with the time since the first event of the match
+#[derive(Debug, Clone)]
+pub struct TimedEvent {
+    pub event: GameEvent,
+    pub elapsed_ms: u64,
+}
+
+/// A completed match, one step per turn or round
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Replay {
+    pub game_id: String,
+    pub players: Vec<String>, // Player ids in seat order
+    pub steps: Vec<ReplayStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReplayStep {
+    pub turn: u32,       // 0 is the position before anyone moved
+    pub elapsed_ms: u64, // Since the first event of the match
+    pub moves: Vec<ReplayMove>,
+    pub state: ReplayState,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReplayMove {
+    pub seat: usize,
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub col: Option<usize>,
+}
+
+/// Game state after a step
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReplayState {
+    /// Cells row by row, holding the seat that marked them
+    Board {
+        cells: Vec<Option<usize>>,
+        next_seat: Option<usize>, // None once the game is over
+        winner: Option<usize>,
+    },
+    /// Running scores, in seat order
+    Scores { scores: Vec<i32> },
+}
+
+/// Builds the steps of a replay from the events of one match, in sequence order
+pub trait ReplayRenderer: Debug + Send + Sync {
+    fn render(&self, events: &[TimedEvent]) -> Vec<ReplayStep>;
+}
+
+/// Render the replay of a match of the given game
+pub fn render_replay(game: &crate::GameMetadata, events: &[TimedEvent]) -> Replay {
+    let players = events
+        .iter()
+        .find_map(|e| match &e.event {
+            GameEvent::GameInit { player_ids, .. } => Some(player_ids.clone()),
+            _ => None,
+        })
+        .unwrap_or_default();
+    Replay {
+        game_id: game.id.to_string(),
+        players,
+        steps: game.replay.render(events),
+    }
+}
+
+/// Games where every seat moves at once each round and scores add up
+#[derive(Debug)]
+pub struct RoundReplay {
+    pub actions: &'static [&'static str], // Name of each move, by its logged number
+    pub payoff: fn(u8, u8) -> (i32, i32),
+}
+
+pub const ROCK_PAPER_SCISSORS_REPLAY: RoundReplay = RoundReplay {
+    actions: &["rock", "paper", "scissors"],
+    payoff: crate::payoff::rock_paper_scissors,
+};
+
+pub const PRISONERS_DILEMMA_REPLAY: RoundReplay = RoundReplay {
+    actions: &["cooperate", "defect"],
+    payoff: crate::payoff::prisoners_dilemma,
+};
+
+impl ReplayRenderer for RoundReplay {
+    fn render(&self, events: &[TimedEvent]) -> Vec<ReplayStep> {
+        let mut steps = Vec::new();
+        let mut scores = vec![0; 2];
+        for timed in events {
+            match &timed.event {
+                GameEvent::GameInit { .. } => {
+                    scores = vec![0; 2];
+                    steps.clear();
+                    steps.push(ReplayStep {
+                        turn: 0,
+                        elapsed_ms: timed.elapsed_ms,
+                        moves: Vec::new(),
+                        state: ReplayState::Scores {
+                            scores: scores.clone(),
+                        },
+                    });
+                }
+                GameEvent::RoundResult { round, moves } if moves.len() >= 2 => {
+                    let (a, b) = (self.payoff)(moves[0], moves[1]);
+                    scores[0] += a;
+                    scores[1] += b;
+                    steps.push(ReplayStep {
+                        turn: *round,
+                        elapsed_ms: timed.elapsed_ms,
+                        moves: moves
+                            .iter()
+                            .enumerate()
+                            .map(|(seat, choice)| ReplayMove {
+                                seat,
+                                action: self
+                                    .actions
+                                    .get(*choice as usize)
+                                    .unwrap_or(&"invalid")
+                                    .to_string(),
+                                row: None,
+                                col: None,
+                            })
+                            .collect(),
+                        state: ReplayState::Scores {
+                            scores: scores.clone(),
+                        },
+                    });
+                }
+                _ => {}
+            }
+        }
+        steps
+    }
+}
+
+/// Tic-tac-toe, one step per placed mark
+#[derive(Debug)]
+pub struct TicTacToeReplay;
+
+pub const TIC_TAC_TOE_REPLAY: TicTacToeReplay = TicTacToeReplay;
+
+impl ReplayRenderer for TicTacToeReplay {
+    fn render(&self, events: &[TimedEvent]) -> Vec<ReplayStep> {
+        let mut steps: Vec<ReplayStep> = Vec::new();
+        let mut cells = vec![None; 9];
+        for timed in events {
+            match &timed.event {
+                GameEvent::GameInit { .. } => {
+                    cells = vec![None; 9];
+                    steps.clear();
+                    steps.push(ReplayStep {
+                        turn: 0,
+                        elapsed_ms: timed.elapsed_ms,
+                        moves: Vec::new(),
+                        state: ReplayState::Board {
+                            cells: cells.clone(),
+                            next_seat: Some(0),
+                            winner: None,
+                        },
+                    });
+                }
+                GameEvent::Move { seat, row, col } if row * 3 + col < 9 => {
+                    cells[row * 3 + col] = Some(*seat);
+                    steps.push(ReplayStep {
+                        turn: steps.len() as u32,
+                        elapsed_ms: timed.elapsed_ms,
+                        moves: vec![ReplayMove {
+                            seat: *seat,
+                            action: "place".to_string(),
+                            row: Some(*row),
+                            col: Some(*col),
+                        }],
+                        state: ReplayState::Board {
+                            cells: cells.clone(),
+                            next_seat: Some((seat + 1) % 2),
+                            winner: None,
+                        },
+                    });
+                }
+                // The game ending belongs to the move that ended it
+                GameEvent::Winner { seat } => {
+                    if let Some(ReplayState::Board {
+                        next_seat, winner, ..
+                    }) = steps.last_mut().map(|s| &mut s.state)
+                    {
+                        *next_seat = None;
+                        *winner = Some(*seat);
+                    }
+                }
+                GameEvent::Draw => {
+                    if let Some(ReplayState::Board { next_seat, .. }) =
+                        steps.last_mut().map(|s| &mut s.state)
+                    {
+                        *next_seat = None;
+                    }
+                }
+                _ => {}
+            }
+        }
+        steps
+    }
+}
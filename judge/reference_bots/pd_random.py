@@ -0,0 +1,9 @@
+import random
+import sys
+
+for line in sys.stdin:
+    line = line.strip()
+    if line == "START" or line.startswith("RESULT"):
+        print(random.choice(["C", "D"]), flush=True)
+    elif line == "END":
+        break
@@ -0,0 +1,12 @@
+import sys
+
+# Cooperate first, then repeat the opponent's last move
+for line in sys.stdin:
+    line = line.strip()
+    if line == "START":
+        print("C", flush=True)
+    elif line.startswith("RESULT"):
+        # RESULT <opponent move> <own move> <opponent score> <own score>
+        print(line.split()[1], flush=True)
+    elif line == "END":
+        break
@@ -0,0 +1,11 @@
+import random
+import sys
+
+MOVES = ["ROCK", "PAPER", "SCISSORS"]
+
+for line in sys.stdin:
+    line = line.strip()
+    if line == "START" or line.startswith("ROUND"):
+        print(random.choice(MOVES), flush=True)
+    elif line == "END":
+        break
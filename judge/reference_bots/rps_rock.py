@@ -0,0 +1,8 @@
+import sys
+
+for line in sys.stdin:
+    line = line.strip()
+    if line == "START" or line.startswith("ROUND"):
+        print("ROCK", flush=True)
+    elif line == "END":
+        break
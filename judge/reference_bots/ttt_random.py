@@ -0,0 +1,14 @@
+import random
+import sys
+
+board = "." * 9
+for line in sys.stdin:
+    line = line.strip()
+    if line.startswith("BOARD"):
+        board = line.split()[1]
+    elif line == "YOUR_TURN":
+        free = [i for i, cell in enumerate(board) if cell == "."]
+        cell = random.choice(free)
+        print("MOVE {} {}".format(cell // 3, cell % 3), flush=True)
+    elif line == "END":
+        break
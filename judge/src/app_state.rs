@@ -2,13 +2,15 @@ use std::sync::Arc;
 
 use crate::services::capacity::CapacityTracker;
 use crate::db::Database;
-use crate::models::game::Game;
+use crate::games::GameRegistry;
 use crate::services::room::RoomManager;
+use crate::services::spectator::SpectatorHub;
 
-pub struct AppState<G: Game> {
+pub struct AppState {
     pub db: Database,
-    pub game: G,
+    pub games: GameRegistry,
     pub capacity: CapacityTracker,
     pub room_manager: Arc<RoomManager>,
+    pub spectators: Arc<SpectatorHub>,
     pub jwt_secret: String,
 }
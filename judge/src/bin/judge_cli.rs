@@ -0,0 +1,155 @@
+//! Run bots against each other locally, without SurrealDB or a tournament.
+//!
+//! Usage: judge-cli <game-id> <bot> <bot> [<bot>...]
+//!
+//! Each bot is a source file, compiled in the same sandbox as submissions
+//! (.rs, .go, .c, .py, .js), or an already built executable.
+
+use anyhow::{Context, Result, bail};
+use game_registry::{GameMetadata, ProgrammingLanguage};
+use judge::games::{self, GameResult};
+use judge::models::players::{BotPlayer, Player};
+use judge::services::room::GameContext;
+use judge::services::sandbox::ResourceLimits;
+use judge::services::sandbox::compiler::CompilerSandbox;
+use std::path::Path;
+use surrealdb::sql::Thing;
+
+const USAGE: &str = "Usage: judge-cli <game-id> <bot> <bot> [<bot>...]";
+
+/// Submission language of a source file, None for anything else
+fn source_language(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()? {
+        "rs" => Some("rust"),
+        "go" => Some("go"),
+        "c" => Some("c"),
+        "py" => Some("python"),
+        "js" => Some("javascript"),
+        _ => None,
+    }
+}
+
+/// Compile a source file or take an executable as is
+async fn build_bot(
+    sandbox: &CompilerSandbox,
+    game: &GameMetadata,
+    seat: usize,
+    path: &Path,
+) -> Result<String> {
+    let Some(language) = source_language(path) else {
+        return Ok(path
+            .canonicalize()
+            .with_context(|| format!("Bot {} not found", path.display()))?
+            .to_string_lossy()
+            .to_string());
+    };
+    let supported = language
+        .parse::<ProgrammingLanguage>()
+        .is_ok_and(|lang| game.supported_languages.contains(&lang));
+    if !supported {
+        bail!("{} does not accept {} bots", game.name, language);
+    }
+
+    let code = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    eprintln!("Compiling {} as {}", path.display(), language);
+    sandbox
+        .compile(&format!("local_{}", seat + 1), language, &code)
+        .await
+        .with_context(|| format!("Failed to compile {}", path.display()))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Logs go to stderr so stdout only holds the match report
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((game_id, bots)) = args.split_first() else {
+        bail!(USAGE);
+    };
+    let registry = games::registry();
+    let Some(game) = registry.get(game_id) else {
+        let known: Vec<&str> = registry.iter().map(|g| g.metadata().id).collect();
+        bail!(
+            "Unknown game {}, expected one of: {}",
+            game_id,
+            known.join(", ")
+        );
+    };
+    if bots.len() < game.min_players() || bots.len() > game.max_players() {
+        bail!(
+            "{} needs {} to {} bots\n{}",
+            game.metadata().name,
+            game.min_players(),
+            game.max_players(),
+            USAGE
+        );
+    }
+    let metadata = game.metadata();
+
+    let workspace = tempfile::tempdir().context("Failed to create compile workspace")?;
+    let sandbox = CompilerSandbox::new(workspace.path().to_path_buf())?;
+    let mut binary_paths = Vec::with_capacity(bots.len());
+    for (seat, bot) in bots.iter().enumerate() {
+        binary_paths.push(build_bot(&sandbox, metadata, seat, Path::new(bot)).await?);
+    }
+
+    // Same players, limits and game loop as an automated match
+    let limits = ResourceLimits::for_game(metadata);
+    let mut players: Vec<Box<dyn Player>> = Vec::with_capacity(binary_paths.len());
+    let mut monitors = Vec::with_capacity(binary_paths.len());
+    for (seat, binary_path) in binary_paths.iter().enumerate() {
+        let seat_id = format!("p{}", seat + 1);
+        let mut player = BotPlayer::new(
+            Thing::from(("user", seat_id.as_str())),
+            binary_path,
+            limits.clone(),
+        )
+        .await?;
+        player.set_timeout(metadata.bot_turn_timeout_ms);
+        monitors.push(player.monitor());
+        players.push(Box::new(player));
+    }
+
+    let game_context = GameContext::in_memory(Thing::from(("match", "local")));
+    let results = game
+        .run(players, metadata.bot_turn_timeout_ms, game_context.clone())
+        .await;
+
+    println!("Event log:");
+    for record in game_context.events() {
+        println!(
+            "{:>4} {}",
+            record.seq,
+            serde_json::to_string(&record.event).unwrap_or_default()
+        );
+    }
+
+    println!("\nResults:");
+    for (seat, monitor) in monitors.iter().enumerate() {
+        let result = monitor.final_result(results.get(seat));
+        let outcome = match &result {
+            GameResult::Accepted(score) => format!("score {}", score),
+            GameResult::Killed(signal) => format!("signal {}", signal),
+            _ => String::new(),
+        };
+        println!(
+            "  seat {}  {:<22} {:<12} {}",
+            seat + 1,
+            result.verdict(),
+            outcome,
+            bots[seat]
+        );
+    }
+
+    for (seat, monitor) in monitors.iter().enumerate() {
+        let log = monitor.stderr_log().await;
+        if !log.text.is_empty() {
+            println!("\nStderr of seat {}:\n{}", seat + 1, log.text);
+        }
+    }
+    Ok(())
+}
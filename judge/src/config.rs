@@ -12,6 +12,7 @@ pub struct Config {
     pub database_pass: String,
     pub max_capacity: usize,
     pub max_claim_delay_ms: u64,
+    pub max_spectators: usize, // Spectators allowed per match or room
     pub jwt_secret: String,
     pub frontend_url: String,
 }
@@ -38,6 +39,10 @@ impl Config {
                 .unwrap_or_else(|_| "1000".to_string())
                 .parse()
                 .unwrap_or(1000),
+            max_spectators: env::var("MAX_SPECTATORS")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .unwrap_or(50),
             jwt_secret: env::var("JWT_SECRET").unwrap_or_else(|_| "supersecret".to_string()),
             frontend_url: env::var("FRONTEND_URL")
                 .unwrap_or_else(|_| "http://localhost:5173".to_string()),
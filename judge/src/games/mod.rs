@@ -6,4 +6,43 @@ pub use tic_tac_toe::TicTacToe;
 pub use rock_paper_scissors::RockPaperScissors;
 pub use prisoners_dilemma::PrisonersDilemma;
 pub use crate::models::game::{Game, GameResult};
-pub use crate::models::game_metadata::find_game_by_id;
+pub use game_registry::find_game_by_id;
+
+use std::sync::Arc;
+
+/// Game implementations this judge can run, looked up by game id
+#[derive(Clone, Default)]
+pub struct GameRegistry {
+    games: Vec<Arc<dyn Game>>,
+}
+
+impl GameRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<G: Game>(mut self, game: G) -> Self {
+        self.games.push(Arc::new(game));
+        self
+    }
+
+    pub fn get(&self, game_id: &str) -> Option<Arc<dyn Game>> {
+        self.games
+            .iter()
+            .find(|game| game.metadata().id == game_id)
+            .cloned()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn Game>> {
+        self.games.iter()
+    }
+}
+
+/// Every game served by the judge. Routes and match watchers are built from
+/// this list, so a new game only needs to be registered here.
+pub fn registry() -> GameRegistry {
+    GameRegistry::new()
+        .register(TicTacToe::new())
+        .register(RockPaperScissors::new())
+        .register(PrisonersDilemma::new())
+}
@@ -1,4 +1,8 @@
 use crate::models::game::{Game, GameResult};
+use crate::models::game_event::GameEvent;
+use async_trait::async_trait;
+use game_registry::GameMetadata;
+use game_registry::payoff::prisoners_dilemma;
 use crate::models::players::Player;
 use rand::Rng;
 use std::sync::{Arc, Mutex};
@@ -19,6 +23,7 @@ struct GameState {
     player_ids: Vec<String>,   // Maps player_id to player number (0 or 1)
 }
 
+#[async_trait]
 impl Game for PrisonersDilemma {
     fn new() -> Self {
         PrisonersDilemma {
@@ -34,6 +39,10 @@ impl Game for PrisonersDilemma {
         }
     }
 
+    fn metadata(&self) -> &'static GameMetadata {
+        &game_registry::PRISONERS_DILEMMA
+    }
+
     async fn run(&self, mut players: Vec<Box<dyn Player>>, timeout_ms: u64, game_context: crate::services::room::GameContext) -> Vec<GameResult> {
         const ROUNDS: u32 = 10;
         const ROUND_VAR: u32 = 3;
@@ -63,7 +72,7 @@ impl Game for PrisonersDilemma {
         };
 
         // Write game initialization to history
-        game_context.write_event(&format!("GAME_INIT {} {} {}", player_ids[0], player_ids[1], rounds)).await;
+        game_context.write_event(GameEvent::GameInit { player_ids: player_ids.clone(), rounds: Some(rounds) }).await;
 
         // Send start messages
         let _ = players[0].send_message("START").await;
@@ -110,13 +119,7 @@ impl Game for PrisonersDilemma {
 
             // Calculate scores based on prisoner's dilemma payoff matrix
             // (C,C) = (3,3), (C,D) = (0,5), (D,C) = (5,0), (D,D) = (1,1)
-            let (score0, score1) = match (moves[0], moves[1]) {
-                (0, 0) => (3, 3), // Both cooperate
-                (0, 1) => (0, 5), // P0 cooperates, P1 defects
-                (1, 0) => (5, 0), // P0 defects, P1 cooperates
-                (1, 1) => (1, 1), // Both defect
-                _ => unreachable!(),
-            };
+            let (score0, score1) = prisoners_dilemma(moves[0], moves[1]);
 
             // Update state
             {
@@ -131,7 +134,7 @@ impl Game for PrisonersDilemma {
             }
 
             // Write round result to history
-            game_context.write_event(&format!("ROUND_RESULT {} {} {}", round, moves[0], moves[1])).await;
+            game_context.write_event(GameEvent::RoundResult { round, moves: moves.clone() }).await;
 
             let scores = {
                 let state = self.state.lock().unwrap();
@@ -157,7 +160,7 @@ impl Game for PrisonersDilemma {
         };
 
         // Write game end to history
-        game_context.write_event(&format!("GAME_END {} {}", final_scores[0], final_scores[1])).await;
+        game_context.write_event(GameEvent::GameEnd { scores: final_scores.to_vec() }).await;
 
         // Send final results
         let _ = players[0].send_message(&format!("SCORE {}", final_scores[0])).await;
@@ -195,13 +198,7 @@ impl Game for PrisonersDilemma {
 
         for moves in state.round_history.iter() {
             // Calculate scores for this round
-            let (score0, score1) = match (moves[0], moves[1]) {
-                (0, 0) => (3, 3), // Both cooperate
-                (0, 1) => (0, 5), // P0 cooperates, P1 defects
-                (1, 0) => (5, 0), // P0 defects, P1 cooperates
-                (1, 1) => (1, 1), // Both defect
-                _ => unreachable!(),
-            };
+            let (score0, score1) = prisoners_dilemma(moves[0], moves[1]);
 
             scores[0] += score0;
             scores[1] += score1;
@@ -225,7 +222,7 @@ impl Game for PrisonersDilemma {
         messages
     }
 
-    fn restore_from_events(&self, events: &[String]) {
+    fn restore_from_events(&self, events: &[GameEvent]) {
         let mut state = self.state.lock().unwrap();
 
         // Reset state
@@ -238,52 +235,28 @@ impl Game for PrisonersDilemma {
         state.player_ids.clear();
 
         for event in events {
-            let parts: Vec<&str> = event.trim().split_whitespace().collect();
-            if parts.is_empty() {
-                continue;
-            }
-
-            match parts[0] {
-                "GAME_INIT" if parts.len() >= 4 => {
-                    state.player_ids = vec![parts[1].to_string(), parts[2].to_string()];
-                    if let Ok(total_rounds) = parts[3].parse::<u32>() {
-                        state.total_rounds = total_rounds;
-                    }
+            match event {
+                GameEvent::GameInit { player_ids, rounds } => {
+                    state.player_ids = player_ids.clone();
+                    state.total_rounds = rounds.unwrap_or(0);
                     state.game_started = true;
                 }
-                "ROUND_RESULT" if parts.len() >= 4 => {
-                    if let (Ok(round_num), Ok(move0), Ok(move1)) = (
-                        parts[1].parse::<u32>(),
-                        parts[2].parse::<u8>(),
-                        parts[3].parse::<u8>(),
-                    ) {
-                        state.current_round = round_num;
-                        state.round_history.push([move0, move1]);
-
-                        // Recalculate scores from round history
-                        state.scores = [0, 0];
-                        let history_clone = state.round_history.clone();
-                        for moves in &history_clone {
-                            let (score0, score1) = match (moves[0], moves[1]) {
-                                (0, 0) => (3, 3),
-                                (0, 1) => (0, 5),
-                                (1, 0) => (5, 0),
-                                (1, 1) => (1, 1),
-                                _ => (0, 0),
-                            };
-                            state.scores[0] += score0;
-                            state.scores[1] += score1;
-                        }
+                GameEvent::RoundResult { round, moves } if moves.len() >= 2 => {
+                    state.current_round = *round;
+                    state.round_history.push([moves[0], moves[1]]);
+
+                    // Recalculate scores from round history
+                    state.scores = [0, 0];
+                    let history_clone = state.round_history.clone();
+                    for moves in &history_clone {
+                        let (score0, score1) = prisoners_dilemma(moves[0], moves[1]);
+                        state.scores[0] += score0;
+                        state.scores[1] += score1;
                     }
                 }
-                "GAME_END" if parts.len() >= 3 => {
-                    if let (Ok(score0), Ok(score1)) = (
-                        parts[1].parse::<i32>(),
-                        parts[2].parse::<i32>(),
-                    ) {
-                        state.scores = [score0, score1];
-                        state.game_finished = true;
-                    }
+                GameEvent::GameEnd { scores } if scores.len() >= 2 => {
+                    state.scores = [scores[0], scores[1]];
+                    state.game_finished = true;
                 }
                 _ => {}
             }
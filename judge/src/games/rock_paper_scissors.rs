@@ -1,4 +1,8 @@
 use crate::models::game::{Game, GameResult};
+use crate::models::game_event::GameEvent;
+use async_trait::async_trait;
+use game_registry::GameMetadata;
+use game_registry::payoff::rock_paper_scissors;
 use crate::models::players::Player;
 use rand::Rng;
 use std::sync::{Arc, Mutex};
@@ -22,6 +26,7 @@ struct GameState {
 const ROUNDS: u32 = 5;
 const ROUND_VAR: u32 = 2;
 
+#[async_trait]
 impl Game for RockPaperScissors {
     fn new() -> Self {
         RockPaperScissors {
@@ -37,6 +42,10 @@ impl Game for RockPaperScissors {
         }
     }
 
+    fn metadata(&self) -> &'static GameMetadata {
+        &game_registry::ROCK_PAPER_SCISSORS
+    }
+
     async fn run(&self, mut players: Vec<Box<dyn Player>>, timeout_ms: u64, game_context: crate::services::room::GameContext) -> Vec<GameResult> {
         tracing::info!("RPS: Starting game with {} players, timeout: {}ms", players.len(), timeout_ms);
 
@@ -69,7 +78,7 @@ impl Game for RockPaperScissors {
         };
 
         // Write game initialization to history
-        game_context.write_event(&format!("GAME_INIT {} {} {}", player_ids[0], player_ids[1], rounds)).await;
+        game_context.write_event(GameEvent::GameInit { player_ids: player_ids.clone(), rounds: Some(rounds) }).await;
 
         // Send start messages
         tracing::debug!("RPS: Sending START messages to both players");
@@ -127,29 +136,16 @@ impl Game for RockPaperScissors {
 
             tracing::debug!("RPS: Both players submitted moves: {:?}", moves);
 
-            // Determine winner: 0=rock, 1=paper, 2=scissors
-            let winner = match (moves[0], moves[1]) {
-                (a, b) if a == b => {
-                    tracing::debug!("RPS: Round {} is a draw ({} vs {})", round, a, b);
-                    None
-                }, // Draw
-                (0, 2) | (1, 0) | (2, 1) => {
-                    tracing::debug!("RPS: Round {} won by player 0 ({} vs {})", round, moves[0], moves[1]);
-                    Some(0)
-                }, // Player 0 wins
-                _ => {
-                    tracing::debug!("RPS: Round {} won by player 1 ({} vs {})", round, moves[0], moves[1]);
-                    Some(1)
-                }, // Player 1 wins
-            };
+            // Score the round: 0=rock, 1=paper, 2=scissors
+            let (score0, score1) = rock_paper_scissors(moves[0], moves[1]);
+            tracing::debug!("RPS: Round {} scored {}-{} ({} vs {})", round, score0, score1, moves[0], moves[1]);
 
             // Update state
             {
                 let mut state = self.state.lock().unwrap();
-                if let Some(w) = winner {
-                    state.scores[w] += 1;
-                    tracing::debug!("RPS: Updated score for player {}, new scores: {:?}", w, state.scores);
-                }
+                state.scores[0] += score0;
+                state.scores[1] += score1;
+                tracing::debug!("RPS: New scores: {:?}", state.scores);
 
                 let _current_scores = state.scores;
 
@@ -158,7 +154,7 @@ impl Game for RockPaperScissors {
             }
 
             // Write round result to history
-            game_context.write_event(&format!("ROUND_RESULT {} {} {}", round, moves[0], moves[1])).await;
+            game_context.write_event(GameEvent::RoundResult { round, moves: moves.clone() }).await;
 
             let scores = {
                 let state = self.state.lock().unwrap();
@@ -182,7 +178,7 @@ impl Game for RockPaperScissors {
         };
 
         // Write game end to history
-        game_context.write_event(&format!("GAME_END {} {}", final_scores[0], final_scores[1])).await;
+        game_context.write_event(GameEvent::GameEnd { scores: final_scores.to_vec() }).await;
 
         // Send final results
         tracing::debug!("RPS: Sending final scores to players");
@@ -224,16 +220,10 @@ impl Game for RockPaperScissors {
         // Replay all completed rounds with scores (same format as live gameplay)
         let mut scores = [0, 0];
         for (round_idx, moves) in state.round_history.iter().enumerate() {
-            // Determine winner of this round
-            let winner = match (moves[0], moves[1]) {
-                (a, b) if a == b => None, // Draw
-                (0, 2) | (1, 0) | (2, 1) => Some(0), // Player 0 wins
-                _ => Some(1), // Player 1 wins
-            };
-
-            if let Some(w) = winner {
-                scores[w] += 1;
-            }
+            // Score this round
+            let (score0, score1) = rock_paper_scissors(moves[0], moves[1]);
+            scores[0] += score0;
+            scores[1] += score1;
 
             messages.push(format!(
                 "ROUND {} SCORE {} {}",
@@ -260,7 +250,7 @@ impl Game for RockPaperScissors {
         messages
     }
 
-    fn restore_from_events(&self, events: &[String]) {
+    fn restore_from_events(&self, events: &[GameEvent]) {
         let mut state = self.state.lock().unwrap();
 
         // Reset state
@@ -273,51 +263,28 @@ impl Game for RockPaperScissors {
         state.player_ids.clear();
 
         for event in events {
-            let parts: Vec<&str> = event.trim().split_whitespace().collect();
-            if parts.is_empty() {
-                continue;
-            }
-
-            match parts[0] {
-                "GAME_INIT" if parts.len() >= 4 => {
-                    state.player_ids = vec![parts[1].to_string(), parts[2].to_string()];
-                    if let Ok(total_rounds) = parts[3].parse::<u32>() {
-                        state.total_rounds = total_rounds;
-                    }
+            match event {
+                GameEvent::GameInit { player_ids, rounds } => {
+                    state.player_ids = player_ids.clone();
+                    state.total_rounds = rounds.unwrap_or(0);
                     state.game_started = true;
                 }
-                "ROUND_RESULT" if parts.len() >= 4 => {
-                    if let (Ok(round_num), Ok(move0), Ok(move1)) = (
-                        parts[1].parse::<u32>(),
-                        parts[2].parse::<u8>(),
-                        parts[3].parse::<u8>(),
-                    ) {
-                        state.current_round = round_num;
-                        state.round_history.push([move0, move1]);
-
-                        // Recalculate scores from round history
-                        state.scores = [0, 0];
-                        let history_clone = state.round_history.clone();
-                        for moves in &history_clone {
-                            let winner = match (moves[0], moves[1]) {
-                                (a, b) if a == b => None,
-                                (0, 2) | (1, 0) | (2, 1) => Some(0),
-                                _ => Some(1),
-                            };
-                            if let Some(w) = winner {
-                                state.scores[w as usize] += 1;
-                            }
-                        }
+                GameEvent::RoundResult { round, moves } if moves.len() >= 2 => {
+                    state.current_round = *round;
+                    state.round_history.push([moves[0], moves[1]]);
+
+                    // Recalculate scores from round history
+                    state.scores = [0, 0];
+                    let history_clone = state.round_history.clone();
+                    for moves in &history_clone {
+                        let (score0, score1) = rock_paper_scissors(moves[0], moves[1]);
+                        state.scores[0] += score0;
+                        state.scores[1] += score1;
                     }
                 }
-                "GAME_END" if parts.len() >= 3 => {
-                    if let (Ok(score0), Ok(score1)) = (
-                        parts[1].parse::<i32>(),
-                        parts[2].parse::<i32>(),
-                    ) {
-                        state.scores = [score0, score1];
-                        state.game_finished = true;
-                    }
+                GameEvent::GameEnd { scores } if scores.len() >= 2 => {
+                    state.scores = [scores[0], scores[1]];
+                    state.game_finished = true;
                 }
                 _ => {}
             }
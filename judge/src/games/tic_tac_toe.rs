@@ -1,4 +1,7 @@
 use crate::models::game::{Game, GameResult};
+use crate::models::game_event::GameEvent;
+use async_trait::async_trait;
+use game_registry::GameMetadata;
 use crate::models::players::Player;
 use std::sync::{Arc, Mutex};
 
@@ -18,6 +21,7 @@ struct GameState {
     player_ids: Vec<String>,   // Maps player_id to player number (0 or 1)
 }
 
+#[async_trait]
 impl Game for TicTacToe {
     fn new() -> Self {
         TicTacToe {
@@ -32,6 +36,10 @@ impl Game for TicTacToe {
         }
     }
 
+    fn metadata(&self) -> &'static GameMetadata {
+        &game_registry::TIC_TAC_TOE
+    }
+
     async fn run(&self, mut players: Vec<Box<dyn Player>>, timeout_ms: u64, game_context: crate::services::room::GameContext) -> Vec<GameResult> {
         if players.len() != 2 {
             return vec![GameResult::RuntimeError; players.len()];
@@ -55,7 +63,7 @@ impl Game for TicTacToe {
         };
 
         // Write game initialization to history
-        game_context.write_event(&format!("GAME_INIT {} {}", player_ids[0], player_ids[1])).await;
+        game_context.write_event(GameEvent::GameInit { player_ids: player_ids.clone(), rounds: None }).await;
 
         // Send start messages
         let _ = players[0].send_message("START X").await;
@@ -157,7 +165,7 @@ impl Game for TicTacToe {
             };
 
             // Write move to history
-            game_context.write_event(&format!("MOVE {} {} {}", current_turn, row, col)).await;
+            game_context.write_event(GameEvent::Move { seat: current_turn, row, col }).await;
 
             // Send updated board state
             let _ = players[0].send_message(&board_msg).await;
@@ -166,7 +174,7 @@ impl Game for TicTacToe {
             // Check winner
             if let Some(winner) = winner {
                 // Write winner to history
-                game_context.write_event(&format!("WINNER {}", winner)).await;
+                game_context.write_event(GameEvent::Winner { seat: winner }).await;
 
                 let _ = players[0].send_message(&format!("SCORE {}", if winner == 0 { 1 } else { 0 })).await;
                 let _ = players[1].send_message(&format!("SCORE {}", if winner == 1 { 1 } else { 0 })).await;
@@ -187,7 +195,7 @@ impl Game for TicTacToe {
         }
 
         // Write draw to history
-        game_context.write_event("DRAW").await;
+        game_context.write_event(GameEvent::Draw).await;
 
         let _ = players[0].send_message("SCORE 0").await;
         let _ = players[1].send_message("SCORE 0").await;
@@ -240,7 +248,7 @@ impl Game for TicTacToe {
         messages
     }
 
-    fn restore_from_events(&self, events: &[String]) {
+    fn restore_from_events(&self, events: &[GameEvent]) {
         let mut state = self.state.lock().unwrap();
 
         // Reset state
@@ -252,36 +260,23 @@ impl Game for TicTacToe {
         state.player_ids.clear();
 
         for event in events {
-            let parts: Vec<&str> = event.trim().split_whitespace().collect();
-            if parts.is_empty() {
-                continue;
-            }
-
-            match parts[0] {
-                "GAME_INIT" if parts.len() >= 3 => {
-                    state.player_ids = vec![parts[1].to_string(), parts[2].to_string()];
+            match event {
+                GameEvent::GameInit { player_ids, .. } => {
+                    state.player_ids = player_ids.clone();
                     state.game_started = true;
                 }
-                "MOVE" if parts.len() >= 4 => {
-                    if let (Ok(player_num), Ok(row), Ok(col)) = (
-                        parts[1].parse::<usize>(),
-                        parts[2].parse::<usize>(),
-                        parts[3].parse::<usize>(),
-                    ) {
-                        let pos = row * 3 + col;
-                        if pos < 9 {
-                            state.board[pos] = Some(player_num);
-                            state.current_turn = 1 - player_num;
-                        }
+                GameEvent::Move { seat, row, col } => {
+                    let pos = row * 3 + col;
+                    if pos < 9 {
+                        state.board[pos] = Some(*seat);
+                        state.current_turn = 1 - seat;
                     }
                 }
-                "WINNER" if parts.len() >= 2 => {
-                    if let Ok(winner) = parts[1].parse::<usize>() {
-                        state.winner = Some(winner);
-                        state.game_finished = true;
-                    }
+                GameEvent::Winner { seat } => {
+                    state.winner = Some(*seat);
+                    state.game_finished = true;
                 }
-                "DRAW" => {
+                GameEvent::Draw => {
                     state.game_finished = true;
                     state.winner = None;
                 }
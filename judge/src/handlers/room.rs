@@ -1,5 +1,4 @@
 use crate::middleware::auth::Claims;
-use crate::models::game::Game;
 use crate::models::room::{CreateRoomRequest, RoomResponse, ListRoomsQuery, RoomListItem};
 use axum::http::StatusCode;
 use axum::response::Json;
@@ -8,15 +7,16 @@ use axum::Extension;
 use std::sync::Arc;
 
 /// Create a new room
-pub async fn create_room<G>(
-    State(state): State<Arc<crate::app_state::AppState<G>>>,
+pub async fn create_room(
+    State(state): State<Arc<crate::app_state::AppState>>,
     Extension(claims): Extension<Claims>,
     Json(request): Json<CreateRoomRequest>,
-) -> Result<Json<RoomResponse>, StatusCode>
-where
-    G: Game + Clone + Send + Sync + 'static,
-{
+) -> Result<Json<RoomResponse>, StatusCode> {
     let user_id = claims.sub;
+    let game = state.games.get(&request.game_id).ok_or_else(|| {
+        tracing::error!("HTTP_CREATE_ROOM_FAILED unknown game_id={}", request.game_id);
+        StatusCode::BAD_REQUEST
+    })?;
     
     tracing::info!(
         "HTTP_CREATE_ROOM_REQUEST name='{}' game_id={} host_id={}",
@@ -31,7 +31,7 @@ where
             request.name,
             request.game_id,
             user_id, // Use authenticated user_id as host_id
-            state.game.max_players(),
+            game.max_players(),
             request.human_timeout_ms,
         )
         .await
@@ -48,26 +48,20 @@ where
 }
 
 /// Get room details
-pub async fn get_room<G>(
-    State(state): State<Arc<crate::app_state::AppState<G>>>,
+pub async fn get_room(
+    State(state): State<Arc<crate::app_state::AppState>>,
     Path(room_id): Path<String>,
-) -> Result<Json<RoomResponse>, StatusCode>
-where
-    G: Game + Clone + Send + Sync + 'static,
-{
+) -> Result<Json<RoomResponse>, StatusCode> {
     state.room_manager.get_room(&room_id).await
         .map(Json)
         .ok_or(StatusCode::NOT_FOUND)
 }
 
 /// List all waiting rooms
-pub async fn list_rooms<G>(
-    State(state): State<Arc<crate::app_state::AppState<G>>>,
+pub async fn list_rooms(
+    State(state): State<Arc<crate::app_state::AppState>>,
     Query(query): Query<ListRoomsQuery>,
-) -> Json<Vec<RoomListItem>>
-where
-    G: Game + Clone + Send + Sync + 'static,
-{
+) -> Json<Vec<RoomListItem>> {
     let rooms = state
         .room_manager
         .list_rooms(query.game_id.as_deref())
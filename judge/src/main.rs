@@ -3,8 +3,9 @@ use judge::{
     services::capacity::CapacityTracker,
     config::Config,
     db,
-    games::{self, Game},
+    games,
     services::room::RoomManager,
+    services::spectator::SpectatorHub,
     router,
     services,
 };
@@ -33,41 +34,41 @@ async fn main() -> anyhow::Result<()> {
     // Initialize capacity tracker
     let capacity = CapacityTracker::new(config.max_capacity, config.max_claim_delay_ms);
 
+    // Every game this judge serves
+    let games = games::registry();
+
+    // Compile new submissions as they arrive
+    let submission_db = db.clone();
+    let submission_capacity = capacity.clone();
+    tokio::spawn(async move {
+        if let Err(e) = services::submission_watcher::start_submission_watcher(submission_db, submission_capacity).await {
+            tracing::error!("Submission watcher error: {}", e);
+        }
+    });
+
+    // Live events of running matches for spectators
+    let spectators = Arc::new(SpectatorHub::new(config.max_spectators));
+
     // Start match watchers for automated games (AI vs AI)
-    services::start_match_watchers(db.clone(), capacity.clone());
+    services::start_match_watchers(db.clone(), capacity.clone(), &games, spectators.clone());
 
     // Create a shared RoomManager for all game types
-    let shared_room_manager = Arc::new(RoomManager::new(db.clone()));
+    let shared_room_manager = Arc::new(RoomManager::new(db.clone(), spectators.clone()));
 
     // Recover orphaned rooms from previous server crashes
     services::recover_orphaned_rooms(&shared_room_manager).await;
 
-    // Create app state for each game type (sharing the same RoomManager)
-    let tic_tac_toe_state = Arc::new(AppState {
-        db: db.clone(),
-        game: games::TicTacToe::new(),
-        capacity: capacity.clone(),
-        room_manager: shared_room_manager.clone(),
-        jwt_secret: config.jwt_secret.clone(),
-    });
-
-    let rps_state = Arc::new(AppState {
-        db: db.clone(),
-        game: games::RockPaperScissors::new(),
-        capacity: capacity.clone(),
-        room_manager: shared_room_manager.clone(),
-        jwt_secret: config.jwt_secret.clone(),
-    });
-
-    let pd_state = Arc::new(AppState {
+    // Create app state shared by every game's routes
+    let state = Arc::new(AppState {
         db: db.clone(),
-        game: games::PrisonersDilemma::new(),
+        games,
         capacity: capacity.clone(),
         room_manager: shared_room_manager.clone(),
+        spectators,
         jwt_secret: config.jwt_secret.clone(),
     });
     // Create router
-    let app = router::create_router(&config, tic_tac_toe_state, rps_state, pd_state);
+    let app = router::create_router(&config, state);
     // Start server
     let addr = format!("{}:{}", config.server_host, config.server_port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
@@ -15,14 +15,11 @@ pub struct Claims {
 }
 
 /// Auth middleware that automatically intercepts protected routes
-pub async fn auth_middleware<G>(
-    State(state): State<Arc<crate::app_state::AppState<G>>>,
+pub async fn auth_middleware(
+    State(state): State<Arc<crate::app_state::AppState>>,
     mut req: Request,
     next: Next,
-) -> Result<Response, StatusCode>
-where
-    G: crate::models::game::Game + Clone + Send + Sync + 'static,
-{
+) -> Result<Response, StatusCode> {
     let auth_header = req
         .headers()
         .get("Authorization")
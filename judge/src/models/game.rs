@@ -1,30 +1,37 @@
+use async_trait::async_trait;
+use game_registry::GameMetadata;
+
+pub use game_registry::events::GameResult;
+
 /// Interface for communication between server and client
 /// Individual games implement their own logic
-pub trait Game: Send + Sync + Clone + 'static {
+#[async_trait]
+pub trait Game: Send + Sync + 'static {
     /// Create new game instance
-    fn new() -> Self;
+    fn new() -> Self
+    where
+        Self: Sized;
+
+    /// Shared registry entry describing this game
+    fn metadata(&self) -> &'static GameMetadata;
 
     /// Run the game with players and return results
     /// The game_context parameter allows games to write state changes to persistent history
-    fn run(&self, players: Vec<Box<dyn crate::models::players::Player>>, timeout_ms: u64, game_context: crate::services::room::GameContext) -> impl std::future::Future<Output = Vec<GameResult>> + Send;
+    async fn run(&self, players: Vec<Box<dyn crate::models::players::Player>>, timeout_ms: u64, game_context: crate::services::room::GameContext) -> Vec<GameResult>;
+
+    /// Get the minimum number of players for this game
+    fn min_players(&self) -> usize {
+        2
+    }
 
     /// Get the maximum number of players for this game
     fn max_players(&self) -> usize;
 
-    /// Restore game state from event history
+    /// Restore game state from event history, in sequence order
     /// Called when server restarts and needs to reconstruct ongoing games
-    fn restore_from_events(&self, events: &[String]);
+    fn restore_from_events(&self, events: &[crate::models::game_event::GameEvent]);
 
     /// Get reconnection state for a specific player
     /// Called when a player reconnects to restore their client state
     fn get_event_source(&self, player_id: &str) -> Vec<String>;
 }
-
-/// Result of a player's performance in the game
-#[derive(Debug, Clone, serde::Serialize)]
-pub enum GameResult {
-    Accepted(i32),        // Score
-    TimeLimitExceeded,
-    WrongAnswer,
-    RuntimeError,
-}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::{Datetime, Thing};
+
+pub use game_registry::events::{GAME_EVENT_VERSION, GameEvent};
+
+/// One stored event of a match, ordered by `seq`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameEventRecord {
+    pub match_id: Thing,
+    pub seq: u64,
+    pub version: u32,
+    pub event: GameEvent,
+    pub created_at: Datetime,
+}
@@ -1,4 +1,4 @@
 pub mod room;
 pub mod game;
-pub mod game_metadata;
+pub mod game_event;
 pub mod players;
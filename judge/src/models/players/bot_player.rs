@@ -1,18 +1,133 @@
 use anyhow::{Result, Context};
 use async_trait::async_trait;
 use nix::sys::signal::{kill, Signal};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
 use nix::unistd::Pid;
-use tokio::time::{timeout, Duration};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::time::{sleep, timeout, Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::fs::File as TokioFile;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use tokio::sync::Mutex;
 use surrealdb::sql::Thing;
 
+use crate::models::game::GameResult;
 use crate::models::players::Player;
 use crate::services::sandbox::executor::{spawn_sandboxed, fd_to_file};
 use crate::services::sandbox::cgroup::CgroupHandle;
+use crate::services::sandbox::{ResourceLimits, ResourceUsage};
+
+/// Longest line a bot may send as one message
+const MAX_MESSAGE_BYTES: u64 = 64 * 1024;
+/// Stderr kept per bot and match, the rest is read and discarded
+const MAX_STDERR_BYTES: usize = 64 * 1024;
+/// How long to wait for the last stderr output once the bot is gone
+const STDERR_DRAIN_MS: u64 = 1000;
+/// How long a bot that closed its stdout gets to finish exiting
+const EXIT_GRACE_MS: u64 = 200;
+
+/// What the judge knows about a bot's sandbox. It outlives the player, so the
+/// cause of a failure can still be read after the game has dropped the bot.
+pub struct BotMonitor {
+    pid: Pid,
+    cgroup: CgroupHandle, // Kept alive to enforce limits and cleanup on drop
+    exit_status: std::sync::Mutex<Option<WaitStatus>>,
+    output_exceeded: AtomicBool,
+    stderr: std::sync::Mutex<Vec<u8>>,
+    stderr_truncated: AtomicBool,
+    stderr_task: std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+/// A bot's stderr over one match, cut off at MAX_STDERR_BYTES
+#[derive(Debug, Clone, Default)]
+pub struct StderrLog {
+    pub text: String,
+    pub truncated: bool,
+}
+
+impl BotMonitor {
+    pub fn usage(&self) -> ResourceUsage {
+        self.cgroup.usage()
+    }
+
+    /// Everything the bot wrote to stderr. Call once the player is dropped;
+    /// waits briefly for output still in the pipe.
+    pub async fn stderr_log(&self) -> StderrLog {
+        let task = self.stderr_task.lock().unwrap().take();
+        if let Some(task) = task {
+            let _ = timeout(Duration::from_millis(STDERR_DRAIN_MS), task).await;
+        }
+        let truncated = self.stderr_truncated.load(Ordering::Relaxed);
+        let mut text = String::from_utf8_lossy(&self.stderr.lock().unwrap()).into_owned();
+        if truncated {
+            text.push_str("\n[stderr truncated]");
+        }
+        StderrLog { text, truncated }
+    }
+
+    fn append_stderr(&self, chunk: &[u8]) {
+        let mut stderr = self.stderr.lock().unwrap();
+        let room = MAX_STDERR_BYTES.saturating_sub(stderr.len());
+        if chunk.len() > room {
+            self.stderr_truncated.store(true, Ordering::Relaxed);
+        }
+        stderr.extend_from_slice(&chunk[..chunk.len().min(room)]);
+    }
+
+    /// Verdict from the sandbox's point of view, None when the bot neither
+    /// crashed nor hit a limit
+    pub fn verdict(&self) -> Option<GameResult> {
+        if self.output_exceeded.load(Ordering::Relaxed) {
+            return Some(GameResult::OutputLimitExceeded);
+        }
+        if self.cgroup.usage().oom_kills.is_some_and(|kills| kills > 0) {
+            return Some(GameResult::MemoryLimitExceeded);
+        }
+        match *self.exit_status.lock().unwrap() {
+            Some(WaitStatus::Signaled(_, Signal::SIGSYS, _)) => Some(GameResult::SeccompViolation),
+            Some(WaitStatus::Signaled(_, signal, _)) => Some(GameResult::Killed(signal as i32)),
+            Some(WaitStatus::Exited(_, code)) if code != 0 => Some(GameResult::RuntimeError),
+            _ => None,
+        }
+    }
+
+    /// Result of the bot's seat once the game is over. A scored game stands;
+    /// otherwise the game only saw the bot stop answering and the sandbox knows why.
+    pub fn final_result(&self, result: Option<&GameResult>) -> GameResult {
+        match result {
+            Some(GameResult::Accepted(score)) => GameResult::Accepted(*score),
+            Some(result) => self.verdict().unwrap_or_else(|| result.clone()),
+            None => self.verdict().unwrap_or(GameResult::RuntimeError),
+        }
+    }
+
+    /// Reap the process if it has exited and keep its status. Only the first
+    /// status is kept, so the judge's own kill never hides why a bot died.
+    fn poll_exit(&self) -> bool {
+        let mut status = self.exit_status.lock().unwrap();
+        if status.is_none() {
+            match waitpid(self.pid, Some(WaitPidFlag::WNOHANG)) {
+                Ok(WaitStatus::StillAlive) | Err(_) => {}
+                Ok(exited) => *status = Some(exited),
+            }
+        }
+        status.is_some()
+    }
+
+    /// Poll until the process has exited or `within` has passed. Its stdout
+    /// closes a moment before it can be reaped, so EOF alone is too early.
+    async fn wait_exit(&self, within: Duration) -> bool {
+        let deadline = Instant::now() + within;
+        while !self.poll_exit() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            sleep(Duration::from_millis(5)).await;
+        }
+        true
+    }
+}
 
 /// BotPlayer runs player code in an isolated sandbox using Linux primitives
 pub struct BotPlayer {
@@ -21,17 +136,21 @@ pub struct BotPlayer {
     stdin: Arc<Mutex<TokioFile>>,
     stdout: Arc<Mutex<BufReader<TokioFile>>>,
     timeout_ms: u64,
-    _cgroup: CgroupHandle, // Kept alive to enforce limits and cleanup on drop
+    wall_clock_ms: u64, // Budget for the time spent waiting on this bot's replies
+    used_ms: AtomicU64,
+    monitor: Arc<BotMonitor>,
 }
 
 impl BotPlayer {
-    pub async fn new(player_id: Thing, binary_path: &str) -> Result<Self> {
+    pub async fn new(player_id: Thing, binary_path: &str, limits: ResourceLimits) -> Result<Self> {
+        let wall_clock_ms = limits.wall_clock_ms;
+
         // Spawn the sandboxed process
         let process = tokio::task::spawn_blocking({
             let binary_path = binary_path.to_string();
             let player_id_str = player_id.to_string();
             move || {
-                spawn_sandboxed(&player_id_str, Path::new(&binary_path))
+                spawn_sandboxed(&player_id_str, Path::new(&binary_path), &limits)
             }
         })
         .await
@@ -41,6 +160,7 @@ impl BotPlayer {
         let stdin_file = TokioFile::from_std(fd_to_file(process.stdin_fd));
         let stdout_file = TokioFile::from_std(fd_to_file(process.stdout_fd));
         let stdout_reader = BufReader::new(stdout_file);
+        let stderr_file = TokioFile::from_std(fd_to_file(process.stderr_fd));
 
         tracing::info!(
             player_id = %player_id,
@@ -49,15 +169,50 @@ impl BotPlayer {
             "Created sandboxed player"
         );
 
+        let monitor = Arc::new(BotMonitor {
+            pid: process.pid,
+            cgroup: process.cgroup,
+            exit_status: std::sync::Mutex::new(None),
+            output_exceeded: AtomicBool::new(false),
+            stderr: std::sync::Mutex::new(Vec::new()),
+            stderr_truncated: AtomicBool::new(false),
+            stderr_task: std::sync::Mutex::new(None),
+        });
+        let stderr_task = tokio::spawn(capture_stderr(stderr_file, Arc::downgrade(&monitor)));
+        *monitor.stderr_task.lock().unwrap() = Some(stderr_task);
+
         Ok(Self {
             id: player_id,
             pid: Some(process.pid),
             stdin: Arc::new(Mutex::new(stdin_file)),
             stdout: Arc::new(Mutex::new(stdout_reader)),
             timeout_ms: 5000, // Default timeout
-            _cgroup: process.cgroup, // Keep cgroup alive for resource limits and cleanup
+            wall_clock_ms,
+            used_ms: AtomicU64::new(0),
+            monitor,
         })
     }
+
+    /// Monitor of this bot's sandbox. Holding it keeps the cgroup and its
+    /// counters around after the game has dropped the player.
+    pub fn monitor(&self) -> Arc<BotMonitor> {
+        self.monitor.clone()
+    }
+}
+
+/// Keep reading the bot's stderr so it never blocks on a full pipe. Holds the
+/// monitor weakly, so the cgroup is still torn down with the last real owner.
+async fn capture_stderr(mut stderr: TokioFile, monitor: Weak<BotMonitor>) {
+    let mut buf = [0u8; 4096];
+    loop {
+        match stderr.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => match monitor.upgrade() {
+                Some(monitor) => monitor.append_stderr(&buf[..n]),
+                None => break,
+            },
+        }
+    }
 }
 
 #[async_trait]
@@ -79,7 +234,17 @@ impl Player for BotPlayer {
     }
 
     async fn receive_message(&self) -> Result<String> {
-        let timeout_duration = Duration::from_millis(self.timeout_ms);
+        // A turn may not run past what is left of the match budget
+        let remaining_ms = self.wall_clock_ms.saturating_sub(self.used_ms.load(Ordering::Relaxed));
+        if remaining_ms == 0 {
+            anyhow::bail!(
+                "Player {} exceeded its wall-clock budget of {}ms",
+                self.id,
+                self.wall_clock_ms
+            );
+        }
+        let timeout_duration = Duration::from_millis(self.timeout_ms.min(remaining_ms));
+        let started = std::time::Instant::now();
 
         tracing::debug!(
             player_id = %self.id,
@@ -90,10 +255,24 @@ impl Player for BotPlayer {
         let message = timeout(timeout_duration, async {
             let mut stdout_guard = self.stdout.lock().await;
             let mut line = String::new();
-            stdout_guard
+            let read = (&mut *stdout_guard)
+                .take(MAX_MESSAGE_BYTES)
                 .read_line(&mut line)
                 .await
                 .context("Failed to read from player stdout")?;
+            if read == 0 {
+                // The bot exited or closed its stdout, keep its status for the verdict
+                self.monitor.wait_exit(Duration::from_millis(EXIT_GRACE_MS)).await;
+                anyhow::bail!("Player {} closed its output", self.id);
+            }
+            if read as u64 >= MAX_MESSAGE_BYTES && !line.ends_with('\n') {
+                self.monitor.output_exceeded.store(true, Ordering::Relaxed);
+                anyhow::bail!(
+                    "Player {} exceeded the output limit of {} bytes per message",
+                    self.id,
+                    MAX_MESSAGE_BYTES
+                );
+            }
 
             let trimmed = line.trim().to_string();
             tracing::debug!(
@@ -103,8 +282,10 @@ impl Player for BotPlayer {
             );
             Ok::<String, anyhow::Error>(trimmed)
         })
-        .await
-        .context(format!("Timeout waiting for message from player {}", self.id))??;
+        .await;
+        self.used_ms.fetch_add(started.elapsed().as_millis() as u64, Ordering::Relaxed);
+        let message = message
+            .context(format!("Timeout waiting for message from player {}", self.id))??;
 
         Ok(message)
     }
@@ -133,12 +314,22 @@ impl Drop for BotPlayer {
         if let Some(pid) = self.pid {
             tracing::debug!(player_id = %self.id, pid = %pid, "Cleaning up sandboxed player");
 
+            // A bot that already exited is reaped here, its pid may not be signalled again
+            if self.monitor.poll_exit() {
+                return;
+            }
+
             // Send SIGTERM
             let _ = kill(pid, Signal::SIGTERM);
 
             // Wait briefly then SIGKILL
             std::thread::sleep(std::time::Duration::from_secs(1));
-            let _ = kill(pid, Signal::SIGKILL);
+            // Reaped without being recorded, this exit is the judge's doing. A bot
+            // that exited on its own was recorded when its stdout closed.
+            if let Ok(WaitStatus::StillAlive) = waitpid(pid, Some(WaitPidFlag::WNOHANG)) {
+                let _ = kill(pid, Signal::SIGKILL);
+                let _ = waitpid(pid, None);
+            }
 
             // Cgroup cleanup happens automatically via Drop
         }
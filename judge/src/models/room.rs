@@ -1,7 +1,10 @@
 use crate::db::Database;
+use crate::models::game_event::{GAME_EVENT_VERSION, GameEvent, GameEventRecord};
 use crate::models::players::{HumanPlayer, Player};
+use crate::services::spectator::SpectatorHub;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use surrealdb::sql::{Datetime, Thing};
 
 // ============================================================================
@@ -64,7 +67,6 @@ pub struct MatchRecord {
     pub participants: Vec<MatchParticipant>,
     pub metadata: Option<serde_json::Value>,
     pub room_id: Option<Thing>,
-    pub game_event_source: Option<String>,
     pub judge_server_name: Option<String>,
     pub created_at: Datetime,
     pub updated_at: Datetime,
@@ -79,30 +81,110 @@ pub struct MatchParticipant {
     pub score: Option<f64>,
 }
 
+/// Where a match's events end up
+#[derive(Clone)]
+enum EventSink {
+    Database(Database),
+    /// Kept in memory, for running games without SurrealDB
+    Memory(Arc<std::sync::Mutex<Vec<GameEventRecord>>>),
+}
+
 /// Context for games to write events to match history
 #[derive(Clone)]
 pub struct GameContext {
     match_id: Thing,
-    db: Database,
+    sink: EventSink,
+    next_seq: Arc<AtomicU64>, // Shared by clones so one match has one sequence
+    write_error: Arc<std::sync::Mutex<Option<String>>>, // First event that could not be stored
+    spectators: Option<Arc<SpectatorHub>>,
 }
 
 impl GameContext {
     pub fn new(match_id: Thing, db: Database) -> Self {
-        Self { match_id, db }
+        Self {
+            match_id,
+            sink: EventSink::Database(db),
+            next_seq: Arc::new(AtomicU64::new(0)),
+            write_error: Arc::new(std::sync::Mutex::new(None)),
+            spectators: None,
+        }
+    }
+
+    /// Context that keeps the events in memory instead of the database
+    pub fn in_memory(match_id: Thing) -> Self {
+        Self {
+            match_id,
+            sink: EventSink::Memory(Arc::new(std::sync::Mutex::new(Vec::new()))),
+            next_seq: Arc::new(AtomicU64::new(0)),
+            write_error: Arc::new(std::sync::Mutex::new(None)),
+            spectators: None,
+        }
+    }
+
+    /// Events written so far, for in-memory contexts. Database contexts return
+    /// nothing; their events are read back from the game_event table.
+    pub fn events(&self) -> Vec<GameEventRecord> {
+        match &self.sink {
+            EventSink::Database(_) => Vec::new(),
+            EventSink::Memory(events) => events.lock().unwrap().clone(),
+        }
     }
 
-    /// Write a game event to the match history
-    pub async fn write_event(&self, event: &str) {
-        let query = "UPDATE $match_id SET game_event_source = string::concat(game_event_source, $event, '\n'), updated_at = time::now()";
-
-        if let Err(e) = self
-            .db
-            .query(query)
-            .bind(("match_id", self.match_id.clone()))
-            .bind(("event", event.to_string()))
-            .await
-        {
-            tracing::error!("Failed to write game event: {}", e);
+    /// Stream this match's events to spectators while it runs
+    pub fn with_spectators(mut self, spectators: Arc<SpectatorHub>) -> Self {
+        spectators.open(&self.match_id);
+        self.spectators = Some(spectators);
+        self
+    }
+
+    /// Close the spectator feed once the match has written its last event.
+    /// Fails if any event could not be stored, since the history of the match
+    /// would then be incomplete.
+    pub fn finish(&self) -> anyhow::Result<()> {
+        if let Some(spectators) = &self.spectators {
+            spectators.close(&self.match_id);
+        }
+        match self.write_error.lock().unwrap().take() {
+            Some(error) => anyhow::bail!("Failed to write game event of match {}: {}", self.match_id, error),
+            None => Ok(()),
+        }
+    }
+
+    /// Append an event to the match history. Each event is its own record, so
+    /// writing one costs the same however long the match has run. Games keep
+    /// running when a write fails; the failure is reported by `finish`.
+    pub async fn write_event(&self, event: GameEvent) {
+        let record = GameEventRecord {
+            match_id: self.match_id.clone(),
+            seq: self.next_seq.fetch_add(1, Ordering::SeqCst),
+            version: GAME_EVENT_VERSION,
+            event,
+            created_at: Datetime::default(),
+        };
+
+        match &self.sink {
+            EventSink::Database(db) => {
+                // Statement errors such as a duplicate seq come back in the response
+                let written = match db
+                    .query("CREATE game_event CONTENT $record; UPDATE $match_id SET updated_at = time::now();")
+                    .bind(("record", record.clone()))
+                    .bind(("match_id", self.match_id.clone()))
+                    .await
+                {
+                    Ok(response) => response.check(),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = written {
+                    tracing::error!("Failed to write game event: {}", e);
+                    self.write_error.lock().unwrap().get_or_insert(e.to_string());
+                }
+            }
+            EventSink::Memory(events) => events.lock().unwrap().push(record.clone()),
+        }
+
+        // Spectators catch up from the stored events, so publish after writing
+        if let Some(spectators) = &self.spectators {
+            spectators.publish(&record);
         }
     }
 }
@@ -1,63 +1,64 @@
 use crate::app_state::AppState;
 use crate::config::Config;
-use crate::games;
 use crate::handlers;
 use crate::handlers::room;
 use crate::services::room::websocket;
+use crate::services::spectator;
 use crate::middleware;
 use axum::http::{header, Method};
+use axum::extract::{ws::WebSocketUpgrade, Path, State};
 use axum::routing::get;
 use axum::{middleware as axum_middleware, Router};
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 
-pub fn create_router(
-    config: &Config,
-    tic_tac_toe_state: Arc<AppState<games::TicTacToe>>,
-    rps_state: Arc<AppState<games::RockPaperScissors>>,
-    pd_state: Arc<AppState<games::PrisonersDilemma>>,
-) -> Router {
+pub fn create_router(config: &Config, state: Arc<AppState>) -> Router {
     tracing::info!("CORS: Allowing origin: {}", config.frontend_url);
 
     // Public routes (no authentication required)
     let public_routes = Router::new()
         .route("/health", get(handlers::health))
-        .route("/capacity", get(handlers::get_capacity::<games::TicTacToe>))
-        .with_state(tic_tac_toe_state.clone());
+        .route("/capacity", get(handlers::get_capacity))
+        .with_state(state.clone());
 
     // Protected routes (require authentication)
     let protected_routes = Router::new()
         .route(
             "/api/rooms",
-            get(room::list_rooms::<games::TicTacToe>).post(room::create_room::<games::TicTacToe>),
+            get(room::list_rooms).post(room::create_room),
         )
-        .route(
-            "/api/rooms/{room_id}",
-            get(room::get_room::<games::TicTacToe>),
-        )
-        .with_state(tic_tac_toe_state.clone())
+        .route("/api/rooms/{room_id}", get(room::get_room))
+        .with_state(state.clone())
         .route_layer(axum_middleware::from_fn_with_state(
-            tic_tac_toe_state.clone(),
+            state.clone(),
             middleware::auth::auth_middleware,
         ));
 
-    // WebSocket endpoints (auth via LOGIN message, not HTTP headers)
-    let websocket_routes = Router::new()
-        .route(
-            "/ws/tic-tac-toe/{room_id}",
-            get(websocket::ws_get_room::<games::TicTacToe>),
-        )
-        .with_state(tic_tac_toe_state.clone())
-        .route(
-            "/ws/rock-paper-scissors/{room_id}",
-            get(websocket::ws_get_room::<games::RockPaperScissors>),
-        )
-        .with_state(rps_state.clone())
-        .route(
-            "/ws/prisoners-dilemma/{room_id}",
-            get(websocket::ws_get_room::<games::PrisonersDilemma>),
-        )
-        .with_state(pd_state.clone());
+    // WebSocket endpoints, one per registered game (auth via LOGIN message, not HTTP headers)
+    let mut websocket_routes = Router::new();
+    for game in state.games.iter() {
+        let game = game.clone();
+        websocket_routes = websocket_routes.route(
+            &format!("/ws/{}/{{room_id}}", game.metadata().id),
+            get(
+                move |ws: WebSocketUpgrade,
+                      Path(room_id): Path<String>,
+                      State(state): State<Arc<AppState>>| {
+                    websocket::ws_get_room(ws, room_id, state, game.clone())
+                },
+            ),
+        );
+    }
+    // Read-only spectators of a match or a room's latest match
+    websocket_routes = websocket_routes.route(
+        "/ws/spectate/{id}",
+        get(
+            |ws: WebSocketUpgrade, Path(id): Path<String>, State(state): State<Arc<AppState>>| {
+                spectator::ws_spectate(ws, id, state)
+            },
+        ),
+    );
+    let websocket_routes = websocket_routes.with_state(state.clone());
 
     // Combine all routes
     Router::new()
@@ -29,6 +29,6 @@ impl Compiler {
         self.sandbox
             .compile(submission_id, language, code)
             .await
-            .map_err(|e| anyhow::anyhow!("Compilation error: {}", e))
+            .map_err(anyhow::Error::from)
     }
 }
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use surrealdb::sql::{Datetime, Thing};
 use surrealdb::Surreal;
 use surrealdb::engine::remote::ws::Client;
+use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 
 use crate::services::capacity::CapacityTracker;
@@ -10,6 +11,10 @@ use crate::services::compiler::Compiler;
 use crate::models::game::{Game, GameResult};
 use crate::models::players::Player;
 use crate::models::players::BotPlayer;
+use crate::models::players::bot_player::StderrLog;
+use crate::services::sandbox::{ResourceLimits, ResourceUsage};
+use crate::services::spectator::SpectatorHub;
+use game_registry::GameMetadata;
 
 type Database = Surreal<Client>;
 
@@ -49,20 +54,97 @@ struct Submission {
     id: Option<Thing>,
     #[serde(default)]
     compiled_binary_path: Option<String>,
+    status: String,
     language: String,
     code: String,
 }
 
+/// What one seat's bot used, stored in the match metadata in seat order
+#[derive(Debug, Clone, Serialize)]
+struct ParticipantUsage {
+    submission_id: Thing,
+    #[serde(flatten)]
+    usage: ResourceUsage,
+}
+
+/// A bot's stderr from one match, readable by its author and admins only
+#[derive(Debug, Clone, Serialize)]
+struct MatchLog {
+    match_id: Thing,
+    submission_id: Thing,
+    user_id: Thing,
+    stderr: String,
+    truncated: bool,
+    created_at: Datetime,
+}
+
+/// Replace the stderr logs of a match with those of the run that just finished
+async fn store_match_logs(db: &Database, match_record: &Match, logs: Vec<StderrLog>) -> Result<()> {
+    db.query("DELETE match_log WHERE match_id = $match_id")
+        .bind(("match_id", match_record.id.clone()))
+        .await
+        .context(format!("Failed to clear logs of match {}", match_record.id))?;
+
+    for (participant, log) in match_record.participants.iter().zip(logs) {
+        let Some(user_id) = participant.user_id.clone() else {
+            continue;
+        };
+        let record = MatchLog {
+            match_id: match_record.id.clone(),
+            submission_id: participant.submission_id.clone(),
+            user_id,
+            stderr: log.text,
+            truncated: log.truncated,
+            created_at: Datetime::default(),
+        };
+        db.query("CREATE match_log CONTENT $record")
+            .bind(("record", record))
+            .await
+            .context(format!("Failed to store log of match {}", match_record.id))?;
+    }
+    Ok(())
+}
+
+/// Tournament overrides of the game's bot limits, each falling back to the game
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ResourceLimitOverrides {
+    #[serde(default)]
+    cpu_limit: Option<f64>,
+    #[serde(default)]
+    memory_limit_mb: Option<u64>,
+    #[serde(default)]
+    max_pids: Option<u32>,
+    #[serde(default)]
+    wall_clock_ms: Option<u64>,
+}
+
+/// Sandbox limits for the bots of a match: the game's, with any overrides of its tournament
+async fn match_limits(db: &Database, tournament_id: &Thing, game_metadata: &GameMetadata) -> Result<ResourceLimits> {
+    let mut result = db
+        .query("SELECT VALUE resource_limits FROM $tournament_id")
+        .bind(("tournament_id", tournament_id.clone()))
+        .await
+        .context(format!("Failed to query resource limits of {}", tournament_id))?;
+    let overrides: Vec<Option<ResourceLimitOverrides>> = result.take(0)
+        .context(format!("Failed to deserialize resource limits of {}", tournament_id))?;
+    let overrides = overrides.into_iter().flatten().next().unwrap_or_default();
+
+    Ok(ResourceLimits::new(
+        overrides.cpu_limit.unwrap_or(game_metadata.cpu_limit),
+        overrides.memory_limit_mb.unwrap_or(game_metadata.memory_limit_mb),
+        overrides.max_pids.unwrap_or(game_metadata.max_pids),
+        overrides.wall_clock_ms.unwrap_or(game_metadata.wall_clock_ms),
+    ))
+}
+
 /// Watches for pending automated matches and executes them
-pub async fn start_match_watcher<G>(
+pub async fn start_match_watcher(
     db: Database,
-    game: G,
-    game_id: String,
+    game: Arc<dyn Game>,
     capacity: CapacityTracker,
-) -> Result<()>
-where
-    G: Game + Clone + Send + Sync + 'static,
-{
+    spectators: Arc<SpectatorHub>,
+) -> Result<()> {
+    let game_id = game.metadata().id;
     tracing::info!("Starting match watcher for game: {}", game_id);
 
     loop {
@@ -110,10 +192,12 @@ where
             let db_clone = db.clone();
             let capacity_clone = capacity.clone();
             let game_clone = game.clone();
+            let spectators_clone = spectators.clone();
             let match_id_clone = match_id_str.clone();
+            let match_thing = match_record.id.clone();
 
             tokio::spawn(async move {
-                let result = execute_match(db_clone.clone(), game_clone, match_record).await;
+                let result = execute_match(db_clone.clone(), game_clone, match_record, spectators_clone).await;
 
                 match result {
                     Ok(_) => {
@@ -122,11 +206,18 @@ where
                     Err(e) => {
                         tracing::error!("Match {} failed: {}", match_id_clone, e);
                         // Update match status to failed
-                        let _ = db_clone
-                            .query("UPDATE $match_id SET status = 'failed', error_message = $error, updated_at = time::now(), completed_at = time::now()")
-                            .bind(("match_id", match_id_clone.clone()))
+                        let failed = match db_clone
+                            .query("UPDATE $match_id SET status = 'failed', error_message = $error, updated_at = time::now(), completed_at = time::now() WHERE status IN ['queued', 'running']")
+                            .bind(("match_id", match_thing))
                             .bind(("error", e.to_string()))
-                            .await;
+                            .await
+                        {
+                            Ok(response) => response.check().map(|_| ()),
+                            Err(e) => Err(e),
+                        };
+                        if let Err(e) = failed {
+                            tracing::error!("Failed to mark match {} as failed: {}", match_id_clone, e);
+                        }
                     }
                 }
 
@@ -139,20 +230,35 @@ where
     }
 }
 
-async fn execute_match<G>(db: Database, game: G, match_record: Match) -> Result<()>
-where
-    G: Game,
-{
+async fn execute_match(
+    db: Database,
+    game: Arc<dyn Game>,
+    match_record: Match,
+    spectators: Arc<SpectatorHub>,
+) -> Result<()> {
     let match_id_str = match_record.id.to_string();
 
-    // Update status to running
-    db.query("UPDATE $match_id SET status = 'running', started_at = time::now(), updated_at = time::now()")
+    // Update status to running, unless the match was cancelled while queued
+    let mut started = db
+        .query("UPDATE $match_id SET status = 'running', started_at = time::now(), updated_at = time::now() WHERE status = 'queued' RETURN AFTER")
         .bind(("match_id", match_record.id.clone()))
         .await?;
+    let started: Vec<Match> = started.take(0)?;
+    if started.is_empty() {
+        tracing::info!("Match {} is no longer queued, skipping it", match_id_str);
+        return Ok(());
+    }
+
+    // A match requeued after a crash starts its history over
+    db.query("DELETE game_event WHERE match_id = $match_id; DELETE match_log WHERE match_id = $match_id;")
+        .bind(("match_id", match_record.id.clone()))
+        .await
+        .context(format!("Failed to clear history of match {}", match_id_str))?
+        .check()
+        .context(format!("Failed to clear history of match {}", match_id_str))?;
 
     // Get game metadata for timeouts
-    let game_metadata = crate::games::find_game_by_id(&match_record.game_id)
-        .ok_or_else(|| anyhow::anyhow!("Game metadata not found for {}", match_record.game_id))?;
+    let game_metadata = game.metadata();
 
     // Initialize compiler
     let compiler = Compiler::new()?;
@@ -164,7 +270,7 @@ where
         let submission_id_str = participant.submission_id.to_string();
 
         let mut result = db
-            .query("SELECT compiled_binary_path, language, code FROM $submission_id")
+            .query("SELECT compiled_binary_path, status, language, code FROM $submission_id")
             .bind(("submission_id", participant.submission_id.clone()))
             .await
             .context(format!("Failed to query submission {}", submission_id_str))?;
@@ -173,11 +279,15 @@ where
             .context(format!("Failed to deserialize submission {}", submission_id_str))?;
 
         if let Some(submission) = submissions.first() {
+            // Matches are only generated for accepted submissions, compiled by the submission watcher
+            if submission.status != "accepted" {
+                anyhow::bail!("Submission {} is {}, not accepted", submission_id_str, submission.status);
+            }
             let binary_path = match &submission.compiled_binary_path {
-                Some(path) => path.clone(),
-                None => {
-                    // Submission not compiled yet, compile it now
-                    tracing::info!("Compiling submission {} on-demand", submission_id_str);
+                Some(path) if std::path::Path::new(path).exists() => path.clone(),
+                _ => {
+                    // Accepted elsewhere but the artifact is missing here, rebuild it
+                    tracing::info!("Rebuilding artifact of submission {}", submission_id_str);
 
                     let compiled_path = compiler
                         .compile_submission(&submission_id_str, &submission.language, &submission.code)
@@ -185,7 +295,7 @@ where
                         .context(format!("Failed to compile submission {}", submission_id_str))?;
 
                     // Update submission with compiled binary path
-                    db.query("UPDATE $submission_id SET compiled_binary_path = $binary_path, status = 'accepted'")
+                    db.query("UPDATE $submission_id SET compiled_binary_path = $binary_path")
                         .bind(("submission_id", participant.submission_id.clone()))
                         .bind(("binary_path", compiled_path.clone()))
                         .await
@@ -200,51 +310,110 @@ where
         }
     }
 
-    if binary_paths.len() != 2 {
-        anyhow::bail!("Expected 2 binaries, got {}", binary_paths.len());
+    if binary_paths.len() < game.min_players() || binary_paths.len() > game.max_players() {
+        anyhow::bail!(
+            "Expected {} to {} binaries, got {}",
+            game.min_players(),
+            game.max_players(),
+            binary_paths.len()
+        );
     }
 
     tracing::info!("Executing match {} with binaries: {:?}", match_id_str, binary_paths);
 
-    let mut player1 = BotPlayer::new(Thing::from(("user", "p1")), &binary_paths[0]).await?;
-    let mut player2 = BotPlayer::new(Thing::from(("user", "p2")), &binary_paths[1]).await?;
-
-    // Set bot timeouts
-    player1.set_timeout(game_metadata.bot_turn_timeout_ms);
-    player2.set_timeout(game_metadata.bot_turn_timeout_ms);
-
-    let players: Vec<Box<dyn crate::models::players::Player>> = vec![Box::new(player1), Box::new(player2)];
+    let limits = match_limits(&db, &match_record.tournament_id, game_metadata).await?;
+
+    let mut players: Vec<Box<dyn crate::models::players::Player>> = Vec::with_capacity(binary_paths.len());
+    let mut monitors = Vec::with_capacity(binary_paths.len());
+    for (seat, binary_path) in binary_paths.iter().enumerate() {
+        // Player ids name the sandbox cgroups, so they must not collide with
+        // the seats of other matches running on this judge
+        let seat_id = format!("{}_p{}", match_id_str.replace(':', "_"), seat + 1);
+        let mut player = BotPlayer::new(Thing::from(("seat", seat_id.as_str())), binary_path, limits.clone()).await?;
+        // Set bot timeout
+        player.set_timeout(game_metadata.bot_turn_timeout_ms);
+        // Keep the sandbox past the game so its counters and exit status can be read
+        monitors.push(player.monitor());
+        players.push(Box::new(player));
+    }
 
     // Create GameContext for automated matches
-    let game_context = crate::services::room::GameContext::new(match_record.id.clone(), db.clone());
+    let game_context = crate::services::room::GameContext::new(match_record.id.clone(), db.clone())
+        .with_spectators(spectators);
 
     // Execute the game
-    let results = game.run(players, game_metadata.bot_turn_timeout_ms, game_context).await;
-
-    // Convert GameResult enum to scores
-    let score0 = match results[0] {
-        GameResult::Accepted(score) => score as f64,
-        _ => 0.0,
-    };
-    let score1 = match results[1] {
-        GameResult::Accepted(score) => score as f64,
-        _ => 0.0,
-    };
-
-    db.query(
+    let results = game.run(players, game_metadata.bot_turn_timeout_ms, game_context.clone()).await;
+    game_context.finish()?;
+
+    let resource_usage: Vec<ParticipantUsage> = match_record
+        .participants
+        .iter()
+        .zip(&monitors)
+        .map(|(participant, monitor)| ParticipantUsage {
+            submission_id: participant.submission_id.clone(),
+            usage: monitor.usage(),
+        })
+        .collect();
+
+    // The game only sees a bot stop answering, the sandbox knows why
+    let results: Vec<GameResult> = monitors
+        .iter()
+        .enumerate()
+        .map(|(seat, monitor)| monitor.final_result(results.get(seat)))
+        .collect();
+
+    let mut logs = Vec::with_capacity(monitors.len());
+    for monitor in &monitors {
+        logs.push(monitor.stderr_log().await);
+    }
+    drop(monitors);
+    store_match_logs(&db, &match_record, logs).await?;
+
+    // Convert GameResult enum to scores, one per seat
+    let scores: Vec<f64> = results
+        .iter()
+        .map(|result| match result {
+            GameResult::Accepted(score) => *score as f64,
+            _ => 0.0,
+        })
+        .collect();
+
+    let score_fields: Vec<String> = (0..scores.len())
+        .map(|seat| {
+            format!(
+                "participants[{seat}].score = $score{seat}, participants[{seat}].verdict = $verdict{seat}, participants[{seat}].signal = $signal{seat}"
+            )
+        })
+        .collect();
+    let update_query = format!(
         "UPDATE $match_id SET
             status = 'completed',
-            participants[0].score = $score0,
-            participants[1].score = $score1,
+            {},
+            metadata.resource_usage = $resource_usage,
             completed_at = time::now(),
-            updated_at = time::now()"
-    )
-    .bind(("match_id", match_record.id))
-    .bind(("score0", score0))
-    .bind(("score1", score1))
-    .await?;
-
-    tracing::info!("Match {} completed with scores: {} {}", match_id_str, score0, score1);
+            updated_at = time::now()
+        WHERE status = 'running'
+        RETURN AFTER",
+        score_fields.join(",\n            ")
+    );
+    let mut update = db
+        .query(update_query)
+        .bind(("match_id", match_record.id))
+        .bind(("resource_usage", resource_usage));
+    for (seat, (score, result)) in scores.iter().zip(&results).enumerate() {
+        update = update
+            .bind((format!("score{}", seat), *score))
+            .bind((format!("verdict{}", seat), result.verdict()))
+            .bind((format!("signal{}", seat), result.signal()));
+    }
+    // A match cancelled while it was being played keeps its cancelled status
+    let completed: Vec<Match> = update.await?.take(0)?;
+    if completed.is_empty() {
+        tracing::info!("Match {} was cancelled while running, dropping its results", match_id_str);
+        return Ok(());
+    }
+
+    tracing::info!("Match {} completed with results: {:?}", match_id_str, results);
 
     Ok(())
 }
@@ -1,64 +1,40 @@
 pub mod match_watcher;
+pub mod submission_watcher;
 pub mod compiler;
 pub mod capacity;
 pub mod sandbox;
 pub mod room;
+pub mod spectator;
+pub mod self_test;
 
 use crate::services::capacity::CapacityTracker;
 use crate::db::Database;
-use crate::games::{self, Game};
+use crate::games::GameRegistry;
 use crate::services::room::RoomManager;
+use crate::services::spectator::SpectatorHub;
 use std::sync::Arc;
 
-/// Start all match watchers for automated games (AI vs AI)
-pub fn start_match_watchers(db: Database, capacity: CapacityTracker) {
-    // Tic-Tac-Toe watcher
-    let db_clone = db.clone();
-    let capacity_clone = capacity.clone();
-    tokio::spawn(async move {
-        if let Err(e) = match_watcher::start_match_watcher(
-            db_clone.clone(),
-            games::TicTacToe::new(),
-            "tic-tac-toe".to_string(),
-            capacity_clone.clone(),
-        )
-        .await
-        {
-            tracing::error!("Tic-Tac-Toe match watcher error: {}", e);
-        }
-    });
-
-    // Rock-Paper-Scissors watcher
-    let db_clone = db.clone();
-    let capacity_clone = capacity.clone();
-    tokio::spawn(async move {
-        if let Err(e) = match_watcher::start_match_watcher(
-            db_clone.clone(),
-            games::RockPaperScissors::new(),
-            "rock-paper-scissors".to_string(),
-            capacity_clone.clone(),
-        )
-        .await
-        {
-            tracing::error!("Rock-Paper-Scissors match watcher error: {}", e);
-        }
-    });
-
-    // Prisoner's Dilemma watcher
-    let db_clone = db.clone();
-    let capacity_clone = capacity.clone();
-    tokio::spawn(async move {
-        if let Err(e) = match_watcher::start_match_watcher(
-            db_clone.clone(),
-            games::PrisonersDilemma::new(),
-            "prisoners-dilemma".to_string(),
-            capacity_clone.clone(),
-        )
-        .await
-        {
-            tracing::error!("Prisoner's Dilemma match watcher error: {}", e);
-        }
-    });
+/// Start a match watcher for every registered game (AI vs AI)
+pub fn start_match_watchers(
+    db: Database,
+    capacity: CapacityTracker,
+    games: &GameRegistry,
+    spectators: Arc<SpectatorHub>,
+) {
+    for game in games.iter() {
+        let db_clone = db.clone();
+        let capacity_clone = capacity.clone();
+        let spectators = spectators.clone();
+        let game = game.clone();
+        tokio::spawn(async move {
+            let name = game.metadata().name;
+            if let Err(e) =
+                match_watcher::start_match_watcher(db_clone, game, capacity_clone, spectators).await
+            {
+                tracing::error!("{} match watcher error: {}", name, e);
+            }
+        });
+    }
 }
 
 /// Recover orphaned rooms from previous server crashes
@@ -1,4 +1,5 @@
 use crate::db::Database;
+use crate::models::game_event::{GameEvent, GameEventRecord};
 use crate::models::room::{MatchRecord, RoomRecord};
 use serde::{Deserialize};
 use serde_json;
@@ -223,14 +224,17 @@ pub async fn append_room_history(
 pub async fn get_room_and_match_history_atomic(
     db: &Database,
     room_id: &str,
-) -> Result<(Vec<String>, Vec<String>), String> {
+) -> Result<(Vec<String>, Vec<GameEvent>), String> {
     // Single query to fetch both room and match history atomically
     let query = "
         LET $room_history = (SELECT event_history FROM room WHERE id = $room_id);
-        LET $match_history = (SELECT game_event_source, created_at FROM match
-                              WHERE room_id = $room_id AND status IN ['running', 'completed']
-                              ORDER BY created_at DESC LIMIT 1);
-        RETURN { room: $room_history[0].event_history, match: $match_history[0].game_event_source };
+        LET $latest_match = (SELECT id, created_at FROM match
+                             WHERE room_id = $room_id AND status IN ['running', 'completed']
+                             ORDER BY created_at DESC LIMIT 1);
+        LET $match_events = (SELECT seq, event FROM game_event
+                             WHERE match_id = $latest_match[0].id
+                             ORDER BY seq);
+        RETURN { room: $room_history[0].event_history, match: $match_events.event };
     ";
 
     let room_thing: Thing = room_id.parse().map_err(|_| "Invalid room ID format".to_string())?;
@@ -244,7 +248,7 @@ pub async fn get_room_and_match_history_atomic(
     struct HistoryResult {
         room: Option<Vec<String>>,
         #[serde(rename = "match")]
-        match_history: Option<String>,
+        match_history: Option<Vec<GameEvent>>,
     }
 
     let result: Option<HistoryResult> = response.take(0).map_err(|e| format!("Parse error: {}", e))?;
@@ -266,11 +270,7 @@ pub async fn get_room_and_match_history_atomic(
         Vec::new()
     };
 
-    let match_history = if let Some(ref history_str) = result.as_ref().and_then(|r| r.match_history.as_ref()) {
-        history_str.lines().map(|s| s.to_string()).collect()
-    } else {
-        Vec::new()
-    };
+    let match_history = result.and_then(|r| r.match_history).unwrap_or_default();
 
     Ok((room_history, match_history))
 }
@@ -308,7 +308,6 @@ pub async fn create_match(
         participants,
         metadata: None,
         room_id: Some(room_thing),
-        game_event_source: Some(String::new()), // Initialize empty game history
         judge_server_name: Some(hostname),
         created_at: Datetime::default(),
         updated_at: Datetime::default(),
@@ -329,27 +328,42 @@ pub async fn create_match(
     }
 }
 
-/// Append game event to match history
-pub async fn append_game_event(db: &Database, match_id: Thing, event: &str) -> Result<(), String> {
-    let query = "UPDATE $match_id SET game_event_source = string::concat(game_event_source, $event, '\n'), updated_at = time::now()";
+/// Complete a match record
+pub async fn complete_match(db: &Database, match_id: Thing) -> Result<(), String> {
+    let query = "UPDATE $match_id SET status = 'completed', completed_at = time::now(), updated_at = time::now()";
 
     db.query(query)
         .bind(("match_id", match_id))
-        .bind(("event", event.to_string()))
         .await
-        .map_err(|e| format!("Failed to append game event: {}", e))?;
+        .map_err(|e| format!("Failed to complete match: {}", e))?;
 
     Ok(())
 }
 
-/// Complete a match record
-pub async fn complete_match(db: &Database, match_id: Thing) -> Result<(), String> {
-    let query = "UPDATE $match_id SET status = 'completed', completed_at = time::now(), updated_at = time::now()";
+/// Latest running or completed match of a room
+pub async fn get_latest_match_id(db: &Database, room_id: &str) -> Result<Option<Thing>, String> {
+    let room_thing: Thing = room_id.parse().map_err(|_| "Invalid room ID format".to_string())?;
+    let mut response = db
+        .query("SELECT id, created_at FROM match WHERE room_id = $room_id AND status IN ['running', 'completed'] ORDER BY created_at DESC LIMIT 1")
+        .bind(("room_id", room_thing))
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
 
-    db.query(query)
+    #[derive(Deserialize)]
+    struct LatestMatch {
+        id: Thing,
+    }
+
+    let latest: Vec<LatestMatch> = response.take(0).map_err(|e| format!("Parse error: {}", e))?;
+    Ok(latest.into_iter().next().map(|m| m.id))
+}
+
+/// Every event of a match so far, in sequence order
+pub async fn get_match_events(db: &Database, match_id: Thing) -> Result<Vec<GameEventRecord>, String> {
+    let mut response = db
+        .query("SELECT * FROM game_event WHERE match_id = $match_id ORDER BY seq")
         .bind(("match_id", match_id))
         .await
-        .map_err(|e| format!("Failed to complete match: {}", e))?;
-
-    Ok(())
+        .map_err(|e| format!("Database error: {}", e))?;
+    response.take(0).map_err(|e| format!("Parse error: {}", e))
 }
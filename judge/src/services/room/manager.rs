@@ -3,8 +3,10 @@
 use super::db;
 use crate::db::Database;
 use crate::models::game::Game;
+use crate::models::game_event::GameEvent;
 use crate::models::players::{HumanPlayer, Player};
 use crate::models::room::*;
+use crate::services::spectator::SpectatorHub;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use surrealdb::sql::Thing;
@@ -15,15 +17,17 @@ pub struct RoomManager {
     pending_players: Arc<RwLock<HashMap<String, Arc<HumanPlayer>>>>,
     db: Database,
     restoring_rooms: Arc<RwLock<HashSet<String>>>,
+    spectators: Arc<SpectatorHub>,
 }
 
 impl RoomManager {
-    pub fn new(db: Database) -> Self {
+    pub fn new(db: Database, spectators: Arc<SpectatorHub>) -> Self {
         Self {
             rooms: Arc::new(RwLock::new(HashMap::new())),
             pending_players: Arc::new(RwLock::new(HashMap::new())),
             db,
             restoring_rooms: Arc::new(RwLock::new(HashSet::new())),
+            spectators,
         }
     }
 
@@ -383,16 +387,19 @@ impl RoomManager {
     // Game Execution
     // ========================================================================
 
-    pub async fn start_game<G: Game>(
+    pub async fn start_game(
         self: &Arc<Self>,
         room_id: &str,
         host_id: &str,
-        game: &G,
+        game: &dyn Game,
     ) -> Result<Vec<crate::games::GameResult>, String> {
         let ctx = self.prepare_game(room_id, host_id).await?;
         let match_id = ctx.match_id.clone();
-        let results = self.run_game(game, ctx).await;
-        self.finalize_game(room_id, &match_id, &results).await;
+        // One context for the whole match keeps its events in one sequence
+        let game_context = GameContext::new(match_id.clone(), self.db.clone())
+            .with_spectators(self.spectators.clone());
+        let results = self.run_game(game, ctx, game_context.clone()).await;
+        self.finalize_game(room_id, &match_id, &game_context, &results).await;
         Ok(results)
     }
 
@@ -444,15 +451,12 @@ impl RoomManager {
         })
     }
 
-    async fn run_game<G: Game>(&self, game: &G, ctx: GameStartContext) -> Vec<crate::games::GameResult> {
-        let game_context = GameContext::new(ctx.match_id.clone(), self.db.clone());
-
+    async fn run_game(&self, game: &dyn Game, ctx: GameStartContext, game_context: GameContext) -> Vec<crate::games::GameResult> {
         // Write start event
-        let _ = db::append_game_event(
-            &self.db,
-            ctx.match_id.clone(),
-            &format!("GAME_START game_id={} players={}", ctx.game_id, ctx.players.len()),
-        ).await;
+        game_context.write_event(GameEvent::MatchStart {
+            game_id: ctx.game_id.clone(),
+            players: ctx.players.len(),
+        }).await;
 
         // Run with 1 hour timeout
         let timeout = tokio::time::Duration::from_secs(3600);
@@ -465,10 +469,13 @@ impl RoomManager {
         }
     }
 
-    async fn finalize_game(&self, room_id: &str, match_id: &Thing, results: &[crate::games::GameResult]) {
+    async fn finalize_game(&self, room_id: &str, match_id: &Thing, game_context: &GameContext, results: &[crate::games::GameResult]) {
         // Write end event
+        game_context.write_event(GameEvent::MatchEnd { results: results.to_vec() }).await;
+        if let Err(e) = game_context.finish() {
+            tracing::error!("{}", e);
+        }
         let results_json = serde_json::to_string(results).unwrap_or_default();
-        let _ = db::append_game_event(&self.db, match_id.clone(), &format!("GAME_END results={}", results_json)).await;
 
         // Update room status
         let rooms = self.rooms.read().await;
@@ -490,10 +497,6 @@ impl RoomManager {
         let _ = db::complete_match(&self.db, match_id.clone()).await;
     }
 
-    pub async fn append_game_event(&self, match_id: Thing, event: &str) -> Result<(), String> {
-        db::append_game_event(&self.db, match_id, event).await
-    }
-
     // ========================================================================
     // Recovery
     // ========================================================================
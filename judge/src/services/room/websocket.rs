@@ -3,25 +3,28 @@ use crate::middleware::auth::validate_jwt;
 use crate::models::game::Game;
 use crate::models::players::{HumanPlayer, Player};
 use axum::{
-    extract::{Path, State, ws::{Message, WebSocket, WebSocketUpgrade}},
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
     response::Response,
 };
 use futures_util::{SinkExt, StreamExt};
 use std::sync::Arc;
 use surrealdb::sql::Thing;
 
-pub async fn ws_get_room<G: Game + Clone + Send + Sync + 'static>(
+/// WebSocket endpoint for one game's rooms, mounted once per registered game
+pub async fn ws_get_room(
     ws: WebSocketUpgrade,
-    Path(room_id): Path<String>,
-    State(state): State<Arc<AppState<G>>>,
+    room_id: String,
+    state: Arc<AppState>,
+    game: Arc<dyn Game>,
 ) -> Response {
-    ws.on_upgrade(move |socket| handle_websocket(socket, room_id, state))
+    ws.on_upgrade(move |socket| handle_websocket(socket, room_id, state, game))
 }
 
-async fn handle_websocket<G: Game + Clone + Send + Sync + 'static>(
+async fn handle_websocket(
     socket: WebSocket,
     room_id: String,
-    state: Arc<AppState<G>>,
+    state: Arc<AppState>,
+    game: Arc<dyn Game>,
 ) {
     let (mut sender, mut receiver) = socket.split();
 
@@ -76,7 +79,6 @@ async fn handle_websocket<G: Game + Clone + Send + Sync + 'static>(
                     for msg in room_history {
                         let _ = player.send_message(&msg).await;
                     }
-                    let game = state.game.clone();
                     if !match_history.is_empty() {
                         game.restore_from_events(&match_history);
                     }
@@ -112,9 +114,10 @@ async fn handle_websocket<G: Game + Clone + Send + Sync + 'static>(
                     let room_id = room_id.clone();
                     let player_id = player_id.clone();
                     let state = state.clone();
+                    let game = game.clone();
                     let player = player.clone();
                     tokio::spawn(async move {
-                        if let Err(e) = state.room_manager.start_game(&room_id, &player_id, &state.game).await {
+                        if let Err(e) = state.room_manager.start_game(&room_id, &player_id, game.as_ref()).await {
                             let _ = player.send_message(&format!("ERROR {}", e)).await;
                         }
                     });
@@ -1,10 +1,12 @@
 use cgroups_rs::*;
 use cgroups_rs::fs::{cgroup_builder::CgroupBuilder, MaxValue, Cgroup, hierarchies};
 use nix::unistd::Pid;
-use crate::services::sandbox::{Result, SandboxError, ResourceLimits};
+use std::path::PathBuf;
+use crate::services::sandbox::{Result, SandboxError, ResourceLimits, ResourceUsage};
 
 pub struct CgroupHandle {
     cgroup: Cgroup,
+    path: PathBuf,
 }
 
 impl CgroupHandle {
@@ -14,15 +16,15 @@ impl CgroupHandle {
         Self::new(&format!("judge/compilation/submission_{}", submission_id), limits)
     }
 
-    /// Create a new cgroup for execution with appropriate resource limits
-    pub fn new_execution(player_id: &str) -> Result<Self> {
-        let limits = ResourceLimits::execution();
-        Self::new(&format!("judge/execution/player_{}", player_id), limits)
+    /// Create a new cgroup for execution with the game's resource limits
+    pub fn new_execution(player_id: &str, limits: &ResourceLimits) -> Result<Self> {
+        Self::new(&format!("judge/execution/player_{}", player_id), limits.clone())
     }
 
     /// Create a cgroup with custom resource limits
     fn new(cgroup_name: &str, limits: ResourceLimits) -> Result<Self> {
         let hierarchy = hierarchies::auto();
+        let path = hierarchy.root().join(cgroup_name);
 
         // Try to delete existing cgroup first
         let existing = Cgroup::load(hierarchy, cgroup_name);
@@ -42,7 +44,7 @@ impl CgroupHandle {
             .build(hierarchies::auto())
             .map_err(|e| SandboxError::CgroupError(format!("Failed to create cgroup: {}", e)))?;
 
-        Ok(Self { cgroup })
+        Ok(Self { cgroup, path })
     }
 
     pub fn add_task(&self, pid: Pid) -> Result<()> {
@@ -52,6 +54,32 @@ impl CgroupHandle {
             .map_err(|e| SandboxError::CgroupError(format!("Failed to add task to cgroup v2: {}", e)))?;
         Ok(())
     }
+
+    /// Read the usage counters of the cgroup. They outlive the processes in it,
+    /// so this works after the bot has exited, until the handle is dropped.
+    /// Only cgroup v2 files are read, so on a v1 host every counter is None.
+    pub fn usage(&self) -> ResourceUsage {
+        ResourceUsage {
+            peak_memory_bytes: self.read_value("memory.peak"),
+            cpu_time_us: self.read_key("cpu.stat", "usage_usec"),
+            oom_kills: self.read_key("memory.events", "oom_kill"),
+            pid_limit_hits: self.read_key("pids.events", "max"),
+        }
+    }
+
+    /// Read a single value file such as memory.peak
+    fn read_value(&self, file: &str) -> Option<u64> {
+        std::fs::read_to_string(self.path.join(file)).ok()?.trim().parse().ok()
+    }
+
+    /// Read one key of a flat keyed file such as cpu.stat
+    fn read_key(&self, file: &str, key: &str) -> Option<u64> {
+        let content = std::fs::read_to_string(self.path.join(file)).ok()?;
+        content.lines().find_map(|line| {
+            let (name, value) = line.split_once(' ')?;
+            if name == key { value.trim().parse().ok() } else { None }
+        })
+    }
 }
 
 impl Drop for CgroupHandle {
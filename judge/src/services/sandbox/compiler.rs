@@ -7,8 +7,9 @@ use nix::unistd::{fork, ForkResult, execvp, Pid};
 use std::os::unix::io::IntoRawFd;
 use std::ffi::CString;
 use std::time::Duration;
-use crate::services::sandbox::{Result, SandboxError};
+use crate::services::sandbox::{Result, ResourceLimits, SandboxError};
 use crate::services::sandbox::cgroup::CgroupHandle;
+use crate::services::sandbox::interpreter::Interpreter;
 
 /// Compiler sandbox for secure code compilation
 pub struct CompilerSandbox {
@@ -29,6 +30,7 @@ impl CompilerSandbox {
     /// Compile source code with resource limits (cgroups only, no namespace isolation)
     /// Compilation doesn't need namespace isolation since the compiler is trusted.
     /// We only need CPU/memory limits to prevent resource exhaustion.
+    /// Interpreted languages are only syntax checked and the source becomes the artifact.
     pub async fn compile(
         &self,
         submission_id: &str,
@@ -45,6 +47,8 @@ impl CompilerSandbox {
             "rust" => ("main.rs", "player"),
             "go" => ("main.go", "player"),
             "c" => ("main.c", "player"),
+            "python" => (Interpreter::Python.source_name(), Interpreter::Python.script_name()),
+            "javascript" => (Interpreter::Node.source_name(), Interpreter::Node.script_name()),
             _ => return Err(SandboxError::CompilationError(format!("Unsupported language: {}", language))),
         };
 
@@ -88,7 +92,7 @@ impl CompilerSandbox {
         };
 
         // Wait for compilation with timeout (in separate task to avoid blocking)
-        let timeout = Duration::from_secs(60);
+        let timeout = Duration::from_millis(ResourceLimits::compilation().wall_clock_ms);
         let result = tokio::task::spawn_blocking(move || {
             Self::wait_for_child(pid, timeout)
         })
@@ -110,14 +114,20 @@ impl CompilerSandbox {
                 .unwrap_or_else(|_| "Failed to read compile log".to_string());
 
             return Err(SandboxError::CompilationError(format!(
-                "Compilation failed with exit code: {}. Log:\n{}",
+                "exit code {}\n{}",
                 result,
                 log_content
             )));
         }
 
-        // Check if binary was created
+        // Interpreted sources passed the check and are run as they are
         let binary_path = workspace.join(binary_name);
+        if Interpreter::from_language(language).is_some() {
+            async_fs::copy(&source_path, &binary_path).await
+                .map_err(|e| SandboxError::CompilationError(format!("Failed to store checked source: {}", e)))?;
+        }
+
+        // Check if binary was created
         if !binary_path.exists() {
             return Err(SandboxError::CompilationError("Compilation produced no binary".to_string()));
         }
@@ -306,6 +316,13 @@ impl CompilerSandbox {
                     ],
                 ))
             }
+            "python" | "javascript" => {
+                let interpreter = Interpreter::from_language(language)
+                    .ok_or_else(|| SandboxError::CompilationError(format!("Unsupported language: {}", language)))?;
+                let source_path = workspace.join(interpreter.source_name());
+
+                Ok((interpreter.binary(), interpreter.check_args(&source_path)))
+            }
             _ => Err(SandboxError::CompilationError(format!("Unsupported language: {}", language))),
         }
     }
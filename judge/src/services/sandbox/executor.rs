@@ -1,14 +1,15 @@
 use std::path::Path;
 use std::os::unix::io::{FromRawFd, IntoRawFd, AsRawFd};
 use caps::{CapSet, clear};
-use nix::unistd::{fork, ForkResult, execv, Pid, pipe, dup2};
+use nix::unistd::{fork, ForkResult, execv, execve, Pid, pipe, dup2};
 use std::ffi::CString;
 use std::fs::File;
-use crate::services::sandbox::{Result, SandboxError};
+use crate::services::sandbox::{Result, ResourceLimits, SandboxError};
 use crate::services::sandbox::namespace::{create_namespaces, setup_mount_namespace, setup_self_uid_mapping};
 use crate::services::sandbox::cgroup::CgroupHandle;
+use crate::services::sandbox::interpreter::Interpreter;
 use crate::services::sandbox::landlock::apply_execution_rules;
-use crate::services::sandbox::seccomp::apply_execution_filter;
+use crate::services::sandbox::seccomp::{apply_execution_filter, apply_interpreter_filter};
 use crate::services::sandbox::rootfs::setup_execution_rootfs;
 
 /// Result of spawning a sandboxed process
@@ -16,6 +17,7 @@ pub struct SandboxedProcess {
     pub pid: Pid,
     pub stdin_fd: i32,
     pub stdout_fd: i32,
+    pub stderr_fd: i32,
     pub cgroup: CgroupHandle,
 }
 
@@ -26,8 +28,8 @@ pub fn drop_all_capabilities() -> Result<()> {
     let _ = clear(None, CapSet::Ambient);
     Ok(())
 }
-/// Spawn a binary in an isolated sandbox with stdin/stdout pipes
-pub fn spawn_sandboxed(player_id: &str, binary_path: &Path) -> Result<SandboxedProcess> {
+/// Spawn a binary in an isolated sandbox with stdin/stdout pipes, confined by `limits`
+pub fn spawn_sandboxed(player_id: &str, binary_path: &Path, limits: &ResourceLimits) -> Result<SandboxedProcess> {
     // Create pipes for stdin/stdout communication
     let (stdin_read, stdin_write) = pipe()
         .map_err(|e| SandboxError::ProcessError(format!("Failed to create stdin pipe: {}", e)))?;
@@ -35,21 +37,27 @@ pub fn spawn_sandboxed(player_id: &str, binary_path: &Path) -> Result<SandboxedP
     let (stdout_read, stdout_write) = pipe()
         .map_err(|e| SandboxError::ProcessError(format!("Failed to create stdout pipe: {}", e)))?;
 
+    // Stderr gets its own pipe so bot debug output never lands in the judge's logs
+    let (stderr_read, stderr_write) = pipe()
+        .map_err(|e| SandboxError::ProcessError(format!("Failed to create stderr pipe: {}", e)))?;
+
     let stdin_read_raw = stdin_read.as_raw_fd();
     let stdout_write_raw = stdout_write.as_raw_fd();
+    let stderr_write_raw = stderr_write.as_raw_fd();
 
     // Keep pipes in blocking mode
     // The game uses async I/O with timeouts, so blocking is fine and avoids race conditions
     // where we try to read before the child process has started writing
 
     // Create cgroup before forking
-    let cgroup = CgroupHandle::new_execution(player_id)?;
+    let cgroup = CgroupHandle::new_execution(player_id, limits)?;
 
     match unsafe { fork() } {
         Ok(ForkResult::Parent { child }) => {
             // Parent process: close child ends of pipes
             drop(stdin_read);
             drop(stdout_write);
+            drop(stderr_write);
 
             // Add child to cgroup (do this before child enters new user namespace)
             cgroup.add_task(child)?;
@@ -64,6 +72,7 @@ pub fn spawn_sandboxed(player_id: &str, binary_path: &Path) -> Result<SandboxedP
                 pid: child,
                 stdin_fd: stdin_write.into_raw_fd(),
                 stdout_fd: stdout_read.into_raw_fd(),
+                stderr_fd: stderr_read.into_raw_fd(),
                 cgroup,
             })
         }
@@ -72,8 +81,9 @@ pub fn spawn_sandboxed(player_id: &str, binary_path: &Path) -> Result<SandboxedP
             // Close parent ends of pipes (drop them)
             drop(stdin_write);
             drop(stdout_read);
+            drop(stderr_read);
 
-            if let Err(e) = child_setup_and_exec(binary_path, stdin_read_raw, stdout_write_raw) {
+            if let Err(e) = child_setup_and_exec(binary_path, stdin_read_raw, stdout_write_raw, stderr_write_raw) {
                 eprintln!("Child setup failed: {}", e);
                 std::process::exit(1);
             }
@@ -88,22 +98,27 @@ fn child_setup_and_exec(
     binary_path: &Path,
     stdin_fd: i32,
     stdout_fd: i32,
+    stderr_fd: i32,
 ) -> Result<()> {
-    // 1. Dup pipes to stdin/stdout
-    // Keep stderr (fd 2) pointing to parent's stderr for error messages
+    // 1. Dup pipes to stdin/stdout/stderr
     use std::os::fd::{FromRawFd, OwnedFd};
     unsafe {
         let stdin_owned = OwnedFd::from_raw_fd(stdin_fd);
         let stdout_owned = OwnedFd::from_raw_fd(stdout_fd);
+        let stderr_owned = OwnedFd::from_raw_fd(stderr_fd);
         let mut stdin = OwnedFd::from_raw_fd(0);
         let mut stdout = OwnedFd::from_raw_fd(1);
+        let mut stderr = OwnedFd::from_raw_fd(2);
         dup2(&stdin_owned, &mut stdin)
             .map_err(|e| SandboxError::ProcessError(format!("Failed to dup2 stdin: {}", e)))?;
         dup2(&stdout_owned, &mut stdout)
             .map_err(|e| SandboxError::ProcessError(format!("Failed to dup2 stdout: {}", e)))?;
+        dup2(&stderr_owned, &mut stderr)
+            .map_err(|e| SandboxError::ProcessError(format!("Failed to dup2 stderr: {}", e)))?;
         std::mem::forget(stdin);
         std::mem::forget(stdout);
-        // stdin_owned and stdout_owned will be closed when dropped
+        std::mem::forget(stderr);
+        // stdin_owned, stdout_owned and stderr_owned will be closed when dropped
     }
 
     let (host_uid, host_gid) = create_namespaces(false)?;
@@ -113,13 +128,35 @@ fn child_setup_and_exec(
     let tmp_root = tempfile::tempdir()
         .map_err(|e| SandboxError::RootfsError(format!("Failed to create temp dir: {}", e)))?;
 
-    let binary_in_sandbox = setup_execution_rootfs(binary_path, tmp_root.path())?;
+    // Scripts are run by an interpreter instead of being executed directly
+    let interpreter = Interpreter::from_script(binary_path);
+    let binary_in_sandbox = setup_execution_rootfs(binary_path, tmp_root.path(), interpreter)?;
 
+    // The interpreter lives under /usr, which the landlock rules already allow
     if crate::services::sandbox::landlock::is_supported() {
         apply_execution_rules(&binary_in_sandbox)?;
     }
 
     drop_all_capabilities()?;
+
+    if let Some(interpreter) = interpreter {
+        apply_interpreter_filter()?;
+
+        let interpreter_bin = interpreter.binary();
+        let bin_cstring = CString::new(interpreter_bin.as_str())
+            .map_err(|e| SandboxError::ProcessError(format!("Invalid interpreter path: {}", e)))?;
+        let mut args = vec![bin_cstring.clone()];
+        for arg in interpreter.run_args(&binary_in_sandbox) {
+            args.push(CString::new(arg)
+                .map_err(|e| SandboxError::ProcessError(format!("Invalid interpreter argument: {}", e)))?);
+        }
+        // Start from a clean environment so nothing from the judge leaks into the interpreter
+        let env = vec![CString::new("PATH=/usr/local/bin:/usr/bin").unwrap()];
+        execve(&bin_cstring, &args, &env)
+            .map_err(|e| SandboxError::ProcessError(format!("execve failed: {}", e)))?;
+        unreachable!("execve should not return");
+    }
+
     apply_execution_filter()?;
 
     let bin_cstring = CString::new(binary_in_sandbox.to_str().unwrap())
@@ -0,0 +1,76 @@
+use std::path::Path;
+
+/// Interpreters for submissions that are not compiled to a native binary.
+/// The checked source file is the artifact; at run time the interpreter and
+/// its standard library are mounted read-only from the host's /usr.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interpreter {
+    Python,
+    Node,
+}
+
+impl Interpreter {
+    /// Interpreter for a submission language, None for compiled languages
+    pub fn from_language(language: &str) -> Option<Self> {
+        match language {
+            "python" => Some(Self::Python),
+            "javascript" => Some(Self::Node),
+            _ => None,
+        }
+    }
+
+    /// Interpreter for a compiled artifact, based on its extension
+    pub fn from_script(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "py" => Some(Self::Python),
+            "js" => Some(Self::Node),
+            _ => None,
+        }
+    }
+
+    /// Name of the submitted source in the compile workspace
+    pub fn source_name(&self) -> &'static str {
+        match self {
+            Self::Python => "main.py",
+            Self::Node => "main.js",
+        }
+    }
+
+    /// Name of the artifact, both in the workspace and inside the sandbox
+    pub fn script_name(&self) -> &'static str {
+        match self {
+            Self::Python => "player.py",
+            Self::Node => "player.js",
+        }
+    }
+
+    /// Absolute path of the interpreter. It has to live under /usr, which is
+    /// the only part of the host visible inside the execution sandbox.
+    pub fn binary(&self) -> String {
+        match self {
+            Self::Python => {
+                std::env::var("PYTHON_BIN").unwrap_or_else(|_| "/usr/bin/python3".to_string())
+            }
+            Self::Node => std::env::var("NODE_BIN").unwrap_or_else(|_| "/usr/bin/node".to_string()),
+        }
+    }
+
+    /// Arguments that byte-compile or syntax check the source without running it
+    pub fn check_args(&self, source: &Path) -> Vec<String> {
+        let source = source.to_string_lossy().to_string();
+        match self {
+            Self::Python => vec!["-m".to_string(), "py_compile".to_string(), source],
+            Self::Node => vec!["--check".to_string(), source],
+        }
+    }
+
+    /// Arguments that run the script. Python is isolated from the environment,
+    /// writes no bytecode and keeps stdout unbuffered so moves reach the judge.
+    pub fn run_args(&self, script: &Path) -> Vec<String> {
+        let script = script.to_string_lossy().to_string();
+        match self {
+            Self::Python => vec!["-I".to_string(), "-B".to_string(), "-u".to_string(), script],
+            Self::Node => vec!["--v8-pool-size=1".to_string(), script],
+        }
+    }
+}
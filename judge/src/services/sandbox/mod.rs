@@ -4,8 +4,11 @@ pub mod landlock;
 pub mod seccomp;
 pub mod rootfs;
 pub mod compiler;
+pub mod interpreter;
 pub mod executor;
 
+use game_registry::GameMetadata;
+use serde::Serialize;
 use thiserror::Error;
 
 /// Sandbox result type
@@ -62,26 +65,55 @@ pub struct ResourceLimits {
     pub cpu_period: u64,
     /// Maximum number of PIDs
     pub max_pids: i64,
+    /// Wall-clock budget in milliseconds, enforced outside the cgroup
+    pub wall_clock_ms: u64,
 }
 
 impl ResourceLimits {
-    /// Limits for compilation: 512MB RAM, 1 CPU, 128 processes
+    /// Limits for a bot from a CPU count, memory in MB, process count and time budget
+    pub fn new(cpu_limit: f64, memory_limit_mb: u64, max_pids: u32, wall_clock_ms: u64) -> Self {
+        let cpu_period = 100_000;
+        Self {
+            memory_bytes: (memory_limit_mb * 1024 * 1024) as i64,
+            cpu_quota: (cpu_limit * cpu_period as f64).round().max(1_000.0) as i64,
+            cpu_period,
+            max_pids: max_pids.max(1) as i64,
+            wall_clock_ms,
+        }
+    }
+
+    /// Limits for compilation: 512MB RAM, 1 CPU, 128 processes, 60 seconds
     pub fn compilation() -> Self {
         Self {
             memory_bytes: 512 * 1024 * 1024,
             cpu_quota: 100_000,
             cpu_period: 100_000,
             max_pids: 128,
+            wall_clock_ms: 60_000,
         }
     }
 
-    /// Limits for execution: 64MB RAM, 1 CPU, 16 processes
+    /// Default limits for execution: 64MB RAM, 1 CPU, 16 processes, 60 seconds
     pub fn execution() -> Self {
-        Self {
-            memory_bytes: 64 * 1024 * 1024,
-            cpu_quota: 100_000,
-            cpu_period: 100_000,
-            max_pids: 16,
-        }
+        Self::new(1.0, 64, 16, 60_000)
+    }
+
+    /// Limits a game declares for its bots
+    pub fn for_game(game: &GameMetadata) -> Self {
+        Self::new(game.cpu_limit, game.memory_limit_mb, game.max_pids, game.wall_clock_ms)
     }
 }
+
+/// Resources a sandboxed process used, read from its cgroup v2 counters.
+/// A counter the host does not expose, such as every one of them on cgroup v1, is None.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResourceUsage {
+    /// Highest memory use in bytes, None on kernels without memory.peak
+    pub peak_memory_bytes: Option<u64>,
+    /// CPU time in microseconds, user and system combined
+    pub cpu_time_us: Option<u64>,
+    /// Processes killed by the OOM killer
+    pub oom_kills: Option<u64>,
+    /// Forks and clones refused because the pid limit was reached
+    pub pid_limit_hits: Option<u64>,
+}
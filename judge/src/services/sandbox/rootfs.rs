@@ -4,6 +4,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::io::Read;
 use crate::services::sandbox::{Result, SandboxError};
+use crate::services::sandbox::interpreter::Interpreter;
 
 fn check_if_dynamic_binary(binary: &Path) -> Result<bool> {
     let mut file = fs::File::open(binary)
@@ -47,13 +48,57 @@ fn check_if_dynamic_binary(binary: &Path) -> Result<bool> {
     Ok(false)
 }
 
-pub fn setup_execution_rootfs(binary: &Path, tmp_dir: &Path) -> Result<PathBuf> {
+/// Bind mount the host's /usr read-only into the new root, with /lib and
+/// /lib64 pointing into it, for dynamic loaders and interpreters
+fn mount_system_usr(tmp_dir: &Path) -> Result<()> {
+    fs::create_dir_all(tmp_dir.join("usr"))
+        .map_err(|e| SandboxError::RootfsError(format!("Failed to create usr dir: {}", e)))?;
+
+    mount(
+        Some(Path::new("/usr")),
+        tmp_dir.join("usr").as_path(),
+        None::<&str>,
+        MsFlags::MS_BIND | MsFlags::MS_REC,
+        None::<&str>,
+    )
+    .map_err(|e| SandboxError::RootfsError(format!("Failed to bind mount /usr: {}", e)))?;
+
+    mount(
+        None::<&str>,
+        tmp_dir.join("usr").as_path(),
+        None::<&str>,
+        MsFlags::MS_BIND | MsFlags::MS_REMOUNT | MsFlags::MS_RDONLY,
+        None::<&str>,
+    )
+    .map_err(|e| SandboxError::RootfsError(format!("Failed to remount /usr as read-only: {}", e)))?;
+
+    std::os::unix::fs::symlink("usr/lib", tmp_dir.join("lib"))
+        .map_err(|e| SandboxError::RootfsError(format!("Failed to create /lib symlink: {}", e)))?;
+
+    if Path::new("/usr/lib64").exists() {
+        std::os::unix::fs::symlink("usr/lib64", tmp_dir.join("lib64"))
+            .map_err(|e| SandboxError::RootfsError(format!("Failed to create /lib64 symlink: {}", e)))?;
+    } else {
+        std::os::unix::fs::symlink("usr/lib", tmp_dir.join("lib64"))
+            .map_err(|e| SandboxError::RootfsError(format!("Failed to create /lib64 symlink: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Build the sandbox root around the player. Scripts are copied next to where
+/// a binary would go and get the interpreter's /usr mounted alongside.
+pub fn setup_execution_rootfs(
+    binary: &Path,
+    tmp_dir: &Path,
+    interpreter: Option<Interpreter>,
+) -> Result<PathBuf> {
     fs::create_dir_all(tmp_dir.join("dev"))
         .map_err(|e| SandboxError::RootfsError(format!("Failed to create dev dir: {}", e)))?;
     fs::create_dir_all(tmp_dir.join("oldroot"))
         .map_err(|e| SandboxError::RootfsError(format!("Failed to create oldroot dir: {}", e)))?;
 
-    let binary_dest = tmp_dir.join("player");
+    let player_name = interpreter.map_or("player", |i| i.script_name());
+    let binary_dest = tmp_dir.join(player_name);
     fs::copy(binary, &binary_dest)
         .map_err(|e| SandboxError::RootfsError(format!("Failed to copy binary: {}", e)))?;
 
@@ -68,41 +113,12 @@ pub fn setup_execution_rootfs(binary: &Path, tmp_dir: &Path) -> Result<PathBuf>
             .map_err(|e| SandboxError::RootfsError(format!("Failed to set binary permissions: {}", e)))?;
     }
 
-    let is_likely_dynamic = check_if_dynamic_binary(&binary_dest)?;
-    if is_likely_dynamic {
+    if let Some(interpreter) = interpreter {
+        tracing::debug!("Script for {:?}, mounting interpreter and standard library", interpreter);
+        mount_system_usr(tmp_dir)?;
+    } else if check_if_dynamic_binary(&binary_dest)? {
         tracing::debug!("Binary is dynamically linked, mounting system libraries");
-
-        fs::create_dir_all(tmp_dir.join("usr"))
-            .map_err(|e| SandboxError::RootfsError(format!("Failed to create usr dir: {}", e)))?;
-
-        mount(
-            Some(Path::new("/usr")),
-            tmp_dir.join("usr").as_path(),
-            None::<&str>,
-            MsFlags::MS_BIND | MsFlags::MS_REC,
-            None::<&str>,
-        )
-        .map_err(|e| SandboxError::RootfsError(format!("Failed to bind mount /usr: {}", e)))?;
-
-        mount(
-            None::<&str>,
-            tmp_dir.join("usr").as_path(),
-            None::<&str>,
-            MsFlags::MS_BIND | MsFlags::MS_REMOUNT | MsFlags::MS_RDONLY,
-            None::<&str>,
-        )
-        .map_err(|e| SandboxError::RootfsError(format!("Failed to remount /usr as read-only: {}", e)))?;
-
-        std::os::unix::fs::symlink("usr/lib", tmp_dir.join("lib"))
-            .map_err(|e| SandboxError::RootfsError(format!("Failed to create /lib symlink: {}", e)))?;
-
-        if Path::new("/usr/lib64").exists() {
-            std::os::unix::fs::symlink("usr/lib64", tmp_dir.join("lib64"))
-                .map_err(|e| SandboxError::RootfsError(format!("Failed to create /lib64 symlink: {}", e)))?;
-        } else {
-            std::os::unix::fs::symlink("usr/lib", tmp_dir.join("lib64"))
-                .map_err(|e| SandboxError::RootfsError(format!("Failed to create /lib64 symlink: {}", e)))?;
-        }
+        mount_system_usr(tmp_dir)?;
     } else {
         tracing::debug!("Binary is statically linked");
     }
@@ -129,5 +145,5 @@ pub fn setup_execution_rootfs(binary: &Path, tmp_dir: &Path) -> Result<PathBuf>
         .map_err(|e| SandboxError::RootfsError(format!("Failed to remove oldroot: {}", e)))?;
 
     tracing::debug!("Execution rootfs setup complete");
-    Ok(PathBuf::from("/player"))
+    Ok(Path::new("/").join(player_name))
 }
@@ -3,11 +3,145 @@ use std::collections::BTreeMap;
 use crate::services::sandbox::{Result, SandboxError};
 
 pub fn apply_execution_filter() -> Result<()> {
-    let filter = execution_filter()?;
+    let mut filter_rules = execution_rules(&[]);
+    filter_rules.insert(nix::libc::SYS_tgkill, vec![own_process_rule()?]);
+    apply_filter(forbidden_filter()?)?;
+    let filter = build_filter(filter_rules)?;
     apply_filter(filter)
 }
 
-fn execution_filter() -> Result<BpfProgram> {
+/// Execution filter plus what Python and Node need on top of a native binary:
+/// runtime threads, memory management and their event loops. Threads are
+/// allowed but new processes are not, so a script cannot fork and exec the
+/// host binaries under the mounted /usr.
+pub fn apply_interpreter_filter() -> Result<()> {
+    let mut filter_rules = execution_rules(&[
+        nix::libc::SYS_madvise,
+        nix::libc::SYS_mremap,
+        nix::libc::SYS_membarrier,
+        nix::libc::SYS_sigaltstack,
+        nix::libc::SYS_getppid,
+        nix::libc::SYS_getresuid,
+        nix::libc::SYS_getresgid,
+        nix::libc::SYS_getrusage,
+        nix::libc::SYS_sysinfo,
+        nix::libc::SYS_statfs,
+        nix::libc::SYS_fstatfs,
+        nix::libc::SYS_eventfd2,
+        nix::libc::SYS_epoll_pwait2,
+        nix::libc::SYS_clock_getres,
+        nix::libc::SYS_gettimeofday,
+        nix::libc::SYS_sched_getparam,
+        nix::libc::SYS_sched_getscheduler,
+    ]);
+    filter_rules.insert(nix::libc::SYS_clone, vec![thread_clone_rule()?]);
+    filter_rules.insert(nix::libc::SYS_tgkill, vec![own_process_rule()?]);
+    // Let clone3 through here so the ENOSYS of the fallback filter decides it;
+    // the kernel applies the strictest action of all installed filters
+    filter_rules.insert(nix::libc::SYS_clone3, vec![]);
+    let filter = build_filter(filter_rules)?;
+    apply_filter(forbidden_filter()?)?;
+    apply_filter(clone3_fallback_filter()?)?;
+    // Last, since it no longer allows the seccomp syscall itself
+    apply_filter(filter)
+}
+
+/// clone is only allowed with CLONE_THREAD, which creates a thread of the
+/// calling process; fork, vfork-style spawns and new namespaces lack it
+fn thread_clone_rule() -> Result<SeccompRule> {
+    let clone_thread = nix::libc::CLONE_THREAD as u64;
+    let condition = SeccompCondition::new(
+        0,
+        SeccompCmpArgLen::Qword,
+        SeccompCmpOp::MaskedEq(clone_thread),
+        clone_thread,
+    )
+    .map_err(|e| SandboxError::SeccompError(format!("Invalid clone condition: {:?}", e)))?;
+    SeccompRule::new(vec![condition])
+        .map_err(|e| SandboxError::SeccompError(format!("Invalid clone rule: {:?}", e)))
+}
+
+/// tgkill is only allowed on the calling process, which is how abort() and
+/// runtimes signal their own threads. The bot shares the judge's uid, so it
+/// must not reach other processes. Built in the child, where getpid is the bot.
+fn own_process_rule() -> Result<SeccompRule> {
+    let condition = SeccompCondition::new(
+        0,
+        SeccompCmpArgLen::Dword,
+        SeccompCmpOp::Eq,
+        nix::unistd::getpid().as_raw() as u64,
+    )
+    .map_err(|e| SandboxError::SeccompError(format!("Invalid tgkill condition: {:?}", e)))?;
+    SeccompRule::new(vec![condition])
+        .map_err(|e| SandboxError::SeccompError(format!("Invalid tgkill rule: {:?}", e)))
+}
+
+/// clone3 passes its flags in a struct that seccomp cannot inspect. Failing it
+/// with ENOSYS makes libc fall back to clone, where the flags are checked.
+fn clone3_fallback_filter() -> Result<BpfProgram> {
+    let rules: BTreeMap<i64, Vec<SeccompRule>> =
+        BTreeMap::from([(nix::libc::SYS_clone3, vec![])]);
+
+    SeccompFilter::new(
+        rules,
+        SeccompAction::Allow,
+        SeccompAction::Errno(nix::libc::ENOSYS as u32),
+        std::env::consts::ARCH.try_into()
+            .map_err(|e| SandboxError::SeccompError(format!("Invalid arch: {:?}", e)))?,
+    )
+    .map_err(|e| SandboxError::SeccompError(format!("Failed to create seccomp filter: {:?}", e)))?
+    .try_into()
+    .map_err(|e| SandboxError::SeccompError(format!("Failed to build BPF program: {:?}", e)))
+}
+
+/// Syscalls that only make sense as an escape attempt. Other syscalls outside
+/// the allow list fail with EPERM, which runtimes cope with; these kill the
+/// process with SIGSYS so the attempt shows up as a seccomp violation.
+/// Stacked filters take the strictest action, so this sits on top of the allow list.
+fn forbidden_filter() -> Result<BpfProgram> {
+    let forbidden_syscalls = vec![
+        nix::libc::SYS_ptrace,
+        nix::libc::SYS_process_vm_readv,
+        nix::libc::SYS_process_vm_writev,
+        nix::libc::SYS_mount,
+        nix::libc::SYS_umount2,
+        nix::libc::SYS_pivot_root,
+        nix::libc::SYS_chroot,
+        nix::libc::SYS_unshare,
+        nix::libc::SYS_setns,
+        nix::libc::SYS_bpf,
+        nix::libc::SYS_perf_event_open,
+        nix::libc::SYS_userfaultfd,
+        nix::libc::SYS_open_by_handle_at,
+        nix::libc::SYS_keyctl,
+        nix::libc::SYS_add_key,
+        nix::libc::SYS_request_key,
+        nix::libc::SYS_init_module,
+        nix::libc::SYS_finit_module,
+        nix::libc::SYS_delete_module,
+        nix::libc::SYS_kexec_load,
+        nix::libc::SYS_reboot,
+    ];
+    let rules: BTreeMap<i64, Vec<SeccompRule>> = forbidden_syscalls
+        .into_iter()
+        .map(|syscall| (syscall, vec![]))
+        .collect();
+
+    SeccompFilter::new(
+        rules,
+        SeccompAction::Allow,
+        SeccompAction::KillProcess,
+        std::env::consts::ARCH.try_into()
+            .map_err(|e| SandboxError::SeccompError(format!("Invalid arch: {:?}", e)))?,
+    )
+    .map_err(|e| SandboxError::SeccompError(format!("Failed to create seccomp filter: {:?}", e)))?
+    .try_into()
+    .map_err(|e| SandboxError::SeccompError(format!("Failed to build BPF program: {:?}", e)))
+}
+
+/// Allow list shared by every execution; a syscall mapped to no rules is
+/// allowed with any arguments
+fn execution_rules(extra_syscalls: &[i64]) -> BTreeMap<i64, Vec<SeccompRule>> {
     let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
 
     let allowed_syscalls = vec![
@@ -82,10 +216,13 @@ fn execution_filter() -> Result<BpfProgram> {
         nix::libc::SYS_dup3,
     ];
 
-    for syscall in allowed_syscalls {
+    for syscall in allowed_syscalls.into_iter().chain(extra_syscalls.iter().copied()) {
         rules.insert(syscall, vec![]);
     }
+    rules
+}
 
+fn build_filter(rules: BTreeMap<i64, Vec<SeccompRule>>) -> Result<BpfProgram> {
     SeccompFilter::new(
         rules,
         SeccompAction::Errno(nix::libc::EPERM as u32),
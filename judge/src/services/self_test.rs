@@ -0,0 +1,261 @@
+use crate::games::{self, GameResult};
+use crate::models::players::{BotPlayer, Player};
+use crate::services::compiler::Compiler;
+use crate::services::room::GameContext;
+use crate::services::sandbox::ResourceLimits;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use surrealdb::sql::Thing;
+
+/// Protocol lines kept from a self-test game, the last ones being the ones
+/// that explain a failure
+const MAX_TRACE_LINES: usize = 200;
+
+/// A built-in opponent that every submission of its game has to get through
+pub struct ReferenceBot {
+    pub name: &'static str,
+    pub game_id: &'static str,
+    pub source: &'static str, // Python
+}
+
+pub const REFERENCE_BOTS: &[ReferenceBot] = &[
+    ReferenceBot {
+        name: "random",
+        game_id: "rock-paper-scissors",
+        source: include_str!("../../reference_bots/rps_random.py"),
+    },
+    ReferenceBot {
+        name: "always-rock",
+        game_id: "rock-paper-scissors",
+        source: include_str!("../../reference_bots/rps_rock.py"),
+    },
+    ReferenceBot {
+        name: "tit-for-tat",
+        game_id: "prisoners-dilemma",
+        source: include_str!("../../reference_bots/pd_tit_for_tat.py"),
+    },
+    ReferenceBot {
+        name: "random",
+        game_id: "prisoners-dilemma",
+        source: include_str!("../../reference_bots/pd_random.py"),
+    },
+    ReferenceBot {
+        name: "random",
+        game_id: "tic-tac-toe",
+        source: include_str!("../../reference_bots/ttt_random.py"),
+    },
+    ReferenceBot {
+        name: "first-free",
+        game_id: "tic-tac-toe",
+        source: include_str!("../../reference_bots/ttt_first_free.py"),
+    },
+];
+
+/// Outcome of a submission's games against the reference bots, stored on the
+/// submission
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub games: Vec<SelfTestGame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SelfTestGame {
+    pub opponent: String,
+    pub seat: usize, // Seat the submission played in
+    pub verdict: String,
+    pub score: Option<i32>,
+    pub trace: Option<String>, // Protocol trace, only kept for failed games
+}
+
+impl SelfTestReport {
+    /// The first game the submission did not finish cleanly
+    pub fn first_failure(&self) -> Option<&SelfTestGame> {
+        self.games.iter().find(|game| game.verdict != "accepted")
+    }
+}
+
+struct CompiledReference {
+    bot: &'static ReferenceBot,
+    binary_path: String,
+}
+
+/// Plays submissions against the reference bots of their game
+pub struct SelfTest {
+    references: Vec<CompiledReference>,
+}
+
+impl SelfTest {
+    /// Compile every reference bot once. A bot that fails to compile is left
+    /// out with a warning rather than blocking every submission of its game.
+    pub async fn new(compiler: &Compiler) -> Self {
+        let mut references = Vec::with_capacity(REFERENCE_BOTS.len());
+        for bot in REFERENCE_BOTS {
+            let key = format!("reference_{}_{}", bot.game_id, bot.name);
+            match compiler
+                .compile_submission(&key, "python", bot.source)
+                .await
+            {
+                Ok(binary_path) => references.push(CompiledReference { bot, binary_path }),
+                Err(e) => tracing::warn!(
+                    "Reference bot {} for {} failed to compile: {}",
+                    bot.name,
+                    bot.game_id,
+                    e
+                ),
+            }
+        }
+        Self { references }
+    }
+
+    /// Play a compiled submission once against each reference bot of its game,
+    /// alternating seats. None when the game has no reference bots to play.
+    pub async fn run(
+        &self,
+        game_id: &str,
+        submission_key: &str,
+        binary_path: &str,
+    ) -> Result<Option<SelfTestReport>> {
+        let opponents: Vec<&CompiledReference> = self
+            .references
+            .iter()
+            .filter(|reference| reference.bot.game_id == game_id)
+            .collect();
+        if opponents.is_empty() {
+            return Ok(None);
+        }
+
+        let mut games = Vec::with_capacity(opponents.len());
+        for (index, opponent) in opponents.iter().enumerate() {
+            let seat = index % 2;
+            games.push(
+                play_reference_game(game_id, submission_key, binary_path, opponent, seat, index)
+                    .await?,
+            );
+        }
+        let passed = games.iter().all(|game| game.verdict == "accepted");
+        Ok(Some(SelfTestReport { passed, games }))
+    }
+}
+
+async fn play_reference_game(
+    game_id: &str,
+    submission_key: &str,
+    binary_path: &str,
+    opponent: &CompiledReference,
+    seat: usize,
+    index: usize,
+) -> Result<SelfTestGame> {
+    // A fresh instance, games keep their state between runs
+    let game = games::registry()
+        .get(game_id)
+        .with_context(|| format!("Unknown game {}", game_id))?;
+    let metadata = game.metadata();
+    let limits = ResourceLimits::for_game(metadata);
+
+    // Player ids name the sandbox cgroups, so they must not collide with
+    // other self-tests or matches running on this judge
+    let key = format!("{}_{}", submission_key.replace(':', "_"), index);
+    let submission_id = Thing::from(("selftest", key.as_str()));
+    let reference_id = Thing::from(("reference", key.as_str()));
+
+    let mut submission = BotPlayer::new(submission_id, binary_path, limits.clone())
+        .await
+        .context("Failed to start submission for self-test")?;
+    submission.set_timeout(metadata.bot_turn_timeout_ms);
+    let monitor = submission.monitor();
+    let mut reference = BotPlayer::new(reference_id, &opponent.binary_path, limits)
+        .await
+        .context("Failed to start reference bot")?;
+    reference.set_timeout(metadata.bot_turn_timeout_ms);
+
+    let trace = Arc::new(Mutex::new(VecDeque::new()));
+    let submission: Box<dyn Player> = Box::new(TracedPlayer {
+        inner: Box::new(submission),
+        trace: trace.clone(),
+    });
+    let mut players: Vec<Box<dyn Player>> = vec![Box::new(reference)];
+    players.insert(seat, submission);
+
+    let game_context = GameContext::in_memory(Thing::from(("match", key.as_str())));
+    let results = game
+        .run(players, metadata.bot_turn_timeout_ms, game_context)
+        .await;
+    let result = monitor.final_result(results.get(seat));
+
+    let trace = match result {
+        GameResult::Accepted(_) => None,
+        _ => Some(
+            trace
+                .lock()
+                .unwrap()
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+    };
+    Ok(SelfTestGame {
+        opponent: opponent.bot.name.to_string(),
+        seat,
+        verdict: result.verdict().to_string(),
+        score: match result {
+            GameResult::Accepted(score) => Some(score),
+            _ => None,
+        },
+        trace,
+    })
+}
+
+/// Records what the judge and a bot said to each other: "> " for messages to
+/// the bot, "< " for its replies and "! " for failed reads and writes
+struct TracedPlayer {
+    inner: Box<dyn Player>,
+    trace: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl TracedPlayer {
+    fn record(&self, line: String) {
+        let mut trace = self.trace.lock().unwrap();
+        if trace.len() == MAX_TRACE_LINES {
+            trace.pop_front();
+        }
+        trace.push_back(line);
+    }
+}
+
+#[async_trait]
+impl Player for TracedPlayer {
+    async fn send_message(&self, message: &str) -> Result<()> {
+        self.record(format!("> {}", message));
+        let sent = self.inner.send_message(message).await;
+        if let Err(e) = &sent {
+            self.record(format!("! {}", e));
+        }
+        sent
+    }
+
+    async fn receive_message(&self) -> Result<String> {
+        let received = self.inner.receive_message().await;
+        match &received {
+            Ok(message) => self.record(format!("< {}", message)),
+            Err(e) => self.record(format!("! {:#}", e)),
+        }
+        received
+    }
+
+    fn player_id(&self) -> &Thing {
+        self.inner.player_id()
+    }
+
+    async fn is_alive(&self) -> bool {
+        self.inner.is_alive().await
+    }
+
+    fn set_timeout(&mut self, timeout_ms: u64) {
+        self.inner.set_timeout(timeout_ms);
+    }
+}
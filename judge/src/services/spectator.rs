@@ -0,0 +1,226 @@
+use crate::app_state::AppState;
+use crate::models::game_event::{GameEvent, GameEventRecord};
+use crate::services::room::db;
+use axum::{
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    response::Response,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use surrealdb::sql::Thing;
+use tokio::sync::broadcast;
+
+/// Events buffered per match for spectators that fall behind
+const FEED_CAPACITY: usize = 256;
+
+/// Live game events of running matches, fanned out to read-only spectators
+pub struct SpectatorHub {
+    feeds: Mutex<HashMap<Thing, MatchFeed>>,
+    max_per_match: usize,
+}
+
+struct MatchFeed {
+    sender: broadcast::Sender<GameEventRecord>,
+    watchers: Arc<AtomicUsize>,
+}
+
+/// A spectator's place in a match feed, released when dropped. It holds no
+/// sender, so closing the feed ends the stream.
+pub struct Subscription {
+    pub events: broadcast::Receiver<GameEventRecord>,
+    watchers: Arc<AtomicUsize>,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.watchers.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl SpectatorHub {
+    pub fn new(max_per_match: usize) -> Self {
+        Self {
+            feeds: Mutex::new(HashMap::new()),
+            max_per_match,
+        }
+    }
+
+    /// Start a feed for a match that is about to run
+    pub fn open(&self, match_id: &Thing) {
+        let (sender, _) = broadcast::channel(FEED_CAPACITY);
+        let feed = MatchFeed {
+            sender,
+            watchers: Arc::new(AtomicUsize::new(0)),
+        };
+        self.feeds.lock().unwrap().insert(match_id.clone(), feed);
+    }
+
+    /// Send an event to everyone watching its match
+    pub fn publish(&self, record: &GameEventRecord) {
+        if let Some(feed) = self.feeds.lock().unwrap().get(&record.match_id) {
+            // No receivers just means nobody is watching
+            let _ = feed.sender.send(record.clone());
+        }
+    }
+
+    /// End the feed of a finished match; its spectators see the stream close
+    pub fn close(&self, match_id: &Thing) {
+        self.feeds.lock().unwrap().remove(match_id);
+    }
+
+    /// Watch a running match. None when the match is not live on this judge,
+    /// an error when it already has as many spectators as allowed.
+    pub fn subscribe(&self, match_id: &Thing) -> Result<Option<Subscription>, String> {
+        let feeds = self.feeds.lock().unwrap();
+        let Some(feed) = feeds.get(match_id) else {
+            return Ok(None);
+        };
+        let admitted = feed
+            .watchers
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < self.max_per_match).then_some(n + 1)
+            });
+        if admitted.is_err() {
+            return Err("Too many spectators".to_string());
+        }
+        Ok(Some(Subscription {
+            events: feed.sender.subscribe(),
+            watchers: feed.watchers.clone(),
+        }))
+    }
+
+    /// Spectators currently watching a match
+    pub fn watchers(&self, match_id: &Thing) -> usize {
+        self.feeds
+            .lock()
+            .unwrap()
+            .get(match_id)
+            .map_or(0, |feed| feed.watchers.load(Ordering::SeqCst))
+    }
+}
+
+#[derive(Serialize)]
+struct SpectatorEvent<'a> {
+    seq: u64,
+    event: &'a GameEvent,
+}
+
+fn event_message(record: &GameEventRecord) -> String {
+    let payload = SpectatorEvent {
+        seq: record.seq,
+        event: &record.event,
+    };
+    format!(
+        "GAME_EVENT {}",
+        serde_json::to_string(&payload).unwrap_or_default()
+    )
+}
+
+/// Read-only WebSocket for a match, or for the latest match of a room
+pub async fn ws_spectate(ws: WebSocketUpgrade, target: String, state: Arc<AppState>) -> Response {
+    ws.on_upgrade(move |socket| handle_spectator(socket, target, state))
+}
+
+async fn handle_spectator(socket: WebSocket, target: String, state: Arc<AppState>) {
+    let (mut sender, mut receiver) = socket.split();
+
+    let match_id = match resolve_match(&state, &target).await {
+        Ok(match_id) => match_id,
+        Err(e) => {
+            let _ = sender
+                .send(Message::Text(format!("ERROR {}", e).into()))
+                .await;
+            return;
+        }
+    };
+
+    // Subscribe before reading the history so no event falls in between;
+    // anything seen in both is skipped by its sequence number
+    let mut subscription = match state.spectators.subscribe(&match_id) {
+        Ok(subscription) => subscription,
+        Err(e) => {
+            let _ = sender
+                .send(Message::Text(format!("ERROR {}", e).into()))
+                .await;
+            return;
+        }
+    };
+    let history = match db::get_match_events(&state.db, match_id.clone()).await {
+        Ok(history) => history,
+        Err(e) => {
+            let _ = sender
+                .send(Message::Text(format!("ERROR {}", e).into()))
+                .await;
+            return;
+        }
+    };
+
+    let _ = sender
+        .send(Message::Text(format!("SPECTATING {}", match_id).into()))
+        .await;
+    let _ = sender.send(Message::Text("REPLAY_START".into())).await;
+    let mut last_seq = None;
+    for record in &history {
+        if sender
+            .send(Message::Text(event_message(record).into()))
+            .await
+            .is_err()
+        {
+            return;
+        }
+        last_seq = Some(record.seq);
+    }
+    let _ = sender.send(Message::Text("REPLAY_END".into())).await;
+
+    let Some(subscription) = subscription.as_mut() else {
+        // Not running here, the history is all there is
+        let _ = sender.send(Message::Text("MATCH_FINISHED".into())).await;
+        return;
+    };
+
+    loop {
+        tokio::select! {
+            event = subscription.events.recv() => match event {
+                Ok(record) => {
+                    if last_seq.is_some_and(|seq| record.seq <= seq) {
+                        continue;
+                    }
+                    last_seq = Some(record.seq);
+                    if sender.send(Message::Text(event_message(&record).into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    let _ = sender.send(Message::Text("ERROR Spectator fell behind".into())).await;
+                    break;
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    let _ = sender.send(Message::Text("MATCH_FINISHED".into())).await;
+                    break;
+                }
+            },
+            // Spectators cannot act; anything they send is ignored
+            msg = receiver.next() => match msg {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+}
+
+/// The match a spectator asked for: a match id as is, or the room's latest match
+async fn resolve_match(state: &AppState, target: &str) -> Result<Thing, String> {
+    let thing: Thing = target
+        .parse()
+        .map_err(|_| "Invalid match or room id".to_string())?;
+    match thing.tb.as_str() {
+        "match" => Ok(thing),
+        "room" => db::get_latest_match_id(&state.db, &thing.to_string())
+            .await?
+            .ok_or_else(|| "No match has started in this room".to_string()),
+        _ => Err("Invalid match or room id".to_string()),
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use surrealdb::Surreal;
+use surrealdb::engine::remote::ws::Client;
+use surrealdb::sql::Thing;
+use tokio::time::{Duration, sleep};
+
+use crate::services::capacity::CapacityTracker;
+use crate::services::compiler::Compiler;
+use crate::services::self_test::{SelfTest, SelfTestReport};
+
+type Database = Surreal<Client>;
+
+/// Compiler output kept on a failed submission, enough for any real diagnostic
+const MAX_ERROR_MESSAGE_BYTES: usize = 64 * 1024;
+/// How often a claimed submission's compile_started_at is refreshed. The API's
+/// healer hands back submissions not refreshed for 5 minutes.
+const CLAIM_REFRESH_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Deserialize)]
+struct PendingSubmission {
+    id: Thing,
+    game_id: String,
+    language: String,
+    code: String,
+}
+
+/// Watches for new submissions and compiles them as they arrive, moving each
+/// one from pending through compiling to accepted or failed. A compiled
+/// submission is only accepted once it has played the reference bots of its
+/// game without breaking the protocol.
+pub async fn start_submission_watcher(db: Database, capacity: CapacityTracker) -> Result<()> {
+    tracing::info!("Starting submission watcher");
+    let compiler = Compiler::new()?;
+    let self_test = SelfTest::new(&compiler).await;
+
+    loop {
+        let mut response = db
+            .query("SELECT id, game_id, language, code, created_at FROM submission WHERE status = 'pending' ORDER BY created_at LIMIT 10")
+            .await
+            .context("Failed to execute submission query")?;
+        let submissions: Vec<PendingSubmission> = response
+            .take(0)
+            .context("Failed to deserialize submissions")?;
+
+        for submission in submissions {
+            if !capacity.can_accept_work().await {
+                tracing::debug!("At capacity, skipping submission claiming");
+                break;
+            }
+
+            // Claim the submission so only one judge compiles it
+            let mut claim_result_query = db
+                .query("UPDATE $submission_id SET status = 'compiling', compile_started_at = time::now() WHERE status = 'pending' RETURN AFTER")
+                .bind(("submission_id", submission.id.clone()))
+                .await
+                .context("Failed to execute claim query")?;
+            let claimed: Vec<PendingSubmission> = claim_result_query
+                .take(0)
+                .context("Failed to deserialize claim results")?;
+            if claimed.is_empty() {
+                tracing::debug!(
+                    "Submission {} already claimed by another server",
+                    submission.id
+                );
+                continue;
+            }
+
+            tracing::info!("Compiling submission {}", submission.id);
+            // Compiling and self-testing can outlast the healer's threshold
+            let keep_claim = tokio::spawn(refresh_claim(db.clone(), submission.id.clone()));
+            let compiled = compile_submission(&db, &compiler, &self_test, &submission).await;
+            keep_claim.abort();
+            if let Err(e) = compiled {
+                // Left in 'compiling' for the healer to hand back
+                tracing::error!("Submission {} failed: {:#}", submission.id, e);
+            }
+        }
+
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+/// Keep a claimed submission from looking abandoned while this judge works on it
+async fn refresh_claim(db: Database, submission_id: Thing) {
+    loop {
+        sleep(Duration::from_secs(CLAIM_REFRESH_SECS)).await;
+        if let Err(e) = db
+            .query("UPDATE $submission_id SET compile_started_at = time::now() WHERE status = 'compiling'")
+            .bind(("submission_id", submission_id.clone()))
+            .await
+        {
+            tracing::warn!("Failed to refresh the claim on submission {}: {}", submission_id, e);
+        }
+    }
+}
+
+/// Compile a claimed submission, self-test it and record the outcome. A compile
+/// error or a failed self-test is the author's problem and is stored on the
+/// submission; only database errors fail.
+async fn compile_submission(
+    db: &Database,
+    compiler: &Compiler,
+    self_test: &SelfTest,
+    submission: &PendingSubmission,
+) -> Result<()> {
+    let submission_id_str = submission.id.to_string();
+    match compiler
+        .compile_submission(&submission_id_str, &submission.language, &submission.code)
+        .await
+    {
+        Ok(binary_path) => {
+            let report = match self_test
+                .run(&submission.game_id, &submission_id_str, &binary_path)
+                .await
+            {
+                Ok(report) => report,
+                Err(e) => {
+                    // Not the submission's fault, accept it untested rather than hold it back
+                    tracing::warn!("Self-test of submission {} could not run: {:#}", submission_id_str, e);
+                    None
+                }
+            };
+
+            if let Some(report) = report.as_ref().filter(|report| !report.passed) {
+                db.query("UPDATE $submission_id SET status = 'failed', compiled_binary_path = NONE, self_test = $self_test, error_message = $error, compile_started_at = NONE WHERE status = 'compiling'")
+                    .bind(("submission_id", submission.id.clone()))
+                    .bind(("self_test", report.clone()))
+                    .bind(("error", truncate_message(self_test_error(report))))
+                    .await
+                    .context(format!("Failed to reject submission {}", submission_id_str))?;
+                tracing::info!("Submission {} failed its self-test", submission_id_str);
+                return Ok(());
+            }
+
+            db.query("UPDATE $submission_id SET status = 'accepted', compiled_binary_path = $binary_path, self_test = $self_test, error_message = NONE, compile_started_at = NONE WHERE status = 'compiling'")
+                .bind(("submission_id", submission.id.clone()))
+                .bind(("binary_path", binary_path))
+                .bind(("self_test", report))
+                .await
+                .context(format!("Failed to accept submission {}", submission_id_str))?;
+            tracing::info!("Submission {} accepted", submission_id_str);
+        }
+        Err(e) => {
+            db.query("UPDATE $submission_id SET status = 'failed', compiled_binary_path = NONE, error_message = $error, compile_started_at = NONE WHERE status = 'compiling'")
+                .bind(("submission_id", submission.id.clone()))
+                .bind(("error", truncate_message(e.to_string())))
+                .await
+                .context(format!("Failed to reject submission {}", submission_id_str))?;
+            tracing::info!("Submission {} failed to compile", submission_id_str);
+        }
+    }
+    Ok(())
+}
+
+/// Why a submission failed its self-test, with the protocol trace of the first
+/// game it lost that way
+fn self_test_error(report: &SelfTestReport) -> String {
+    let Some(game) = report.first_failure() else {
+        return "Self-test failed".to_string();
+    };
+    format!(
+        "Self-test failed against reference bot {} (seat {}): {}\n\nProtocol trace (> judge to bot, < bot to judge):\n{}",
+        game.opponent,
+        game.seat + 1,
+        game.verdict,
+        game.trace.as_deref().unwrap_or("")
+    )
+}
+
+fn truncate_message(mut message: String) -> String {
+    if message.len() > MAX_ERROR_MESSAGE_BYTES {
+        let mut end = MAX_ERROR_MESSAGE_BYTES;
+        while !message.is_char_boundary(end) {
+            end -= 1;
+        }
+        message.truncate(end);
+        message.push_str("\n[output truncated]");
+    }
+    message
+}
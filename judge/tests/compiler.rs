@@ -22,7 +22,7 @@ mod compiler_tests {
         let sandbox = CompilerSandbox::new(temp_dir.path().to_path_buf()).unwrap();
 
         let result = sandbox
-            .compile("test_sub", "python", "print('hello')")
+            .compile("test_sub", "cobol", "DISPLAY 'hello'.")
             .await;
 
         assert!(result.is_err(), "Should fail with unsupported language");
@@ -157,6 +157,64 @@ mod compiler_tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_check_valid_python_code() {
+        let temp_dir = TempDir::new().unwrap();
+        let sandbox = CompilerSandbox::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let valid_code = "import sys\nfor line in sys.stdin:\n    print('ROCK')\n";
+
+        let result = sandbox.compile("test_valid_python", "python", valid_code).await;
+        assert!(
+            result.is_ok(),
+            "syntax check of valid code should succeed: {:?}", result.err()
+        );
+        let script_path = result.unwrap();
+        assert!(script_path.ends_with("player.py"), "Artifact should be the checked script");
+        assert_eq!(fs::read_to_string(script_path).unwrap(), valid_code);
+    }
+
+    #[tokio::test]
+    async fn test_check_invalid_python_code() {
+        let temp_dir = TempDir::new().unwrap();
+        let sandbox = CompilerSandbox::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let result = sandbox.compile("test_invalid_python", "python", "def main(:\n    pass\n").await;
+        assert!(result.is_err(), "Invalid Python should fail the syntax check");
+        assert!(
+            !temp_dir.path().join("submission_test_invalid_python/player.py").exists(),
+            "No artifact should be stored for invalid code"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_valid_javascript_code() {
+        let temp_dir = TempDir::new().unwrap();
+        let sandbox = CompilerSandbox::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let valid_code = r#"
+            const readline = require("readline");
+            readline.createInterface({ input: process.stdin }).on("line", () => console.log("ROCK"));
+        "#;
+
+        let result = sandbox.compile("test_valid_js", "javascript", valid_code).await;
+        assert!(
+            result.is_ok(),
+            "syntax check of valid code should succeed: {:?}", result.err()
+        );
+        let script_path = result.unwrap();
+        assert!(script_path.ends_with("player.js"), "Artifact should be the checked script");
+    }
+
+    #[tokio::test]
+    async fn test_check_invalid_javascript_code() {
+        let temp_dir = TempDir::new().unwrap();
+        let sandbox = CompilerSandbox::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let result = sandbox.compile("test_invalid_js", "javascript", "function (").await;
+        assert!(result.is_err(), "Invalid JavaScript should fail the syntax check");
+    }
+
     #[tokio::test]
     async fn test_workspace_isolation() {
         let temp_dir = TempDir::new().unwrap();
@@ -2,13 +2,14 @@ mod db;
 
 use std::sync::Arc;
 use judge::services::room::RoomManager;
+use judge::services::spectator::SpectatorHub;
 use judge::games::TicTacToe;
 use judge::games::Game;
 
 #[tokio::test]
 async fn test_room_creation_and_joining() {
     let db = db::setup_test_db().await;
-    let room_manager = Arc::new(RoomManager::new(db));
+    let room_manager = Arc::new(RoomManager::new(db, Arc::new(SpectatorHub::new(10))));
 
     // Create a room
     let room = room_manager.create_room(
@@ -29,9 +30,10 @@ async fn test_room_creation_and_joining() {
     // Since we haven't connected via WebSocket yet, players list will be empty
     assert_eq!(room_response.players.len(), 0);
 
-    let (room_response, is_reconnecting) = room_manager.join_room(&room_id, "user:bob".to_string()).await.unwrap();
-    assert!(!is_reconnecting, "First join should not be reconnecting");
-    // Still no WebSocket connections, so players list remains empty
+    // Joining connects the player's WebSocket, so without one it is refused
+    let join_result = room_manager.join_and_connect(&room_id, "user:bob").await;
+    assert_eq!(join_result, Err("WebSocket not found".to_string()));
+    let room_response = room_manager.get_room(&room_id).await.unwrap();
     assert_eq!(room_response.players.len(), 0);
 }
 
@@ -85,7 +87,7 @@ async fn test_jwt_validation() {
 #[tokio::test]
 async fn test_room_leave_functionality() {
     let db = db::setup_test_db().await;
-    let room_manager = Arc::new(RoomManager::new(db));
+    let room_manager = Arc::new(RoomManager::new(db, Arc::new(SpectatorHub::new(10))));
 
     // Create room and join players
     let room = room_manager.create_room(
@@ -97,8 +99,9 @@ async fn test_room_leave_functionality() {
     ).await.unwrap();
     let room_id = room.id;
 
-    room_manager.join_room(&room_id, "user:bob".to_string()).await.unwrap();
-    room_manager.join_room(&room_id, "user:charlie".to_string()).await.unwrap();
+    // Without WebSockets the players take their seats but are never connected
+    let _ = room_manager.join_and_connect(&room_id, "user:bob").await;
+    let _ = room_manager.join_and_connect(&room_id, "user:charlie").await;
 
     // Bob leaves explicitly
     let leave_result = room_manager.leave_room(&room_id, "user:bob").await;
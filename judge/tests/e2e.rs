@@ -11,6 +11,13 @@ struct SubmissionStatus {
     compiled_binary_path: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct CompiledSubmission {
+    status: String,
+    compiled_binary_path: Option<String>,
+    error_message: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct Participant {
     score: Option<f64>,
@@ -24,6 +31,16 @@ struct MatchStatus {
 }
 
 async fn create_test_submission(db: &Surreal<Client>, tournament_id: &str, code: String) -> Result<Thing> {
+    // Matches only ever reference accepted submissions; the artifact is rebuilt on demand
+    create_test_submission_with_status(db, tournament_id, code, "accepted").await
+}
+
+async fn create_test_submission_with_status(
+    db: &Surreal<Client>,
+    tournament_id: &str,
+    code: String,
+    status: &str,
+) -> Result<Thing> {
     // Add a small delay to avoid race conditions when creating multiple submissions
     tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
 
@@ -58,13 +75,14 @@ async fn create_test_submission(db: &Surreal<Client>, tournament_id: &str, code:
             game_id = 'rock-paper-scissors',
             language = 'rust',
             code = $code,
-            status = 'pending',
+            status = $status,
             created_at = time::now(),
             updated_at = time::now()"
     )
     .bind(("submission_id", submission_thing.clone()))
     .bind(("tournament_id", Thing::from(("tournament", tournament_id))))
     .bind(("code", code))
+    .bind(("status", status.to_string()))
     .await?;
     Ok(submission_thing)
 }
@@ -119,6 +137,76 @@ async fn wait_for_match_completed(db: &Surreal<Client>, match_id: Thing) -> Resu
     anyhow::bail!("Timeout waiting for match completion")
 }
 
+async fn wait_for_submission_compiled(db: &Surreal<Client>, submission_id: Thing) -> Result<CompiledSubmission> {
+    for _ in 0..60 {
+        let mut result = db.query("SELECT status, compiled_binary_path, error_message FROM $submission_id")
+            .bind(("submission_id", submission_id.clone()))
+            .await?;
+        let submissions: Vec<CompiledSubmission> = result.take(0)?;
+        let finished = submissions
+            .into_iter()
+            .find(|submission| submission.status == "accepted" || submission.status == "failed");
+        if let Some(submission) = finished {
+            return Ok(submission);
+        }
+        sleep(Duration::from_secs(1)).await;
+    }
+    anyhow::bail!("Timeout waiting for submission to compile")
+}
+
+#[tokio::test]
+async fn test_e2e_submission_compiled_on_arrival() -> Result<()> {
+    let db = db::setup_test_db().await;
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let tournament_id = format!("test_tournament_compile_{}", timestamp);
+
+    let valid_id = create_test_submission_with_status(
+        &db,
+        &tournament_id,
+        include_str!("bots/rps_rock.rs").to_string(),
+        "pending",
+    )
+    .await?;
+    let invalid_id = create_test_submission_with_status(
+        &db,
+        &tournament_id,
+        "fn main( { this is not valid rust }".to_string(),
+        "pending",
+    )
+    .await?;
+
+    use judge::services::capacity::CapacityTracker;
+    use judge::services::submission_watcher::start_submission_watcher;
+
+    let watcher_handle = tokio::spawn(start_submission_watcher(db.clone(), CapacityTracker::new(10, 100)));
+
+    let valid = wait_for_submission_compiled(&db, valid_id.clone()).await?;
+    assert_eq!(valid.status, "accepted");
+    assert!(valid.compiled_binary_path.is_some());
+    assert!(valid.error_message.is_none());
+
+    // The compile error is attributed to the submission, with the compiler's output
+    let invalid = wait_for_submission_compiled(&db, invalid_id.clone()).await?;
+    assert_eq!(invalid.status, "failed");
+    assert!(invalid.compiled_binary_path.is_none());
+    assert!(invalid.error_message.as_deref().is_some_and(|m| m.contains("error")));
+
+    watcher_handle.abort();
+    for submission_id in [valid_id, invalid_id] {
+        db.query("DELETE $submission_id")
+            .bind(("submission_id", submission_id))
+            .await?;
+    }
+    db.query("DELETE $tournament_id")
+        .bind(("tournament_id", Thing::from(("tournament", tournament_id.as_str()))))
+        .await?;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_e2e_judge_workflow() -> Result<()> {
     // Connect to database
@@ -139,7 +227,7 @@ async fn test_e2e_judge_workflow() -> Result<()> {
     let submission2_id = create_test_submission(&db, &tournament_id, paper_bot_code.to_string()).await?;
     println!("Created submissions: {} and {}", submission1_id, submission2_id);
 
-    // Step 2: Create match request (submissions will be compiled on-demand by judge)
+    // Step 2: Create match request (artifacts will be rebuilt on demand by judge)
     println!("Step 2: Creating match request...");
     let match_id = create_test_match(&db, submission1_id.clone(), submission2_id.clone(), &tournament_id).await?;
     println!("Created match: {}", match_id);
@@ -150,10 +238,11 @@ async fn test_e2e_judge_workflow() -> Result<()> {
     // Import and use judge's match watcher directly
     use judge::games::RockPaperScissors;
     use judge::services::match_watcher::start_match_watcher;
+    use judge::services::spectator::SpectatorHub;
     use judge::services::capacity::CapacityTracker;
 
     let capacity = CapacityTracker::new(10, 100);
-    let game = RockPaperScissors::new();
+    let game = std::sync::Arc::new(RockPaperScissors::new());
 
     // Start match watcher in background using existing db connection
     let judge_db_clone = db.clone();
@@ -163,8 +252,8 @@ async fn test_e2e_judge_workflow() -> Result<()> {
             result = start_match_watcher(
                 judge_db_clone,
                 game,
-                "rock-paper-scissors".to_string(),
                 capacity_clone,
+                std::sync::Arc::new(SpectatorHub::new(10)),
             ) => {
                 if let Err(e) = result {
                     panic!("Match watcher error: {}", e);
@@ -190,7 +279,7 @@ async fn test_e2e_judge_workflow() -> Result<()> {
     let participants = completed_match.participants.as_ref().unwrap();
     assert_eq!(participants.len(), 2);
     assert!(participants.iter().all(|it| it.score.is_some()));
-    // Verify submissions were compiled during match execution
+    // Verify the judge rebuilt the missing artifacts during match execution
     let mut result1 = db.query("SELECT status, compiled_binary_path FROM $submission_id")
         .bind(("submission_id", submission1_id.clone()))
         .await?;
@@ -291,10 +380,11 @@ async fn test_e2e_c_compilation() -> Result<()> {
     println!("Step 3: Starting judge server...");
     use judge::games::RockPaperScissors;
     use judge::services::match_watcher::start_match_watcher;
+    use judge::services::spectator::SpectatorHub;
     use judge::services::capacity::CapacityTracker;
 
     let capacity = CapacityTracker::new(10, 100);
-    let game = RockPaperScissors::new();
+    let game = std::sync::Arc::new(RockPaperScissors::new());
     let judge_db_clone = db.clone();
     let capacity_clone = capacity.clone();
 
@@ -303,8 +393,8 @@ async fn test_e2e_c_compilation() -> Result<()> {
             result = start_match_watcher(
                 judge_db_clone,
                 game,
-                "rock-paper-scissors".to_string(),
                 capacity_clone,
+                std::sync::Arc::new(SpectatorHub::new(10)),
             ) => {
                 if let Err(e) = result {
                     panic!("Match watcher error: {}", e);
@@ -428,10 +518,11 @@ async fn test_e2e_go_compilation() -> Result<()> {
     println!("Step 3: Starting judge server...");
     use judge::games::RockPaperScissors;
     use judge::services::match_watcher::start_match_watcher;
+    use judge::services::spectator::SpectatorHub;
     use judge::services::capacity::CapacityTracker;
 
     let capacity = CapacityTracker::new(10, 100);
-    let game = RockPaperScissors::new();
+    let game = std::sync::Arc::new(RockPaperScissors::new());
     let judge_db_clone = db.clone();
     let capacity_clone = capacity.clone();
 
@@ -440,8 +531,8 @@ async fn test_e2e_go_compilation() -> Result<()> {
             result = start_match_watcher(
                 judge_db_clone,
                 game,
-                "rock-paper-scissors".to_string(),
                 capacity_clone,
+                std::sync::Arc::new(SpectatorHub::new(10)),
             ) => {
                 if let Err(e) = result {
                     panic!("Match watcher error: {}", e);
@@ -499,3 +590,119 @@ async fn test_e2e_go_compilation() -> Result<()> {
 
     Ok(())
 }
+
+/// Three-seat game that scores each seat by its position without talking to the bots
+struct ThreeSeatGame;
+
+static THREE_SEAT_GAME: game_registry::GameMetadata = game_registry::GameMetadata {
+    id: "three-seat-mock",
+    name: "Three Seat Mock",
+    players_per_match: 3,
+    ..game_registry::ROCK_PAPER_SCISSORS
+};
+
+#[async_trait::async_trait]
+impl Game for ThreeSeatGame {
+    fn new() -> Self {
+        Self
+    }
+
+    fn metadata(&self) -> &'static game_registry::GameMetadata {
+        &THREE_SEAT_GAME
+    }
+
+    async fn run(
+        &self,
+        players: Vec<Box<dyn judge::models::players::Player>>,
+        _timeout_ms: u64,
+        _game_context: judge::services::room::GameContext,
+    ) -> Vec<judge::models::game::GameResult> {
+        (0..players.len())
+            .map(|seat| judge::models::game::GameResult::Accepted(seat as i32 + 1))
+            .collect()
+    }
+
+    fn min_players(&self) -> usize {
+        3
+    }
+
+    fn max_players(&self) -> usize {
+        3
+    }
+
+    fn restore_from_events(&self, _events: &[judge::models::game_event::GameEvent]) {}
+
+    fn get_event_source(&self, _player_id: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+#[tokio::test]
+async fn test_e2e_three_seat_match() -> Result<()> {
+    let db = db::setup_test_db().await;
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let tournament_id = format!("test_tournament_three_seat_{}", timestamp);
+
+    let rock_bot_code = include_str!("bots/rps_rock.rs");
+    let mut submission_ids = Vec::new();
+    for _ in 0..3 {
+        submission_ids.push(create_test_submission(&db, &tournament_id, rock_bot_code.to_string()).await?);
+    }
+
+    let match_id = Thing::from(("match", format!("test_match_three_seat_{}", timestamp).as_str()));
+    db.query(
+        "CREATE $match_id SET
+            tournament_id = $tournament_id,
+            game_id = $game_id,
+            status = 'pending',
+            participants = [
+                { submission_id: $sub0 },
+                { submission_id: $sub1 },
+                { submission_id: $sub2 }
+            ],
+            created_at = time::now(),
+            updated_at = time::now()"
+    )
+    .bind(("match_id", match_id.clone()))
+    .bind(("tournament_id", Thing::from(("tournament", tournament_id.as_str()))))
+    .bind(("game_id", THREE_SEAT_GAME.id))
+    .bind(("sub0", submission_ids[0].clone()))
+    .bind(("sub1", submission_ids[1].clone()))
+    .bind(("sub2", submission_ids[2].clone()))
+    .await?;
+
+    use judge::services::match_watcher::start_match_watcher;
+    use judge::services::spectator::SpectatorHub;
+    use judge::services::capacity::CapacityTracker;
+
+    let watcher_handle = tokio::spawn(start_match_watcher(
+        db.clone(),
+        std::sync::Arc::new(ThreeSeatGame::new()),
+        CapacityTracker::new(10, 100),
+        std::sync::Arc::new(SpectatorHub::new(10)),
+    ));
+
+    // Every seat gets its own score, in seat order
+    let completed_match = wait_for_match_completed(&db, match_id.clone()).await?;
+    watcher_handle.abort();
+    let participants = completed_match.participants.as_ref().unwrap();
+    let scores: Vec<Option<f64>> = participants.iter().map(|p| p.score).collect();
+    assert_eq!(scores, vec![Some(1.0), Some(2.0), Some(3.0)]);
+
+    for submission_id in submission_ids {
+        db.query("DELETE $submission_id")
+            .bind(("submission_id", submission_id))
+            .await?;
+    }
+    db.query("DELETE $match_id")
+        .bind(("match_id", match_id))
+        .await?;
+    db.query("DELETE $tournament_id")
+        .bind(("tournament_id", Thing::from(("tournament", tournament_id.as_str()))))
+        .await?;
+    Ok(())
+}
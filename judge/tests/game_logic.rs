@@ -148,3 +148,143 @@ async fn test_player_timeout_simulation() {
     // Should handle timeout gracefully
     assert_eq!(results.len(), 2);
 }
+
+#[test]
+fn test_registry_covers_every_game() {
+    let registry = judge::games::registry();
+    for metadata in game_registry::GAMES {
+        let game = registry
+            .get(metadata.id)
+            .unwrap_or_else(|| panic!("{} is not registered", metadata.id));
+        assert_eq!(game.metadata().id, metadata.id);
+    }
+    assert_eq!(registry.iter().count(), game_registry::GAMES.len());
+    assert!(registry.get("unknown-game").is_none());
+}
+
+#[test]
+fn test_game_result_verdicts() {
+    use judge::games::GameResult;
+    assert_eq!(GameResult::Accepted(3).verdict(), "accepted");
+    assert_eq!(GameResult::MemoryLimitExceeded.verdict(), "memory_limit_exceeded");
+    assert_eq!(GameResult::SeccompViolation.verdict(), "seccomp_violation");
+    assert_eq!(GameResult::OutputLimitExceeded.verdict(), "output_limit_exceeded");
+    assert_eq!(GameResult::Killed(11).verdict(), "killed");
+    assert_eq!(GameResult::Killed(11).signal(), Some(11));
+    assert_eq!(GameResult::RuntimeError.signal(), None);
+}
+
+#[test]
+fn test_game_event_serialization() {
+    use judge::models::game_event::GameEvent;
+    let event = GameEvent::Move { seat: 1, row: 2, col: 0 };
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json, serde_json::json!({"type": "move", "seat": 1, "row": 2, "col": 0}));
+    let parsed: GameEvent = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, event);
+    assert_eq!(serde_json::to_value(GameEvent::Draw).unwrap(), serde_json::json!({"type": "draw"}));
+}
+
+#[test]
+fn test_restore_from_typed_events() {
+    use judge::models::game_event::GameEvent;
+    let game = TicTacToe::new();
+    let players = vec!["user:alice".to_string(), "user:bob".to_string()];
+    game.restore_from_events(&[
+        GameEvent::MatchStart { game_id: "tic-tac-toe".to_string(), players: 2 },
+        GameEvent::GameInit { player_ids: players, rounds: None },
+        GameEvent::Move { seat: 0, row: 1, col: 1 },
+    ]);
+    let bob_view = game.get_event_source("user:bob");
+    assert_eq!(bob_view[0], "START O");
+    assert!(bob_view.contains(&"YOUR_TURN".to_string()));
+
+    let rps = RockPaperScissors::new();
+    rps.restore_from_events(&[
+        GameEvent::GameInit { player_ids: vec!["user:alice".to_string(), "user:bob".to_string()], rounds: Some(3) },
+        GameEvent::RoundResult { round: 1, moves: vec![1, 0] },
+        GameEvent::GameEnd { scores: vec![1, 0] },
+    ]);
+    let alice_view = rps.get_event_source("user:alice");
+    assert_eq!(alice_view.last().map(String::as_str), Some("END"));
+    assert!(alice_view.contains(&"SCORE 1".to_string()));
+}
+
+#[test]
+fn test_replay_renderers() {
+    use game_registry::replay::{ReplayState, TimedEvent, render_replay};
+    use judge::models::game_event::GameEvent;
+    let timed = |events: Vec<GameEvent>| -> Vec<TimedEvent> {
+        events
+            .into_iter()
+            .enumerate()
+            .map(|(i, event)| TimedEvent { event, elapsed_ms: i as u64 * 10 })
+            .collect()
+    };
+    let players = vec!["user:alice".to_string(), "user:bob".to_string()];
+
+    let ttt = TicTacToe::new();
+    let replay = render_replay(ttt.metadata(), &timed(vec![
+        GameEvent::GameInit { player_ids: players.clone(), rounds: None },
+        GameEvent::Move { seat: 0, row: 0, col: 0 },
+        GameEvent::Move { seat: 1, row: 1, col: 1 },
+        GameEvent::Move { seat: 0, row: 0, col: 1 },
+        GameEvent::Move { seat: 1, row: 2, col: 2 },
+        GameEvent::Move { seat: 0, row: 0, col: 2 },
+        GameEvent::Winner { seat: 0 },
+    ]));
+    assert_eq!(replay.game_id, "tic-tac-toe");
+    assert_eq!(replay.players, players);
+    assert_eq!(replay.steps.len(), 6);
+    assert_eq!(replay.steps[2].moves[0].row, Some(1));
+    assert_eq!(replay.steps[2].elapsed_ms, 20);
+    match &replay.steps[5].state {
+        ReplayState::Board { cells, next_seat, winner } => {
+            assert_eq!(&cells[..3], &[Some(0), Some(0), Some(0)]);
+            assert_eq!(*next_seat, None);
+            assert_eq!(*winner, Some(0));
+        }
+        other => panic!("Expected a board, got {:?}", other),
+    }
+
+    let rps = RockPaperScissors::new();
+    let replay = render_replay(rps.metadata(), &timed(vec![
+        GameEvent::GameInit { player_ids: players.clone(), rounds: Some(2) },
+        GameEvent::RoundResult { round: 1, moves: vec![1, 0] },
+        GameEvent::RoundResult { round: 2, moves: vec![2, 2] },
+        GameEvent::GameEnd { scores: vec![1, 0] },
+    ]));
+    assert_eq!(replay.steps.len(), 3);
+    assert_eq!(replay.steps[1].moves[0].action, "paper");
+    assert_eq!(replay.steps[1].moves[1].action, "rock");
+    assert_eq!(replay.steps[2].state, ReplayState::Scores { scores: vec![1, 0] });
+
+    let pd = PrisonersDilemma::new();
+    let replay = render_replay(pd.metadata(), &timed(vec![
+        GameEvent::GameInit { player_ids: players, rounds: Some(2) },
+        GameEvent::RoundResult { round: 1, moves: vec![0, 1] },
+        GameEvent::RoundResult { round: 2, moves: vec![1, 1] },
+    ]));
+    assert_eq!(replay.steps[1].moves[1].action, "defect");
+    assert_eq!(replay.steps[2].state, ReplayState::Scores { scores: vec![1, 6] });
+}
+
+#[tokio::test]
+async fn test_in_memory_game_context_keeps_events() {
+    use judge::models::game_event::GameEvent;
+    let player1 = MockPlayer::new("alice", vec!["MOVE 0 0", "MOVE 0 1", "MOVE 0 2"]);
+    let player2 = MockPlayer::new("bob", vec!["MOVE 1 0", "MOVE 1 1"]);
+    let players: Vec<Box<dyn Player>> = vec![Box::new(player1), Box::new(player2)];
+
+    let game = TicTacToe::new();
+    let game_context = GameContext::in_memory("match:local".parse().unwrap());
+    let results = game.run(players, 5000, game_context.clone()).await;
+    assert_eq!(results, vec![judge::games::GameResult::Accepted(1), judge::games::GameResult::Accepted(0)]);
+
+    let events = game_context.events();
+    let seqs: Vec<u64> = events.iter().map(|r| r.seq).collect();
+    assert_eq!(seqs, (0..events.len() as u64).collect::<Vec<_>>());
+    assert!(matches!(events[0].event, GameEvent::GameInit { .. }));
+    assert_eq!(events.iter().filter(|r| matches!(r.event, GameEvent::Move { .. })).count(), 5);
+    assert_eq!(events.last().map(|r| &r.event), Some(&GameEvent::Winner { seat: 0 }));
+}
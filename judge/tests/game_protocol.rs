@@ -4,7 +4,9 @@ use anyhow::Result;
 use async_trait::async_trait;
 use judge::services::room::GameContext;
 use judge::services::compiler::Compiler;
+use judge::services::self_test::SelfTest;
 use judge::models::players::{BotPlayer, Player};
+use judge::services::sandbox::ResourceLimits;
 use judge::games::{Game, GameResult, RockPaperScissors, TicTacToe, PrisonersDilemma};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -117,8 +119,9 @@ async fn test_automated_rock_paper_scissors() -> Result<()> {
 
     // Create BotPlayers
     println!("Creating bot players...");
-    let bot1 = BotPlayer::new("user:alice".parse().unwrap(), &binary_path1).await?;
-    let bot2 = BotPlayer::new("user:bob".parse().unwrap(), &binary_path2).await?;
+    let limits = ResourceLimits::for_game(&game_registry::ROCK_PAPER_SCISSORS);
+    let bot1 = BotPlayer::new("user:alice".parse().unwrap(), &binary_path1, limits.clone()).await?;
+    let bot2 = BotPlayer::new("user:bob".parse().unwrap(), &binary_path2, limits).await?;
 
     let players: Vec<Box<dyn Player>> = vec![Box::new(bot1), Box::new(bot2)];
 
@@ -168,8 +171,9 @@ async fn test_automated_tic_tac_toe() -> Result<()> {
     let binary_path2: String = compiler.compile_submission(&bot2_id, "rust", &bot2_code).await?;
 
     println!("Creating bot players...");
-    let bot1 = BotPlayer::new("user:alice".parse().unwrap(), &binary_path1).await?;
-    let bot2 = BotPlayer::new("user:bob".parse().unwrap(), &binary_path2).await?;
+    let limits = ResourceLimits::for_game(&game_registry::TIC_TAC_TOE);
+    let bot1 = BotPlayer::new("user:alice".parse().unwrap(), &binary_path1, limits.clone()).await?;
+    let bot2 = BotPlayer::new("user:bob".parse().unwrap(), &binary_path2, limits).await?;
 
     let players: Vec<Box<dyn Player>> = vec![Box::new(bot1), Box::new(bot2)];
 
@@ -214,8 +218,9 @@ async fn test_automated_prisoners_dilemma() -> Result<()> {
     let binary_path2: String = compiler.compile_submission(&bot2_id, "rust", &bot2_code).await?;
 
     println!("Creating bot players...");
-    let bot1 = BotPlayer::new("user:alice".parse().unwrap(), &binary_path1).await?;
-    let bot2 = BotPlayer::new("user:bob".parse().unwrap(), &binary_path2).await?;
+    let limits = ResourceLimits::for_game(&game_registry::PRISONERS_DILEMMA);
+    let bot1 = BotPlayer::new("user:alice".parse().unwrap(), &binary_path1, limits.clone()).await?;
+    let bot2 = BotPlayer::new("user:bob".parse().unwrap(), &binary_path2, limits).await?;
 
     let players: Vec<Box<dyn Player>> = vec![Box::new(bot1), Box::new(bot2)];
 
@@ -399,3 +404,151 @@ async fn test_interactive_prisoners_dilemma() {
     assert!(p1_messages.iter().any(|m| m == "END"), "Player 1 should receive END");
     assert!(p2_messages.iter().any(|m| m == "END"), "Player 2 should receive END");
 }
+
+#[tokio::test]
+async fn test_bot_wall_clock_budget() -> Result<()> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let bot_id = format!("test_silent_bot_{}", timestamp);
+
+    // Never answers, so every turn runs into a timeout
+    let silent_code = "fn main() { loop { std::thread::sleep(std::time::Duration::from_secs(60)); } }";
+    let compiler = Compiler::new()?;
+    let binary_path: String = compiler.compile_submission(&bot_id, "rust", silent_code).await?;
+
+    let mut bot = BotPlayer::new(
+        "user:alice".parse().unwrap(),
+        &binary_path,
+        ResourceLimits::new(1.0, 64, 16, 1500),
+    )
+    .await?;
+    bot.set_timeout(1000);
+
+    // One full turn, then only what is left of the budget, then nothing
+    let started = std::time::Instant::now();
+    assert!(bot.receive_message().await.is_err());
+    assert!(bot.receive_message().await.is_err());
+    let exhausted = bot.receive_message().await.unwrap_err();
+    assert!(exhausted.to_string().contains("wall-clock budget"), "Unexpected error: {}", exhausted);
+    assert!(started.elapsed() < std::time::Duration::from_millis(2500));
+
+    Ok(())
+}
+
+/// Run a bot until its first move fails and return what the sandbox made of it
+async fn sandbox_verdict(name: &str, language: &str, code: &str) -> Result<Option<GameResult>> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let bot_id = format!("test_{}_bot_{}", name, timestamp);
+    let compiler = Compiler::new()?;
+    let binary_path: String = compiler.compile_submission(&bot_id, language, code).await?;
+
+    let mut bot = BotPlayer::new("user:alice".parse().unwrap(), &binary_path, ResourceLimits::execution()).await?;
+    bot.set_timeout(2000);
+    let monitor = bot.monitor();
+    assert!(bot.receive_message().await.is_err(), "{} bot should not produce a move", name);
+    drop(bot);
+    Ok(monitor.verdict())
+}
+
+#[tokio::test]
+async fn test_bot_sandbox_verdicts() -> Result<()> {
+    let panicking = "fn main() { panic!(\"no move\"); }";
+    assert_eq!(sandbox_verdict("panic", "rust", panicking).await?, Some(GameResult::RuntimeError));
+
+    let aborting = "fn main() { std::process::abort(); }";
+    assert_eq!(sandbox_verdict("abort", "rust", aborting).await?, Some(GameResult::Killed(6)));
+
+    let chatty = "fn main() { print!(\"{}\", \"R\".repeat(100_000)); loop { std::thread::sleep(std::time::Duration::from_secs(60)); } }";
+    assert_eq!(sandbox_verdict("chatty", "rust", chatty).await?, Some(GameResult::OutputLimitExceeded));
+
+    let tracing = "#include <sys/ptrace.h>\nint main(void) { ptrace(PTRACE_TRACEME, 0, 0, 0); return 0; }";
+    assert_eq!(sandbox_verdict("ptrace", "c", tracing).await?, Some(GameResult::SeccompViolation));
+
+    // A bot that merely stays silent is the game's call, not the sandbox's
+    let silent = "fn main() { loop { std::thread::sleep(std::time::Duration::from_secs(60)); } }";
+    assert_eq!(sandbox_verdict("silent", "rust", silent).await?, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bot_stderr_is_captured_and_capped() -> Result<()> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let compiler = Compiler::new()?;
+
+    let debug_code = "fn main() { eprintln!(\"thinking about rock\"); println!(\"ROCK\"); }";
+    let binary_path: String = compiler
+        .compile_submission(&format!("test_debug_bot_{}", timestamp), "rust", debug_code)
+        .await?;
+    let bot = BotPlayer::new("user:alice".parse().unwrap(), &binary_path, ResourceLimits::execution()).await?;
+    let monitor = bot.monitor();
+    assert_eq!(bot.receive_message().await?, "ROCK");
+    drop(bot);
+    let log = monitor.stderr_log().await;
+    assert_eq!(log.text, "thinking about rock\n");
+    assert!(!log.truncated);
+
+    // Far more than the cap, and the bot must not block on the full pipe
+    let noisy_code = "fn main() { for _ in 0..10_000 { eprintln!(\"{}\", \"x\".repeat(100)); } println!(\"ROCK\"); }";
+    let binary_path: String = compiler
+        .compile_submission(&format!("test_noisy_bot_{}", timestamp), "rust", noisy_code)
+        .await?;
+    let bot = BotPlayer::new("user:alice".parse().unwrap(), &binary_path, ResourceLimits::execution()).await?;
+    let monitor = bot.monitor();
+    assert_eq!(bot.receive_message().await?, "ROCK");
+    drop(bot);
+    let log = monitor.stderr_log().await;
+    assert!(log.truncated);
+    assert!(log.text.ends_with("[stderr truncated]"));
+    assert!(log.text.len() <= 64 * 1024 + 32);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_submission_self_test_against_reference_bots() -> Result<()> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let compiler = Compiler::new()?;
+    let self_test = SelfTest::new(&compiler).await;
+
+    // A bot that speaks the protocol passes against every reference bot
+    let rock_code = std::fs::read_to_string("tests/bots/rps_rock.rs")?;
+    let rock_key = format!("test_selftest_rock_{}", timestamp);
+    let binary_path: String = compiler.compile_submission(&rock_key, "rust", &rock_code).await?;
+    let report = self_test.run("rock-paper-scissors", &rock_key, &binary_path).await?
+        .expect("rock-paper-scissors has reference bots");
+    assert!(report.passed, "Rock bot should pass: {:?}", report);
+    assert_eq!(report.games.len(), 2);
+    assert_eq!(report.games[0].seat, 0);
+    assert_eq!(report.games[1].seat, 1);
+    assert!(report.games.iter().all(|game| game.trace.is_none()));
+
+    // A malformed move fails with the trace that led up to it
+    let lizard_code = "use std::io::BufRead;\nfn main() { for _ in std::io::stdin().lock().lines() { println!(\"LIZARD\"); } }";
+    let lizard_key = format!("test_selftest_lizard_{}", timestamp);
+    let binary_path: String = compiler.compile_submission(&lizard_key, "rust", lizard_code).await?;
+    let report = self_test.run("rock-paper-scissors", &lizard_key, &binary_path).await?
+        .expect("rock-paper-scissors has reference bots");
+    assert!(!report.passed);
+    let failure = report.first_failure().expect("Lizard bot should fail");
+    assert_eq!(failure.verdict, "wrong_answer");
+    let trace = failure.trace.as_deref().unwrap_or_default();
+    assert!(trace.contains("> START"), "Trace should show the judge's messages: {}", trace);
+    assert!(trace.contains("< LIZARD"), "Trace should show the bad move: {}", trace);
+
+    // Games without reference bots are not self-tested
+    assert!(self_test.run("no-such-game", &lizard_key, &binary_path).await?.is_none());
+
+    Ok(())
+}
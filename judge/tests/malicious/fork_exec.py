@@ -0,0 +1,25 @@
+# Malicious bot attempting to start new processes from the interpreter
+import os
+import subprocess
+
+breached = False
+
+try:
+    pid = os.fork()
+    if pid == 0:
+        os._exit(0)
+    os.waitpid(pid, 0)
+    print("SECURITY BREACH: os.fork() created a child process")
+    breached = True
+except OSError as e:
+    print(f"BLOCKED: Cannot fork: {e}")
+
+try:
+    result = subprocess.run(["/usr/bin/id"], capture_output=True, text=True)
+    print(f"SECURITY BREACH: Ran /usr/bin/id: {result.stdout.strip()}")
+    breached = True
+except OSError as e:
+    print(f"BLOCKED: Cannot spawn a subprocess: {e}")
+
+if not breached:
+    raise SystemExit(1)
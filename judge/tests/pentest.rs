@@ -11,6 +11,7 @@ use tempfile::TempDir;
 
 #[cfg(test)]
 mod cgroup_tests {
+    use judge::services::sandbox::ResourceLimits;
     use judge::services::sandbox::cgroup::CgroupHandle;
     use nix::unistd::Pid;
 
@@ -18,10 +19,25 @@ mod cgroup_tests {
     fn test_cgroup_creation() {
         let result = CgroupHandle::new_compilation("test_submission_123");
         assert!(result.is_ok(), "cgroup creation should be success");
-        let result = CgroupHandle::new_execution("test_player_456");
+        let result = CgroupHandle::new_execution("test_player_456", &ResourceLimits::execution());
         assert!(result.is_ok(), "cgroup creation should be success");
     }
 
+    #[test]
+    fn test_execution_limits_follow_game() {
+        let game = &game_registry::TIC_TAC_TOE;
+        let limits = ResourceLimits::for_game(game);
+        assert_eq!(limits.memory_bytes, game.memory_limit_mb as i64 * 1024 * 1024);
+        assert_eq!(limits.cpu_quota, (game.cpu_limit * limits.cpu_period as f64) as i64);
+        assert_eq!(limits.max_pids, game.max_pids as i64);
+        assert_eq!(limits.wall_clock_ms, game.wall_clock_ms);
+
+        let half_cpu = ResourceLimits::new(0.5, 128, 4, 1000);
+        assert_eq!(half_cpu.cpu_quota, 50_000);
+        assert_eq!(half_cpu.memory_bytes, 128 * 1024 * 1024);
+        assert!(CgroupHandle::new_execution("test_half_cpu", &half_cpu).is_ok());
+    }
+
     #[test]
     fn test_cgroup_unique_names() {
         // Test that creating multiple cgroups with different names works
@@ -37,7 +53,7 @@ mod cgroup_tests {
 
     #[test]
     fn test_cgroup_add_task_to_nonexistent_pid() {
-        if let Ok(handle) = CgroupHandle::new_execution("test_invalid_pid") {
+        if let Ok(handle) = CgroupHandle::new_execution("test_invalid_pid", &ResourceLimits::execution()) {
             // Try to add an invalid PID
             let invalid_pid = Pid::from_raw(999999);
             let result = handle.add_task(invalid_pid);
@@ -45,6 +61,16 @@ mod cgroup_tests {
             assert!(result.is_err(), "Adding invalid PID should fail");
         }
     }
+
+    #[test]
+    fn test_fresh_cgroup_reports_no_usage() {
+        if let Ok(handle) = CgroupHandle::new_execution("test_fresh_usage", &ResourceLimits::execution()) {
+            let usage = handle.usage();
+            assert_eq!(usage.cpu_time_us.unwrap_or(0), 0);
+            assert_eq!(usage.oom_kills.unwrap_or(0), 0);
+            assert_eq!(usage.pid_limit_hits.unwrap_or(0), 0);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -52,6 +78,7 @@ mod sandbox_execution_tests {
     use super::*;
     use judge::services::sandbox::compiler::CompilerSandbox;
     use judge::services::sandbox::executor;
+    use judge::services::sandbox::ResourceLimits;
 
     /// Helper to run a compiled binary and get its output
     fn run_sandboxed_and_capture(
@@ -60,12 +87,13 @@ mod sandbox_execution_tests {
         timeout_secs: u64,
     ) -> Result<(String, i32), String> {
         // Spawn the sandboxed process
-        let process = executor::spawn_sandboxed(player_id, binary_path)
+        let process = executor::spawn_sandboxed(player_id, binary_path, &ResourceLimits::execution())
             .map_err(|e| format!("Failed to spawn: {}", e))?;
 
         // Close stdin (we don't need to send input)
         unsafe {
             nix::libc::close(process.stdin_fd);
+            nix::libc::close(process.stderr_fd);
         }
 
         let stdout_fd = process.stdout_fd;
@@ -282,6 +310,85 @@ mod sandbox_execution_tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_python_fork_is_blocked() {
+        let temp_dir = TempDir::new().unwrap();
+        let sandbox = CompilerSandbox::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let source = fs::read_to_string("tests/malicious/fork_exec.py")
+            .expect("Should be able to read test file");
+
+        let script = sandbox
+            .compile("fork_exec_test", "python", &source)
+            .await
+            .expect("Compilation should succeed");
+
+        match run_sandboxed_and_capture("fork_exec_test", Path::new(&script), 3) {
+            Ok((output, exit_code)) => {
+                println!("=== Output ===\n{}", output);
+                println!("Exit code: {}", exit_code);
+
+                assert!(
+                    output.contains("BLOCKED: Cannot fork"),
+                    "os.fork() should be denied"
+                );
+                assert!(
+                    output.contains("BLOCKED: Cannot spawn a subprocess"),
+                    "subprocess should be denied"
+                );
+                assert!(
+                    !output.contains("SECURITY BREACH"),
+                    "No security breach should occur"
+                );
+            }
+            Err(e) => panic!("Execution error: {}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_hog_is_counted_as_oom_kill() {
+        if !cgroups_rs::fs::hierarchies::is_cgroup2_unified_mode() {
+            println!("Skipping: the OOM kill counter needs cgroup v2");
+            return;
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let sandbox = CompilerSandbox::new(temp_dir.path().to_path_buf()).unwrap();
+
+        // Touch far more memory than the 64MB execution limit
+        let source = r#"
+            #include <stdlib.h>
+            #include <string.h>
+            int main(void) {
+                for (int i = 0; i < 64; i++) {
+                    char *block = malloc(16 * 1024 * 1024);
+                    if (block) memset(block, 1, 16 * 1024 * 1024);
+                }
+                return 0;
+            }
+        "#;
+        let binary = sandbox
+            .compile("memory_hog_test", "c", source)
+            .await
+            .expect("Compilation should succeed");
+
+        let process = executor::spawn_sandboxed("memory_hog_test", Path::new(&binary), &ResourceLimits::execution())
+            .expect("Spawn should succeed");
+        unsafe {
+            nix::libc::close(process.stdin_fd);
+            nix::libc::close(process.stdout_fd);
+            nix::libc::close(process.stderr_fd);
+        }
+        let _ = waitpid(process.pid, None);
+
+        let usage = process.cgroup.usage();
+        println!("Usage: {:?}", usage);
+        assert!(usage.oom_kills.is_some_and(|kills| kills >= 1), "The OOM kill should be recorded");
+        assert!(usage.cpu_time_us.is_some_and(|time| time > 0), "CPU time should be recorded");
+        if let Some(peak) = usage.peak_memory_bytes {
+            assert!(peak <= 64 * 1024 * 1024, "Peak memory should stay within the limit");
+        }
+    }
+
     #[tokio::test]
     async fn test_all_malicious_programs_blocked() {
         let temp_dir = TempDir::new().unwrap();
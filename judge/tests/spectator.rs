@@ -0,0 +1,33 @@
+use judge::models::game_event::{GAME_EVENT_VERSION, GameEvent, GameEventRecord};
+use judge::services::spectator::SpectatorHub;
+use surrealdb::sql::{Datetime, Thing};
+
+#[tokio::test]
+async fn test_spectator_feed_is_capped_and_closes() {
+    let hub = SpectatorHub::new(2);
+    let match_id = Thing::from(("match", "spectated"));
+
+    // Nothing to watch until the match starts on this judge
+    assert!(hub.subscribe(&match_id).unwrap().is_none());
+
+    hub.open(&match_id);
+    let mut first = hub.subscribe(&match_id).unwrap().unwrap();
+    let second = hub.subscribe(&match_id).unwrap().unwrap();
+    assert!(hub.subscribe(&match_id).is_err(), "Third spectator should be turned away");
+    drop(second);
+    assert_eq!(hub.watchers(&match_id), 1);
+    let _third = hub.subscribe(&match_id).unwrap().unwrap();
+
+    hub.publish(&GameEventRecord {
+        match_id: match_id.clone(),
+        seq: 0,
+        version: GAME_EVENT_VERSION,
+        event: GameEvent::Draw,
+        created_at: Datetime::default(),
+    });
+    let record = first.events.recv().await.unwrap();
+    assert_eq!(record.event, GameEvent::Draw);
+
+    hub.close(&match_id);
+    assert!(first.events.recv().await.is_err(), "Closing the match should end the stream");
+}
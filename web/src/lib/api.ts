@@ -1,9 +1,12 @@
 import { env } from "$env/dynamic/public";
-import type { ApiError } from "./models";
+import type { ApiError, AuthResponse } from "./models";
+import { authStore } from "./stores/auth";
 
 const API_URL = env.PUBLIC_API_URL || "http://localhost:8080";
 
 class ApiClient {
+  // Shared by concurrent requests: a refresh token only works once
+  private refreshing: Promise<boolean> | null = null;
   private getHeaders(includeAuth: boolean = false): HeadersInit {
     const headers: HeadersInit = {
       "Content-Type": "application/json",
@@ -27,12 +30,52 @@ class ApiClient {
     if (!text) return {} as T;
     return JSON.parse(text) as T;
   }
+  // Swap the refresh token for a new access token; false once the session is gone
+  async refreshSession(): Promise<boolean> {
+    if (typeof window === "undefined") return false;
+    if (!this.refreshing) {
+      this.refreshing = (async () => {
+        const refreshToken = localStorage.getItem("refresh_token");
+        if (!refreshToken) return false;
+        const response = await fetch(`${API_URL}/api/auth/refresh`, {
+          method: "POST",
+          headers: this.getHeaders(),
+          body: JSON.stringify({ refresh_token: refreshToken }),
+          credentials: "include",
+        }).catch(() => null);
+        if (!response?.ok) {
+          authStore.logout();
+          return false;
+        }
+        const auth: AuthResponse = await response.json();
+        authStore.setAuth(auth.user, auth.token, auth.refresh_token);
+        return true;
+      })().finally(() => {
+        this.refreshing = null;
+      });
+    }
+    return this.refreshing;
+  }
+  // Send a request, refreshing an expired access token once
+  private async send(
+    path: string,
+    init: RequestInit,
+    authenticated: boolean,
+  ): Promise<Response> {
+    const request = () =>
+      fetch(`${API_URL}${path}`, {
+        ...init,
+        headers: this.getHeaders(authenticated),
+        credentials: "include",
+      });
+    const response = await request();
+    if (response.status === 401 && authenticated && (await this.refreshSession())) {
+      return request();
+    }
+    return response;
+  }
   async get<T>(path: string, authenticated: boolean = false): Promise<T> {
-    const response = await fetch(`${API_URL}${path}`, {
-      method: "GET",
-      headers: this.getHeaders(authenticated),
-      credentials: "include",
-    });
+    const response = await this.send(path, { method: "GET" }, authenticated);
     return this.handleResponse<T>(response);
   }
   async post<T, D = any>(
@@ -40,12 +83,11 @@ class ApiClient {
     data?: D,
     authenticated: boolean = false,
   ): Promise<T> {
-    const response = await fetch(`${API_URL}${path}`, {
-      method: "POST",
-      headers: this.getHeaders(authenticated),
-      body: data ? JSON.stringify(data) : undefined,
-      credentials: "include",
-    });
+    const response = await this.send(
+      path,
+      { method: "POST", body: data ? JSON.stringify(data) : undefined },
+      authenticated,
+    );
     return this.handleResponse<T>(response);
   }
   async put<T, D = any>(
@@ -53,12 +95,11 @@ class ApiClient {
     data: D,
     authenticated: boolean = false,
   ): Promise<T> {
-    const response = await fetch(`${API_URL}${path}`, {
-      method: "PUT",
-      headers: this.getHeaders(authenticated),
-      body: JSON.stringify(data),
-      credentials: "include",
-    });
+    const response = await this.send(
+      path,
+      { method: "PUT", body: JSON.stringify(data) },
+      authenticated,
+    );
     return this.handleResponse<T>(response);
   }
   async patch<T, D = any>(
@@ -66,20 +107,15 @@ class ApiClient {
     data: D,
     authenticated: boolean = false,
   ): Promise<T> {
-    const response = await fetch(`${API_URL}${path}`, {
-      method: "PATCH",
-      headers: this.getHeaders(authenticated),
-      body: JSON.stringify(data),
-      credentials: "include",
-    });
+    const response = await this.send(
+      path,
+      { method: "PATCH", body: JSON.stringify(data) },
+      authenticated,
+    );
     return this.handleResponse<T>(response);
   }
   async delete<T>(path: string, authenticated: boolean = false): Promise<T> {
-    const response = await fetch(`${API_URL}${path}`, {
-      method: "DELETE",
-      headers: this.getHeaders(authenticated),
-      credentials: "include",
-    });
+    const response = await this.send(path, { method: "DELETE" }, authenticated);
     return this.handleResponse<T>(response);
   }
 }
@@ -1,6 +1,6 @@
 // User types
 export type UserRole = "admin" | "player";
-export type OAuthProvider = "google";
+export type OAuthProvider = "google" | "github" | "oidc";
 
 export interface User {
   id: string;
@@ -10,16 +10,83 @@ export interface User {
   location: string;
   oauth_provider?: OAuthProvider;
   is_banned: boolean;
+  email_verified: boolean; // Unverified users cannot join tournaments or submit
   ban_reason?: string;
   created_at: string;
   updated_at: string;
 }
 
 export interface AuthResponse {
-  token: string;
+  token: string; // Short-lived access token
+  refresh_token: string;
+  expires_in: number; // Seconds until the access token expires
   user: User;
 }
 
+// Returned by login instead of a session when the user has two-factor on
+export interface TwoFactorChallenge {
+  two_factor_required: true;
+  two_factor_token: string;
+  expires_in: number;
+}
+
+export type LoginResponse = AuthResponse | TwoFactorChallenge;
+
+export interface TwoFactorStatus {
+  enabled: boolean;
+  required: boolean; // Admins cannot turn it off on this instance
+  recovery_codes_remaining: number;
+}
+
+export interface TwoFactorSetup {
+  secret: string;
+  otpauth_uri: string;
+  qr_code_svg: string;
+}
+
+// A sign-in provider enabled on the server
+export interface OAuthProviderInfo {
+  id: OAuthProvider;
+  name: string;
+}
+
+// An external account linked to the user
+export interface UserIdentity {
+  id: string;
+  provider: OAuthProvider;
+  email?: string;
+  created_at: string;
+  last_login_at?: string;
+}
+
+// Personal API tokens for scripts
+export type ApiTokenScope =
+  | "profile:read"
+  | "submissions:read"
+  | "submissions:write"
+  | "tournaments:write"
+  | "matches:read";
+
+export interface ApiToken {
+  id: string;
+  name: string;
+  scopes: ApiTokenScope[];
+  expires_at?: string;
+  last_used_at?: string;
+  created_at: string;
+}
+
+export interface CreateApiTokenRequest {
+  name: string;
+  scopes: ApiTokenScope[];
+  expires_in_days?: number;
+}
+
+export interface CreatedApiToken {
+  token: string; // Only shown once
+  api_token: ApiToken;
+}
+
 export interface RegisterRequest {
   email: string;
   username: string;
@@ -45,7 +112,9 @@ export type MatchGenerationType =
   | "all_vs_all"
   | "round_robin"
   | "single_elimination"
-  | "double_elimination";
+  | "double_elimination"
+  | "swiss"
+  | "free_for_all";
 
 export interface Tournament {
   id: string;
@@ -55,13 +124,24 @@ export interface Tournament {
   status: TournamentStatus;
   min_players: number;
   max_players: number;
+  registration_time?: string;
   start_time?: string;
   end_time?: string;
   match_generation_type: MatchGenerationType;
+  swiss_rounds?: number;
+  current_round: number;
+  resource_limits?: ResourceLimitOverrides;
   created_at: string;
   updated_at: string;
 }
 
+export interface ResourceLimitOverrides {
+  cpu_limit?: number;
+  memory_limit_mb?: number;
+  max_pids?: number;
+  wall_clock_ms?: number;
+}
+
 export interface TournamentParticipant {
   id: string;
   tournament_id: string;
@@ -69,6 +149,7 @@ export interface TournamentParticipant {
   submission_id?: string;
   score: number;
   rank?: number;
+  byes: number;
   joined_at: string;
   username?: string;
 }
@@ -79,21 +160,24 @@ export interface CreateTournamentRequest {
   description: string;
   min_players: number;
   max_players: number;
+  registration_time?: string;
   start_time?: string;
   end_time?: string;
   match_generation_type?: MatchGenerationType;
+  swiss_rounds?: number;
 }
 
 export interface UpdateTournamentRequest {
   name?: string;
   description?: string;
   status?: TournamentStatus;
+  registration_time?: string;
   start_time?: string;
   end_time?: string;
 }
 
 // Game types
-export type ProgrammingLanguage = "rust" | "go" | "c";
+export type ProgrammingLanguage = "rust" | "go" | "c" | "python" | "javascript";
 
 export interface Game {
   id: string;
@@ -108,6 +192,9 @@ export interface Game {
   bot_turn_timeout_ms: number;
   human_turn_timeout_ms: number;
   memory_limit_mb: number;
+  max_pids: number;
+  wall_clock_ms: number;
+  players_per_match: number;
 }
 
 // Game Template types
@@ -127,7 +214,7 @@ export interface CreateGameTemplateRequest {
 }
 
 // Submission types
-export type SubmissionStatus = "pending" | "accepted" | "failed";
+export type SubmissionStatus = "pending" | "compiling" | "accepted" | "failed";
 
 export interface Submission {
   id: string;
@@ -138,9 +225,24 @@ export interface Submission {
   code: string;
   status: SubmissionStatus;
   error_message?: string;
+  self_test?: SelfTestReport;
   created_at: string;
 }
 
+// Games a compiled submission played against the judge's reference bots
+export interface SelfTestReport {
+  passed: boolean;
+  games: SelfTestGame[];
+}
+
+export interface SelfTestGame {
+  opponent: string;
+  seat: number;
+  verdict: string;
+  score?: number;
+  trace?: string;
+}
+
 export interface CreateSubmissionRequest {
   tournament_id: string;
   language: ProgrammingLanguage;
@@ -152,6 +254,8 @@ export interface SubmissionResponse {
   tournament_id: string;
   language: ProgrammingLanguage;
   status: SubmissionStatus;
+  error_message?: string;
+  self_test?: SelfTestReport;
   created_at: string;
 }
 
@@ -164,10 +268,22 @@ export type MatchStatus =
   | "failed"
   | "cancelled";
 
+export type Verdict =
+  | "accepted"
+  | "time_limit_exceeded"
+  | "wrong_answer"
+  | "runtime_error"
+  | "memory_limit_exceeded"
+  | "killed"
+  | "seccomp_violation"
+  | "output_limit_exceeded";
+
 export interface MatchParticipant {
   user_id?: string;
   submission_id?: string;
   score?: number;
+  verdict?: Verdict;
+  signal?: number; // Set when the verdict is "killed"
   metadata?: Record<string, any>;
 }
 
@@ -177,7 +293,7 @@ export interface Match {
   game_id: string;
   status: MatchStatus;
   participants: MatchParticipant[];
-  metadata?: Record<string, any> | null;
+  metadata?: (Record<string, any> & { resource_usage?: ResourceUsage[] }) | null;
   room_id?: string;
   created_at: string;
   updated_at: string;
@@ -185,6 +301,52 @@ export interface Match {
   completed_at?: string;
 }
 
+// What each bot used in an automated match, in participant order
+export interface ResourceUsage {
+  submission_id: string;
+  peak_memory_bytes?: number;
+  cpu_time_us?: number;
+  oom_kills?: number;
+  pid_limit_hits?: number;
+}
+
+export interface MatchLog {
+  submission_id: string;
+  user_id: string;
+  stderr: string;
+  truncated: boolean;
+  created_at: string;
+}
+
+export interface ReplayMove {
+  seat: number;
+  action: string;
+  row?: number;
+  col?: number;
+}
+
+export type ReplayState =
+  | {
+      kind: "board";
+      cells: (number | null)[];
+      next_seat: number | null;
+      winner: number | null;
+    }
+  | { kind: "scores"; scores: number[] };
+
+export interface ReplayStep {
+  turn: number;
+  elapsed_ms: number;
+  moves: ReplayMove[];
+  state: ReplayState;
+}
+
+export interface MatchReplay {
+  game_id: string;
+  players: string[];
+  steps: ReplayStep[];
+}
+
 export interface CreateMatchRequest {
   tournament_id: string;
   game_id: string;
@@ -211,6 +373,26 @@ export interface LeaderboardEntry {
   tournament_id: string;
 }
 
+export interface RatingLeaderboardEntry {
+  rank: number;
+  user_id: string;
+  username: string;
+  location: string;
+  game_id: string;
+  rating: number;
+  deviation: number;
+  matches_played: number;
+}
+
+export interface RatingHistoryEntry {
+  game_id: string;
+  match_id: string;
+  rating: number;
+  deviation: number;
+  volatility: number;
+  created_at: string;
+}
+
 // Room types
 export type RoomStatus = "waiting" | "playing" | "finished";
 
@@ -0,0 +1,22 @@
+import { api } from "../api";
+import type {
+  ApiToken,
+  CreateApiTokenRequest,
+  CreatedApiToken,
+} from "../models";
+
+export const apiTokenService = {
+  async list(): Promise<ApiToken[]> {
+    return api.get<ApiToken[]>("/api/users/tokens", true);
+  },
+  async create(data: CreateApiTokenRequest): Promise<CreatedApiToken> {
+    return api.post<CreatedApiToken, CreateApiTokenRequest>(
+      "/api/users/tokens",
+      data,
+      true,
+    );
+  },
+  async delete(id: string): Promise<void> {
+    return api.delete<void>(`/api/users/tokens/${id}`, true);
+  },
+};
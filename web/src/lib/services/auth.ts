@@ -2,6 +2,8 @@ import { api } from "../api";
 import type {
   AuthResponse,
   LoginRequest,
+  LoginResponse,
+  OAuthProviderInfo,
   RegisterRequest,
   User,
 } from "../models";
@@ -10,8 +12,33 @@ export const authService = {
   async register(data: RegisterRequest): Promise<AuthResponse> {
     return api.post<AuthResponse, RegisterRequest>("/api/auth/register", data);
   },
-  async login(data: LoginRequest): Promise<AuthResponse> {
-    return api.post<AuthResponse, LoginRequest>("/api/auth/login", data);
+  async login(data: LoginRequest): Promise<LoginResponse> {
+    return api.post<LoginResponse, LoginRequest>("/api/auth/login", data);
+  },
+  // Finishes a login that asked for a second factor
+  async loginTwoFactor(
+    two_factor_token: string,
+    code: string,
+  ): Promise<AuthResponse> {
+    return api.post<AuthResponse>("/api/auth/login/2fa", {
+      two_factor_token,
+      code,
+    });
+  },
+  // Ends the current session on the server
+  async logout(): Promise<{ message: string }> {
+    return api.post("/api/auth/logout", undefined, true);
+  },
+  // Ends every session of the user, on every device
+  async logoutAll(): Promise<{ message: string; revoked: number }> {
+    return api.post("/api/auth/logout-all", undefined, true);
+  },
+  async verifyEmail(token: string): Promise<User> {
+    return api.post<User>("/api/auth/verify-email", { token });
+  },
+  // Sends a new verification link, rate limited by the server
+  async resendVerification(): Promise<{ message: string }> {
+    return api.post("/api/auth/resend-verification", undefined, true);
   },
   async resetPassword(email: string): Promise<{ message: string }> {
     return api.post("/api/auth/reset-password", { email });
@@ -22,16 +49,9 @@ export const authService = {
   ): Promise<{ message: string }> {
     return api.post("/api/auth/confirm-reset", { token, new_password });
   },
-  async getGoogleAuthUrl(): Promise<{ url: string }> {
-    return api.get("/api/auth/google");
-  },
-  async handleGoogleCallback(
-    code: string,
-    state: string,
-  ): Promise<AuthResponse> {
-    return api.get<AuthResponse>(
-      `/api/auth/google/callback?code=${code}&state=${state}`,
-    );
+  // Providers to offer on the login page
+  async getProviders(): Promise<OAuthProviderInfo[]> {
+    return api.get<OAuthProviderInfo[]>("/api/auth/providers");
   },
   async getProfile(): Promise<User> {
     return api.get<User>("/api/users/profile", true);
@@ -0,0 +1,19 @@
+import { api } from "../api";
+import type { OAuthProvider, UserIdentity } from "../models";
+
+export const identityService = {
+  async list(): Promise<UserIdentity[]> {
+    return api.get<UserIdentity[]>("/api/users/identities", true);
+  },
+  // Returns the provider URL to send the browser to
+  async link(provider: OAuthProvider): Promise<{ url: string }> {
+    return api.post<{ url: string }>(
+      `/api/auth/${provider}/link`,
+      undefined,
+      true,
+    );
+  },
+  async unlink(id: string): Promise<void> {
+    return api.delete<void>(`/api/users/identities/${id}`, true);
+  },
+};
@@ -1,5 +1,9 @@
 import { api } from "../api";
-import type { LeaderboardEntry } from "../models";
+import type {
+  LeaderboardEntry,
+  RatingHistoryEntry,
+  RatingLeaderboardEntry,
+} from "../models";
 
 export const leaderboardService = {
   async get(filters?: {
@@ -15,4 +19,25 @@ export const leaderboardService = {
     const query = params.toString() ? `?${params.toString()}` : "";
     return api.get<LeaderboardEntry[]>(`/api/leaderboard${query}`);
   },
+
+  async getRatings(
+    gameId: string,
+    limit?: number,
+  ): Promise<RatingLeaderboardEntry[]> {
+    const params = new URLSearchParams({ mode: "rating", game_id: gameId });
+    if (limit) params.append("limit", limit.toString());
+    return api.get<RatingLeaderboardEntry[]>(
+      `/api/leaderboard?${params.toString()}`,
+    );
+  },
+
+  async getRatingHistory(
+    userId: string,
+    gameId?: string,
+  ): Promise<RatingHistoryEntry[]> {
+    const query = gameId ? `?game_id=${encodeURIComponent(gameId)}` : "";
+    return api.get<RatingHistoryEntry[]>(
+      `/api/users/${encodeURIComponent(userId)}/ratings${query}`,
+    );
+  },
 };
@@ -1,6 +1,8 @@
 import { api } from "../api";
 import type {
   Match,
+  MatchLog,
+  MatchReplay,
   CreateMatchRequest,
   UpdateMatchResultRequest,
 } from "../models";
@@ -22,6 +24,13 @@ export const matchService = {
   async get(id: string): Promise<Match> {
     return api.get<Match>(`/api/matches/${id}`, true);
   },
+  // Stderr of the caller's own bots, or of every bot for admins
+  async logs(id: string): Promise<MatchLog[]> {
+    return api.get<MatchLog[]>(`/api/matches/${id}/logs`, true);
+  },
+  async replay(id: string): Promise<MatchReplay> {
+    return api.get<MatchReplay>(`/api/matches/${id}/replay`, true);
+  },
   async create(data: CreateMatchRequest): Promise<Match> {
     return api.post<Match, CreateMatchRequest>("/api/matches", data, true);
   },
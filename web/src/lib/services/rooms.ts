@@ -1,4 +1,5 @@
 import { env } from '$env/dynamic/public';
+import { api } from '../api';
 import type { Room, CreateRoomRequest, RoomMessage, CreateRoomMessageRequest, RoomStatus } from '$lib/models';
 
 const JUDGE_URL = env.PUBLIC_JUDGE_URL || "http://localhost:8081";
@@ -27,28 +28,32 @@ class JudgeApiClient {
     return JSON.parse(text) as T;
   }
 
+  // Access tokens are short-lived, refresh an expired one once and retry
+  private async send(path: string, init: RequestInit): Promise<Response> {
+    const request = () =>
+      fetch(`${JUDGE_URL}${path}`, { ...init, headers: this.getHeaders(true) });
+    const response = await request();
+    if (response.status === 401 && (await api.refreshSession())) {
+      return request();
+    }
+    return response;
+  }
+
   async get<T>(path: string): Promise<T> {
-    const response = await fetch(`${JUDGE_URL}${path}`, {
-      method: "GET",
-      headers: this.getHeaders(true),
-    });
+    const response = await this.send(path, { method: "GET" });
     return this.handleResponse<T>(response);
   }
 
   async post<T, D = any>(path: string, data?: D): Promise<T> {
-    const response = await fetch(`${JUDGE_URL}${path}`, {
+    const response = await this.send(path, {
       method: "POST",
-      headers: this.getHeaders(true),
       body: data ? JSON.stringify(data) : undefined,
     });
     return this.handleResponse<T>(response);
   }
 
   async delete<T>(path: string): Promise<T> {
-    const response = await fetch(`${JUDGE_URL}${path}`, {
-      method: "DELETE",
-      headers: this.getHeaders(true),
-    });
+    const response = await this.send(path, { method: "DELETE" });
     return this.handleResponse<T>(response);
   }
 }
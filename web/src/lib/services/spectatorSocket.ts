@@ -0,0 +1,79 @@
+import { env } from '$env/dynamic/public';
+
+const JUDGE_URL = env.PUBLIC_JUDGE_URL || "ws://localhost:8081";
+
+export interface SpectatorEvent {
+  seq: number;
+  event: { type: string; [key: string]: unknown };
+}
+
+// Read-only view of a running match, or of a room's latest match
+export class SpectatorSocket {
+  private ws: WebSocket | null = null;
+  private targetId: string;
+  private eventHandlers: Map<string, (data: any) => void> = new Map();
+
+  constructor(targetId: string) {
+    this.targetId = targetId;
+  }
+
+  async connect(): Promise<void> {
+    return new Promise((resolve, reject) => {
+      const wsUrl = JUDGE_URL.replace('http://', 'ws://').replace('https://', 'wss://');
+      this.ws = new WebSocket(`${wsUrl}/ws/spectate/${this.targetId}`);
+
+      this.ws.onopen = () => resolve();
+      this.ws.onerror = (error) => reject(error);
+      this.ws.onclose = () => this.emit('disconnect', '');
+      this.ws.onmessage = (event) => this.handleMessage(event.data);
+    });
+  }
+
+  on(event: string, handler: (data: any) => void): void {
+    this.eventHandlers.set(event, handler);
+  }
+
+  disconnect(): void {
+    if (this.ws) {
+      this.ws.close();
+      this.ws = null;
+    }
+  }
+
+  private handleMessage(data: string): void {
+    const space = data.indexOf(' ');
+    const command = space === -1 ? data : data.slice(0, space);
+    const rest = space === -1 ? '' : data.slice(space + 1);
+
+    switch (command) {
+      case 'SPECTATING':
+        this.emit('spectating', rest);
+        break;
+      case 'REPLAY_START':
+        this.emit('replay_start', '');
+        break;
+      case 'REPLAY_END':
+        this.emit('replay_end', '');
+        break;
+      case 'GAME_EVENT':
+        this.emit('game_event', JSON.parse(rest) as SpectatorEvent);
+        break;
+      case 'MATCH_FINISHED':
+        this.emit('match_finished', '');
+        break;
+      case 'ERROR':
+        this.emit('error', rest);
+        break;
+      default:
+        this.emit('message', data);
+        break;
+    }
+  }
+
+  private emit(event: string, data: any): void {
+    const handler = this.eventHandlers.get(event);
+    if (handler) {
+      handler(data);
+    }
+  }
+}
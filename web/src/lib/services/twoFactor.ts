@@ -0,0 +1,23 @@
+import { api } from "../api";
+import type { TwoFactorSetup, TwoFactorStatus } from "../models";
+
+export const twoFactorService = {
+  async status(): Promise<TwoFactorStatus> {
+    return api.get<TwoFactorStatus>("/api/users/2fa", true);
+  },
+  // Starts a new authenticator, pending until enable confirms a code
+  async setup(): Promise<TwoFactorSetup> {
+    return api.post<TwoFactorSetup>("/api/users/2fa/setup", undefined, true);
+  },
+  async enable(code: string): Promise<{ recovery_codes: string[] }> {
+    return api.post("/api/users/2fa/enable", { code }, true);
+  },
+  async disable(code: string): Promise<void> {
+    return api.post<void>("/api/users/2fa/disable", { code }, true);
+  },
+  async regenerateRecoveryCodes(
+    code: string,
+  ): Promise<{ recovery_codes: string[] }> {
+    return api.post("/api/users/2fa/recovery-codes", { code }, true);
+  },
+};
@@ -18,10 +18,13 @@ function createAuthStore() {
   const { subscribe, set, update } = writable<AuthState>(initialState);
   return {
     subscribe,
-    setAuth: (user: User, token: string) => {
+    setAuth: (user: User, token: string, refreshToken?: string) => {
       if (typeof window !== "undefined") {
         localStorage.setItem("auth_token", token);
         localStorage.setItem("auth_user", JSON.stringify(user));
+        if (refreshToken) {
+          localStorage.setItem("refresh_token", refreshToken);
+        }
       }
       set({ user, token, isAuthenticated: true, loading: false });
     },
@@ -29,6 +32,7 @@ function createAuthStore() {
       if (typeof window !== "undefined") {
         localStorage.removeItem("auth_token");
         localStorage.removeItem("auth_user");
+        localStorage.removeItem("refresh_token");
       }
       set({ user: null, token: null, isAuthenticated: false, loading: false });
     },